@@ -8,12 +8,16 @@ pub struct DatabaseInspectorImpl {
 
 impl DatabaseInspector for DatabaseInspectorImpl {
     fn introspect(&self, schema: &String) -> DatabaseSchema {
+        let search_indexes = self.get_search_indexes(schema);
+
         DatabaseSchema {
             tables: self
                 .get_table_names(schema)
                 .into_iter()
+                .filter(|t| !search_indexes.iter().any(|i| is_backing_table(i, t)))
                 .map(|t| self.get_table(schema, &t))
                 .collect(),
+            search_indexes,
         }
     }
 }
@@ -50,19 +54,115 @@ impl DatabaseInspectorImpl {
         result
     }
 
+    /// The FTS5 tables, found by the statement they were created with.
+    fn get_search_indexes(&self, schema: &String) -> Vec<SearchIndex> {
+        let sql = format!(
+            "
+            SELECT
+                name, sql
+            FROM
+                {}.sqlite_master
+            WHERE
+                type='table' AND sql LIKE 'CREATE VIRTUAL TABLE%USING fts5%'
+        ",
+            schema
+        );
+
+        let mut stmt = self.connection.prepare_cached(&sql).unwrap();
+        let mut rows = stmt.query(NO_PARAMS).unwrap();
+        let mut result = Vec::new();
+
+        while let Some(row) = rows.next() {
+            let row = row.unwrap();
+            let name: String = row.get("name");
+            let sql: String = row.get("sql");
+
+            result.push(SearchIndex {
+                table: content_table(&sql).unwrap_or_else(|| name.clone()),
+                columns: self.get_columns(schema, &name).into_iter().map(|c| c.name).collect(),
+                name,
+            });
+        }
+
+        result
+    }
+
     fn get_table(&self, schema: &String, table: &String) -> Table {
         let introspected_columns = self.get_columns(&schema, &table);
         let introspected_foreign_keys = self.get_foreign_constraints(&schema, &table);
-        // let _index = get_index(&schema, &table);
         // let _seq = get_sequence(&schema, &table);
 
         Table {
             name: table.to_string(),
             columns: convert_introspected_columns(introspected_columns, introspected_foreign_keys),
-            indexes: Vec::new(),
+            indexes: self.get_indexes(&schema, &table),
         }
     }
 
+    /// The indexes created with `CREATE INDEX`. The ones SQLite creates for
+    /// `PRIMARY KEY` and `UNIQUE` constraints are left out.
+    fn get_indexes(&self, schema: &String, table: &String) -> Vec<Index> {
+        let sql = format!(r#"Pragma "{}".index_list("{}");"#, schema, table);
+        let mut stmt = self.connection.prepare_cached(&sql).unwrap();
+        let mut rows = stmt.query(NO_PARAMS).unwrap();
+        let mut result = Vec::new();
+
+        while let Some(row_result) = rows.next() {
+            let row = row_result.unwrap();
+            let origin: String = row.get("origin");
+
+            if origin != "c" {
+                continue;
+            }
+
+            let name: String = row.get("name");
+            let is_partial: bool = row.get("partial");
+
+            result.push(Index {
+                columns: self.get_index_columns(schema, &name),
+                unique: row.get("unique"),
+                condition: match is_partial {
+                    true => self.get_index_condition(schema, &name),
+                    false => None,
+                },
+                name,
+            });
+        }
+
+        result
+    }
+
+    fn get_index_columns(&self, schema: &String, index: &String) -> Vec<String> {
+        let sql = format!(r#"Pragma "{}".index_info("{}");"#, schema, index);
+        let mut stmt = self.connection.prepare_cached(&sql).unwrap();
+        let mut rows = stmt.query(NO_PARAMS).unwrap();
+        let mut result: Vec<(i64, String)> = Vec::new();
+
+        while let Some(row_result) = rows.next() {
+            let row = row_result.unwrap();
+            result.push((row.get("seqno"), row.get("name")));
+        }
+
+        result.sort_by_key(|(seqno, _)| *seqno);
+        result.into_iter().map(|(_, name)| name).collect()
+    }
+
+    /// The `WHERE` clause of a partial index, from the statement it was
+    /// created with.
+    fn get_index_condition(&self, schema: &String, index: &String) -> Option<String> {
+        let sql = format!(
+            r#"SELECT sql FROM "{}".sqlite_master WHERE type='index' AND name = ?"#,
+            schema
+        );
+        let mut stmt = self.connection.prepare_cached(&sql).unwrap();
+        let mut rows = stmt.query(&[index]).unwrap();
+
+        let sql: String = rows.next()?.unwrap().get("sql");
+        let start = sql.find(" WHERE ")? + " WHERE ".len();
+
+        Some(sql[start..].trim().to_string())
+    }
+
     fn get_columns(&self, schema: &String, table: &String) -> Vec<IntrospectedColumn> {
         let sql = format!(r#"Pragma "{}".table_info ("{}")"#, schema, table);
         let mut stmt = self.connection.prepare_cached(&sql).unwrap();
@@ -107,13 +207,27 @@ impl DatabaseInspectorImpl {
         unimplemented!()
     }
 
-    fn get_index(&self, _schema: &String, _table: &String) -> Index {
-        unimplemented!()
-    }
-
     // fn query<F>(&self, schema: &String, parse: F) ->
 }
 
+/// The virtual table of the index and the shadow tables FTS5 keeps its data in.
+fn is_backing_table(index: &SearchIndex, table: &str) -> bool {
+    const SHADOW_TABLES: &[&str] = &["data", "idx", "content", "docsize", "config"];
+
+    table == index.name
+        || SHADOW_TABLES
+            .iter()
+            .any(|suffix| table == format!("{}_{}", index.name, suffix))
+}
+
+/// The table given as `content='..'` option of an FTS5 table.
+fn content_table(sql: &str) -> Option<String> {
+    let start = sql.find("content='")? + "content='".len();
+    let end = sql[start..].find('\'')?;
+
+    Some(sql[start..start + end].to_string())
+}
+
 fn convert_introspected_columns(
     columns: Vec<IntrospectedColumn>,
     foreign_keys: Vec<IntrospectedForeignKey>,
@@ -142,11 +256,14 @@ fn convert_introspected_columns(
 fn column_type(column: &IntrospectedColumn) -> ColumnType {
     match column.tpe.as_ref() {
         "INTEGER" => ColumnType::Int,
+        "BIGINT" => ColumnType::BigInt,
         "REAL" => ColumnType::Float,
+        "NUMERIC" => ColumnType::Decimal,
         "BOOLEAN" => ColumnType::Boolean,
         "TEXT" => ColumnType::String,
         s if s.starts_with("VARCHAR") => ColumnType::String,
         "DATE" => ColumnType::DateTime,
+        "BLOB" => ColumnType::Bytes,
         x => panic!(format!(
             "type {} is not supported here yet. Column was: {}",
             x, column.name
@@ -11,6 +11,8 @@ pub trait DatabaseInspector {
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct DatabaseSchema {
     pub tables: Vec<Table>,
+    /// The full-text indexes, the tables backing them are not in `tables`.
+    pub search_indexes: Vec<SearchIndex>,
 }
 
 impl DatabaseSchema {
@@ -21,6 +23,14 @@ impl DatabaseSchema {
     pub fn has_table(&self, name: &str) -> bool {
         self.table(name).is_some()
     }
+
+    pub fn search_index(&self, name: &str) -> Option<&SearchIndex> {
+        self.search_indexes.iter().find(|i| i.name == name)
+    }
+
+    pub fn has_search_index(&self, name: &str) -> bool {
+        self.search_index(name).is_some()
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -74,10 +84,13 @@ impl Column {
 #[derive(Debug, Copy, PartialEq, Eq, Clone)]
 pub enum ColumnType {
     Int,
+    BigInt,
     Float,
+    Decimal,
     Boolean,
     String,
     DateTime,
+    Bytes,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -92,9 +105,19 @@ pub struct Sequence {
     pub current: u32,
 }
 
+/// A full-text index over columns of a table
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SearchIndex {
+    pub name: String,
+    pub table: String,
+    pub columns: Vec<String>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Index {
     pub name: String,
     pub columns: Vec<String>,
     pub unique: bool,
+    /// Only rows matching this condition are indexed.
+    pub condition: Option<String>,
 }
@@ -13,11 +13,14 @@ fn all_columns_types_must_work() {
     let inspector = setup(|mut migration| {
         migration.create_table("User", |t| {
             t.add_column("int", types::integer());
+            t.add_column("big_int", types::custom("BIGINT"));
             t.add_column("float", types::float());
+            t.add_column("decimal", types::custom("NUMERIC"));
             t.add_column("boolean", types::boolean());
             t.add_column("string1", types::text());
             t.add_column("string2", types::varchar(1));
             t.add_column("date_time", types::date());
+            t.add_column("bytes", types::binary());
         });
     });
 
@@ -32,6 +35,13 @@ fn all_columns_types_must_work() {
             foreign_key: None,
             sequence: None,
         },
+        Column {
+            name: "big_int".to_string(),
+            tpe: ColumnType::BigInt,
+            is_required: true,
+            foreign_key: None,
+            sequence: None,
+        },
         Column {
             name: "float".to_string(),
             tpe: ColumnType::Float,
@@ -39,6 +49,13 @@ fn all_columns_types_must_work() {
             foreign_key: None,
             sequence: None,
         },
+        Column {
+            name: "decimal".to_string(),
+            tpe: ColumnType::Decimal,
+            is_required: true,
+            foreign_key: None,
+            sequence: None,
+        },
         Column {
             name: "boolean".to_string(),
             tpe: ColumnType::Boolean,
@@ -67,6 +84,13 @@ fn all_columns_types_must_work() {
             foreign_key: None,
             sequence: None,
         },
+        Column {
+            name: "bytes".to_string(),
+            tpe: ColumnType::Bytes,
+            is_required: true,
+            foreign_key: None,
+            sequence: None,
+        },
     ];
 
     assert_eq!(table.columns, expected_columns);
@@ -130,6 +154,73 @@ fn foreign_keys_must_work() {
     assert_eq!(user_table.columns, expected_columns);
 }
 
+#[test]
+fn search_indexes_must_work() {
+    let inspector = setup(|mut migration| {
+        migration.create_table("Item", |t| {
+            t.add_column("name", types::text());
+        });
+        migration.inject_custom(format!(
+            r#"CREATE VIRTUAL TABLE "{}"."Item_fts" USING fts5("name", content='Item', content_rowid='rowid')"#,
+            SCHEMA
+        ));
+    });
+
+    let result = inspector.introspect(&SCHEMA.to_string());
+
+    let expected_indexes = vec![SearchIndex {
+        name: "Item_fts".to_string(),
+        table: "Item".to_string(),
+        columns: vec!["name".to_string()],
+    }];
+    assert_eq!(result.search_indexes, expected_indexes);
+
+    let table_names: Vec<&str> = result.tables.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(table_names, vec!["Item"]);
+}
+
+#[test]
+fn indexes_must_work() {
+    let inspector = setup(|mut migration| {
+        migration.create_table("User", |t| {
+            t.add_column("id", types::primary());
+            t.add_column("email", types::text().unique(true));
+            t.add_column("name", types::text());
+            t.add_column("age", types::integer());
+            t.add_column("deletedAt", types::date().nullable(true));
+        });
+        migration.inject_custom(format!(
+            r#"CREATE UNIQUE INDEX "{}"."User.name" ON "User"("name") WHERE "deletedAt" IS NULL"#,
+            SCHEMA
+        ));
+        migration.inject_custom(format!(
+            r#"CREATE INDEX "{}"."User.age_name" ON "User"("age", "name")"#,
+            SCHEMA
+        ));
+    });
+
+    let result = inspector.introspect(&SCHEMA.to_string());
+
+    let mut indexes = result.table("User").unwrap().indexes.clone();
+    indexes.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let expected_indexes = vec![
+        Index {
+            name: "User.age_name".to_string(),
+            columns: vec!["age".to_string(), "name".to_string()],
+            unique: false,
+            condition: None,
+        },
+        Index {
+            name: "User.name".to_string(),
+            columns: vec!["name".to_string()],
+            unique: true,
+            condition: Some(r#""deletedAt" IS NULL"#.to_string()),
+        },
+    ];
+    assert_eq!(indexes, expected_indexes);
+}
+
 fn setup<F>(mut migrationFn: F) -> Box<DatabaseInspector>
 where
     F: FnMut(&mut Migration) -> (),
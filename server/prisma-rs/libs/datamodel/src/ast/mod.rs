@@ -53,6 +53,7 @@ pub enum Value {
     BooleanValue(String, Span),
     StringValue(String, Span),
     ConstantValue(String, Span),
+    ArrayValue(Vec<String>, Span),
 }
 
 #[derive(Debug)]
@@ -91,10 +91,23 @@ fn parse_literal(token: &pest::iterators::Pair<'_, Rule>) -> Value {
     };
 }
 
+// Arrays only hold identifiers, like [firstName, lastName]
+fn parse_array_literal(token: &pest::iterators::Pair<'_, Rule>) -> Value {
+    let mut values: Vec<String> = vec![];
+
+    match_children! { token, current,
+        Rule::identifier => values.push(current.as_str().to_string()),
+        _ => unreachable!("Encounterd impossible array value during parsing: {:?}", current.tokens())
+    };
+
+    return Value::ArrayValue(values, Span::from_pest(&token.as_span()));
+}
+
 // Directive parsing
 fn parse_directive_arg_value(token: &pest::iterators::Pair<'_, Rule>) -> Value {
     return match_first! { token, current,
         Rule::any_literal => parse_literal(&current),
+        Rule::array_literal => parse_array_literal(&current),
         _ => unreachable!("Encounterd impossible value during parsing: {:?}", current.tokens())
     };
 }
@@ -140,6 +153,7 @@ fn parse_directive_args(token: &pest::iterators::Pair<'_, Rule>, arguments: &mut
     }
 }
 
+// Directives in `@` and `@@` form are parsed the same.
 fn parse_directive(token: &pest::iterators::Pair<'_, Rule>) -> Directive {
     let mut name: Option<String> = None;
     let mut arguments: Vec<DirectiveArgument> = vec![];
@@ -229,6 +243,7 @@ fn parse_model(token: &pest::iterators::Pair<'_, Rule>) -> Model {
     match_children! { token, current,
         Rule::identifier => name = Some(current.as_str().to_string()),
         Rule::directive => directives.push(parse_directive(&current)),
+        Rule::block_directive => directives.push(parse_directive(&current)),
         Rule::field_declaration => fields.push(parse_field(&current)),
         _ => unreachable!("Encounterd impossible model declaration during parsing: {:?}", current.tokens())
     }
@@ -41,6 +41,8 @@ pub struct Field {
     pub database_name: Option<String>,
     pub default_value: Option<Value>,
     pub is_unique: bool,
+    pub is_searchable: bool,
+    pub is_version: bool,
     pub id_info: Option<IdInfo>,
     pub scalar_list_strategy: Option<ScalarListStrategy>,
     pub comments: Vec<Comment>,
@@ -73,6 +75,8 @@ impl Field {
             database_name: None,
             default_value: None,
             is_unique: false,
+            is_searchable: false,
+            is_version: false,
             id_info: None,
             scalar_list_strategy: None,
             comments: vec![],
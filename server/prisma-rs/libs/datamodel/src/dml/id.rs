@@ -9,6 +9,7 @@ use std::str::FromStr;
 pub enum IdStrategy {
     Auto,
     None,
+    Sequence,
 }
 
 impl FromStr for IdStrategy {
@@ -18,6 +19,7 @@ impl FromStr for IdStrategy {
         match s {
             "AUTO" => Ok(IdStrategy::Auto),
             "NONE" => Ok(IdStrategy::None),
+            "SEQUENCE" => Ok(IdStrategy::Sequence),
             _ => Err(ValueParserError::new(
                 &format!("Invalid id strategy {}.", s),
                 s,
@@ -31,6 +33,7 @@ impl FromStr for IdStrategy {
 pub enum ScalarListStrategy {
     Embedded,
     Relation,
+    Native,
 }
 
 impl FromStr for ScalarListStrategy {
@@ -40,6 +43,7 @@ impl FromStr for ScalarListStrategy {
         match s {
             "EMBEDDED" => Ok(ScalarListStrategy::Embedded),
             "RELATION" => Ok(ScalarListStrategy::Relation),
+            "NATIVE" => Ok(ScalarListStrategy::Native),
             _ => Err(ValueParserError::new(
                 &format!("Invalid scalar list strategy {}.", s),
                 s,
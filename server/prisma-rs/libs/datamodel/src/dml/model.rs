@@ -3,6 +3,9 @@ use super::field::*;
 use super::traits::*;
 use serde::{Deserialize, Serialize};
 
+/// The field a model with soft deletes keeps the deletion time in.
+pub const DELETED_AT_FIELD: &str = "deletedAt";
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Model {
     pub name: String,
@@ -10,6 +13,11 @@ pub struct Model {
     pub comments: Vec<Comment>,
     pub database_name: Option<String>,
     pub is_embedded: bool,
+    pub is_soft_delete: bool,
+    /// The fields of an `@@id` spanning more than one field.
+    pub id_fields: Vec<String>,
+    /// The fields of each `@@unique`.
+    pub unique_fields: Vec<Vec<String>>,
 }
 
 impl Model {
@@ -20,6 +28,9 @@ impl Model {
             comments: vec![],
             database_name: None,
             is_embedded: false,
+            is_soft_delete: false,
+            id_fields: vec![],
+            unique_fields: vec![],
         }
     }
 
@@ -38,6 +49,14 @@ impl Model {
     pub fn find_field(&self, name: &str) -> Option<&Field> {
         self.fields().find(|f| f.name == *name)
     }
+
+    /// The field with the deletion time, for models with soft deletes.
+    pub fn deleted_at_field(&self) -> Option<&Field> {
+        match self.is_soft_delete {
+            true => self.find_field(DELETED_AT_FIELD),
+            false => None,
+        }
+    }
 }
 
 impl WithName for Model {
@@ -26,6 +26,7 @@ impl RelationInfo {
 #[derive(Debug, Copy, PartialEq, Clone, Serialize, Deserialize)]
 pub enum OnDeleteStrategy {
     Cascade,
+    Restrict,
     None,
 }
 
@@ -35,6 +36,7 @@ impl FromStr for OnDeleteStrategy {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "CASCADE" => Ok(OnDeleteStrategy::Cascade),
+            "RESTRICT" => Ok(OnDeleteStrategy::Restrict),
             "NONE" => Ok(OnDeleteStrategy::None),
             _ => Err(ValueParserError::new(&format!("Invalid onDelete strategy {}.", s), s, &ast::Span::empty())),
         }
@@ -4,11 +4,13 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Copy, PartialEq, Clone, Serialize, Deserialize)]
 pub enum ScalarType {
     Int,
+    BigInt,
     Float,
     Decimal,
     Boolean,
     String,
     DateTime,
+    Bytes,
     Enum,
 }
 
@@ -16,6 +18,7 @@ pub enum ScalarType {
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum Value {
     Int(i32),
+    BigInt(i64),
     Float(f32),
     Decimal(f32),
     Boolean(bool),
@@ -0,0 +1,83 @@
+use crate::dml;
+use crate::dml::validator::directive::{Args, DirectiveValidator, Error};
+
+pub struct CompoundIdDirectiveValidator {}
+
+impl DirectiveValidator<dml::Model> for CompoundIdDirectiveValidator {
+    fn directive_name(&self) -> &'static str {
+        &"id"
+    }
+    fn validate_and_apply(&self, args: &Args, obj: &mut dml::Model) -> Option<Error> {
+        // Records are still identified by their id field internally.
+        if !obj.fields().any(|f| f.id_info.is_some()) {
+            return self.error("A model with @@id needs a @primary field as well.", args.span());
+        }
+
+        match field_names(self, args, obj) {
+            Ok(names) => {
+                obj.id_fields = names;
+                None
+            }
+            Err(err) => err,
+        }
+    }
+}
+
+pub struct CompoundUniqueDirectiveValidator {}
+
+impl DirectiveValidator<dml::Model> for CompoundUniqueDirectiveValidator {
+    fn directive_name(&self) -> &'static str {
+        &"unique"
+    }
+    fn validate_and_apply(&self, args: &Args, obj: &mut dml::Model) -> Option<Error> {
+        match field_names(self, args, obj) {
+            Ok(names) => {
+                obj.unique_fields.push(names);
+                None
+            }
+            Err(err) => err,
+        }
+    }
+}
+
+/// The field names given as `fields` or the unnamed argument. They must name
+/// at least two distinct scalar fields of the model.
+fn field_names<T>(validator: &T, args: &Args, obj: &dml::Model) -> Result<Vec<String>, Option<Error>>
+where
+    T: DirectiveValidator<dml::Model>,
+{
+    let names = match args.default_arg("fields").as_array() {
+        Ok(names) => names,
+        Err(err) => return Err(validator.parser_error(&err)),
+    };
+
+    if names.len() < 2 {
+        return Err(validator.error("Compound directives need at least two fields.", args.span()));
+    }
+
+    for (i, name) in names.iter().enumerate() {
+        if names[..i].contains(name) {
+            return Err(validator.error(&format!("Field {} is given more than once.", name), args.span()));
+        }
+
+        match obj.find_field(name) {
+            Some(field) if is_scalar(field) => (),
+            _ => {
+                return Err(validator.error(
+                    &format!("{} is not a scalar field of the model.", name),
+                    args.span(),
+                ))
+            }
+        }
+    }
+
+    Ok(names)
+}
+
+fn is_scalar(field: &dml::Field) -> bool {
+    match (&field.field_type, &field.arity) {
+        (dml::FieldType::Relation(_), _) => false,
+        (_, dml::FieldArity::List) => false,
+        _ => true,
+    }
+}
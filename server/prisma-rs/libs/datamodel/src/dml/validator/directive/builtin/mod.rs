@@ -5,6 +5,7 @@ use crate::dml::validator::directive::{DirectiveValidator, DirectiveValidationEr
 
 use std::collections::HashMap;
 
+mod compound;
 mod db;
 mod default;
 mod embedded;
@@ -12,8 +13,11 @@ mod ondelete;
 mod primary;
 mod relation;
 mod scalarlist;
+mod search;
 mod sequence;
+mod softdelete;
 mod unique;
+mod version;
 
 // TODO: This should not be in the builtin mod.
 pub struct DirectiveListValidator<T> {
@@ -65,9 +69,11 @@ pub fn new_builtin_field_directives() -> DirectiveListValidator<dml::Field> {
     validator.add(Box::new(scalarlist::ScalarListDirectiveValidator {}));
     validator.add(Box::new(sequence::SequenceDirectiveValidator {}));
     validator.add(Box::new(unique::UniqueDirectiveValidator {}));
+    validator.add(Box::new(search::SearchDirectiveValidator {}));
     validator.add(Box::new(default::DefaultDirectiveValidator {}));
     validator.add(Box::new(relation::RelationDirectiveValidator {}));
     validator.add(Box::new(ondelete::OnDeleteDirectiveValidator {}));
+    validator.add(Box::new(version::VersionDirectiveValidator {}));
 
     return validator;
 }
@@ -79,6 +85,9 @@ pub fn new_builtin_model_directives() -> DirectiveListValidator<dml::Model> {
 
     validator.add(Box::new(db::DbDirectiveValidator {}));
     validator.add(Box::new(embedded::EmbeddedDirectiveValidator {}));
+    validator.add(Box::new(softdelete::SoftDeleteDirectiveValidator {}));
+    validator.add(Box::new(compound::CompoundIdDirectiveValidator {}));
+    validator.add(Box::new(compound::CompoundUniqueDirectiveValidator {}));
 
     return validator;
 }
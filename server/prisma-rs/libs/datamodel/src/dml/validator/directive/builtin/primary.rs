@@ -8,10 +8,11 @@ impl DirectiveValidator<dml::Field> for PrimaryDirectiveValidator {
         &"primary"
     }
     fn validate_and_apply(&self, args: &Args, obj: &mut dml::Field) -> Option<Error> {
-        let mut id_info = dml::IdInfo {
+        // A `@sequence` directive may have been applied already.
+        let mut id_info = obj.id_info.take().unwrap_or(dml::IdInfo {
             strategy: dml::IdStrategy::Auto,
             sequence: None,
-        };
+        });
 
         if let Ok(strategy) = args.arg("name").as_constant_literal() {
             match strategy.parse::<dml::IdStrategy>() {
@@ -0,0 +1,19 @@
+use crate::dml;
+use crate::dml::validator::directive::{Args, DirectiveValidator, Error};
+
+pub struct SearchDirectiveValidator {}
+
+impl DirectiveValidator<dml::Field> for SearchDirectiveValidator {
+    fn directive_name(&self) -> &'static str {
+        &"search"
+    }
+    fn validate_and_apply(&self, args: &Args, obj: &mut dml::Field) -> Option<Error> {
+        match (&obj.field_type, &obj.arity) {
+            (dml::FieldType::Base(dml::ScalarType::String), arity) if arity != &dml::FieldArity::List => {
+                obj.is_searchable = true;
+                None
+            }
+            _ => self.error("Full-text search is only supported on String fields.", args.span()),
+        }
+    }
+}
@@ -27,11 +27,19 @@ impl DirectiveValidator<dml::Field> for SequenceDirectiveValidator {
             Err(err) => return self.parser_error(&err),
         }
 
-        match args.arg("initialValie").as_int() {
+        match args.arg("initialValue").as_int() {
             Ok(initial_value) => seq.initial_value = initial_value,
             Err(err) => return self.parser_error(&err),
         }
 
+        let id_info = obj.id_info.get_or_insert(dml::IdInfo {
+            strategy: dml::IdStrategy::Sequence,
+            sequence: None,
+        });
+
+        id_info.strategy = dml::IdStrategy::Sequence;
+        id_info.sequence = Some(seq);
+
         return None;
     }
 }
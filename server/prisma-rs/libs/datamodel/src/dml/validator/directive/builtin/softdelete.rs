@@ -0,0 +1,24 @@
+use crate::dml;
+use crate::dml::validator::directive::{Args, DirectiveValidator, Error};
+
+pub struct SoftDeleteDirectiveValidator {}
+
+impl DirectiveValidator<dml::Model> for SoftDeleteDirectiveValidator {
+    fn directive_name(&self) -> &'static str {
+        &"softDelete"
+    }
+    fn validate_and_apply(&self, args: &Args, obj: &mut dml::Model) -> Option<Error> {
+        let deleted_at = obj.find_field(dml::DELETED_AT_FIELD).map(|f| (&f.field_type, &f.arity));
+
+        match deleted_at {
+            Some((dml::FieldType::Base(dml::ScalarType::DateTime), dml::FieldArity::Optional)) => {
+                obj.is_soft_delete = true;
+                None
+            }
+            _ => self.error(
+                "Soft-deleted models need an optional DateTime field called deletedAt.",
+                args.span(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use crate::dml;
+use crate::dml::validator::directive::{Args, DirectiveValidator, Error};
+
+pub struct VersionDirectiveValidator {}
+
+impl DirectiveValidator<dml::Field> for VersionDirectiveValidator {
+    fn directive_name(&self) -> &'static str {
+        &"version"
+    }
+    fn validate_and_apply(&self, args: &Args, obj: &mut dml::Field) -> Option<Error> {
+        match (&obj.field_type, &obj.arity) {
+            (dml::FieldType::Base(dml::ScalarType::Int), dml::FieldArity::Required) => {
+                obj.is_version = true;
+                None
+            }
+            _ => self.error("The version field must be a required Int field.", args.span()),
+        }
+    }
+}
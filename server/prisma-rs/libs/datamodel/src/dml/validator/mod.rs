@@ -112,11 +112,13 @@ impl Validator {
         match type_name {
             "ID" => Ok(dml::FieldType::Base(dml::ScalarType::Int)),
             "Int" => Ok(dml::FieldType::Base(dml::ScalarType::Int)),
+            "BigInt" => Ok(dml::FieldType::Base(dml::ScalarType::BigInt)),
             "Float" => Ok(dml::FieldType::Base(dml::ScalarType::Float)),
             "Decimal" => Ok(dml::FieldType::Base(dml::ScalarType::Decimal)),
             "Boolean" => Ok(dml::FieldType::Base(dml::ScalarType::Boolean)),
             "String" => Ok(dml::FieldType::Base(dml::ScalarType::String)),
             "DateTime" => Ok(dml::FieldType::Base(dml::ScalarType::DateTime)),
+            "Bytes" => Ok(dml::FieldType::Base(dml::ScalarType::Bytes)),
             // Distinguish between relation and enum.
             _ => {
                 for model in &ast_schema.models {
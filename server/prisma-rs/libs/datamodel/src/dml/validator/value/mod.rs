@@ -65,27 +65,35 @@ macro_rules! wrap_value (
 pub trait ValueValidator {
     fn is_valid(&self) -> bool;
 
-    fn raw(&self) -> &String;
+    fn raw(&self) -> String;
     fn span(&self) -> &ast::Span;
     fn as_str(&self) -> Result<String, ValueParserError>;
     fn as_int(&self) -> Result<i32, ValueParserError>;
+    fn as_big_int(&self) -> Result<i64, ValueParserError>;
     fn as_float(&self) -> Result<f32, ValueParserError>;
     fn as_decimal(&self) -> Result<f32, ValueParserError>;
     fn as_bool(&self) -> Result<bool, ValueParserError>;
     fn as_date_time(&self) -> Result<DateTime<Utc>, ValueParserError>;
     fn as_constant_literal(&self) -> Result<String, ValueParserError>;
+    fn as_array(&self) -> Result<Vec<String>, ValueParserError>;
 
     fn as_type(&self, scalar_type: &dml::ScalarType) -> Result<dml::Value, ValueParserError> {
         match scalar_type {
-            dml::ScalarType::Int => wrap_value!(self.as_int(), dml::Value::Int, self.raw(), self.span()),
-            dml::ScalarType::Float => wrap_value!(self.as_float(), dml::Value::Float, self.raw(), self.span()),
-            dml::ScalarType::Decimal => wrap_value!(self.as_decimal(), dml::Value::Decimal, self.raw(), self.span()),
-            dml::ScalarType::Boolean => wrap_value!(self.as_bool(), dml::Value::Boolean, self.raw(), self.span()),
+            dml::ScalarType::Int => wrap_value!(self.as_int(), dml::Value::Int, &self.raw(), self.span()),
+            dml::ScalarType::BigInt => wrap_value!(self.as_big_int(), dml::Value::BigInt, &self.raw(), self.span()),
+            dml::ScalarType::Float => wrap_value!(self.as_float(), dml::Value::Float, &self.raw(), self.span()),
+            dml::ScalarType::Decimal => wrap_value!(self.as_decimal(), dml::Value::Decimal, &self.raw(), self.span()),
+            dml::ScalarType::Boolean => wrap_value!(self.as_bool(), dml::Value::Boolean, &self.raw(), self.span()),
             dml::ScalarType::DateTime => {
-                wrap_value!(self.as_date_time(), dml::Value::DateTime, self.raw(), self.span())
+                wrap_value!(self.as_date_time(), dml::Value::DateTime, &self.raw(), self.span())
             }
-            dml::ScalarType::Enum => wrap_value!(self.as_str(), dml::Value::ConstantLiteral, self.raw(), self.span()),
-            dml::ScalarType::String => wrap_value!(self.as_str(), dml::Value::String, self.raw(), self.span()),
+            dml::ScalarType::Bytes => Err(ValueParserError::new(
+                "Bytes fields can not have a default value",
+                &self.raw(),
+                self.span(),
+            )),
+            dml::ScalarType::Enum => wrap_value!(self.as_str(), dml::Value::ConstantLiteral, &self.raw(), self.span()),
+            dml::ScalarType::String => wrap_value!(self.as_str(), dml::Value::String, &self.raw(), self.span()),
         }
     }
 }
@@ -101,12 +109,13 @@ impl ValueValidator for WrappedValue {
         true
     }
 
-    fn raw(&self) -> &String {
+    fn raw(&self) -> String {
         match &self.value {
-            ast::Value::StringValue(x, _) => x,
-            ast::Value::NumericValue(x, _) => x,
-            ast::Value::BooleanValue(x, _) => x,
-            ast::Value::ConstantValue(x, _) => x,
+            ast::Value::StringValue(x, _) => x.clone(),
+            ast::Value::NumericValue(x, _) => x.clone(),
+            ast::Value::BooleanValue(x, _) => x.clone(),
+            ast::Value::ConstantValue(x, _) => x.clone(),
+            ast::Value::ArrayValue(x, _) => format!("[{}]", x.join(", ")),
         }
     }
 
@@ -116,6 +125,7 @@ impl ValueValidator for WrappedValue {
             ast::Value::NumericValue(_, s) => s,
             ast::Value::BooleanValue(_, s) => s,
             ast::Value::ConstantValue(_, s) => s,
+            ast::Value::ArrayValue(_, s) => s,
         }
     }
 
@@ -124,7 +134,7 @@ impl ValueValidator for WrappedValue {
             ast::Value::StringValue(value, _) => Ok(value.to_string()),
             _ => Err(ValueParserError::new(
                 &format!("Expected String Value, received {:?}", self.value),
-                self.raw(),
+                &self.raw(),
                 self.span(),
             )),
         }
@@ -135,7 +145,18 @@ impl ValueValidator for WrappedValue {
             ast::Value::NumericValue(value, span) => ValueParserError::wrap(value.parse::<i32>(), value, span),
             _ => Err(ValueParserError::new(
                 &format!("Expected Numeric Value, received {:?}", self.value),
-                self.raw(),
+                &self.raw(),
+                self.span(),
+            )),
+        }
+    }
+
+    fn as_big_int(&self) -> Result<i64, ValueParserError> {
+        match &self.value {
+            ast::Value::NumericValue(value, span) => ValueParserError::wrap(value.parse::<i64>(), value, span),
+            _ => Err(ValueParserError::new(
+                &format!("Expected Numeric Value, received {:?}", self.value),
+                &self.raw(),
                 self.span(),
             )),
         }
@@ -146,7 +167,7 @@ impl ValueValidator for WrappedValue {
             ast::Value::NumericValue(value, span) => ValueParserError::wrap(value.parse::<f32>(), value, span),
             _ => Err(ValueParserError::new(
                 &format!("Expected Numeric Value, received {:?}", self.value),
-                self.raw(),
+                &self.raw(),
                 self.span(),
             )),
         }
@@ -158,7 +179,7 @@ impl ValueValidator for WrappedValue {
             ast::Value::NumericValue(value, span) => ValueParserError::wrap(value.parse::<f32>(), value, span),
             _ => Err(ValueParserError::new(
                 &format!("Expected Numeric Value, received {:?}", self.value),
-                self.raw(),
+                &self.raw(),
                 self.span(),
             )),
         }
@@ -169,7 +190,7 @@ impl ValueValidator for WrappedValue {
             ast::Value::BooleanValue(value, span) => ValueParserError::wrap(value.parse::<bool>(), value, span),
             _ => Err(ValueParserError::new(
                 &format!("Expected Boolean Value, received {:?}", self.value),
-                self.raw(),
+                &self.raw(),
                 self.span(),
             )),
         }
@@ -181,7 +202,7 @@ impl ValueValidator for WrappedValue {
             ast::Value::StringValue(value, span) => ValueParserError::wrap(value.parse::<DateTime<Utc>>(), value, span),
             _ => Err(ValueParserError::new(
                 &format!("Expected Boolean Value, received {:?}", self.value),
-                self.raw(),
+                &self.raw(),
                 self.span(),
             )),
         }
@@ -192,7 +213,18 @@ impl ValueValidator for WrappedValue {
             ast::Value::ConstantValue(value, _) => Ok(value.to_string()),
             _ => Err(ValueParserError::new(
                 &format!("Expected Constant Value, received {:?}", self.value),
-                self.raw(),
+                &self.raw(),
+                self.span(),
+            )),
+        }
+    }
+
+    fn as_array(&self) -> Result<Vec<String>, ValueParserError> {
+        match &self.value {
+            ast::Value::ArrayValue(values, _) => Ok(values.clone()),
+            _ => Err(ValueParserError::new(
+                &format!("Expected Array Value, received {:?}", self.value),
+                &self.raw(),
                 self.span(),
             )),
         }
@@ -210,8 +242,8 @@ impl ValueValidator for WrappedErrorValue {
         false
     }
 
-    fn raw(&self) -> &String {
-        &self.raw
+    fn raw(&self) -> String {
+        self.raw.clone()
     }
 
     fn span(&self) -> &ast::Span {
@@ -224,6 +256,9 @@ impl ValueValidator for WrappedErrorValue {
     fn as_int(&self) -> Result<i32, ValueParserError> {
         Err(ValueParserError::new(&self.message, &self.raw, &self.span))
     }
+    fn as_big_int(&self) -> Result<i64, ValueParserError> {
+        Err(ValueParserError::new(&self.message, &self.raw, &self.span))
+    }
     fn as_float(&self) -> Result<f32, ValueParserError> {
         Err(ValueParserError::new(&self.message, &self.raw, &self.span))
     }
@@ -239,4 +274,7 @@ impl ValueValidator for WrappedErrorValue {
     fn as_constant_literal(&self) -> Result<String, ValueParserError> {
         Err(ValueParserError::new(&self.message, &self.raw, &self.span))
     }
+    fn as_array(&self) -> Result<Vec<String>, ValueParserError> {
+        Err(ValueParserError::new(&self.message, &self.raw, &self.span))
+    }
 }
@@ -12,6 +12,7 @@ pub struct Field {
     pub dbName: Option<String>,
     pub arity: String,
     pub isUnique: bool,
+    pub isVersion: bool,
     #[serde(rename = "type")]
     pub field_type: String,
 }
@@ -49,11 +50,13 @@ fn get_field_kind(field: &dml::Field) -> String {
 fn type_to_string(scalar: &dml::ScalarType) -> String {
     match scalar {
         dml::ScalarType::Int => String::from("Int"),
+        dml::ScalarType::BigInt => String::from("BigInt"),
         dml::ScalarType::Decimal => String::from("Decimal"),
         dml::ScalarType::Float => String::from("Float"),
         dml::ScalarType::Boolean => String::from("Boolean"),
         dml::ScalarType::String => String::from("String"),
         dml::ScalarType::DateTime => String::from("DateTime"),
+        dml::ScalarType::Bytes => String::from("Bytes"),
         dml::ScalarType::Enum => panic!("Enum is an internally used type and should never be rendered."),
     }
 }
@@ -93,6 +96,7 @@ pub fn field_to_dmmf(field: &dml::Field) -> Field {
         dbName: field.database_name.clone(),
         arity: get_field_arity(field),
         isUnique: field.is_unique,
+        isVersion: field.is_version,
         field_type: get_field_type(field),
     }
 }
@@ -8,9 +8,11 @@ fn parse_scalar_types() {
     model User {
         firstName: String
         age: Int
+        views: BigInt
         isPro: Boolean
         balance: Decimal
         averageGrade: Float
+        avatar: Bytes
     }
     "#;
 
@@ -22,6 +24,9 @@ fn parse_scalar_types() {
     user_model
         .assert_has_field("age")
         .assert_base_type(&dml::ScalarType::Int);
+    user_model
+        .assert_has_field("views")
+        .assert_base_type(&dml::ScalarType::BigInt);
     user_model
         .assert_has_field("isPro")
         .assert_base_type(&dml::ScalarType::Boolean);
@@ -31,6 +36,9 @@ fn parse_scalar_types() {
     user_model
         .assert_has_field("averageGrade")
         .assert_base_type(&dml::ScalarType::Float);
+    user_model
+        .assert_has_field("avatar")
+        .assert_base_type(&dml::ScalarType::Bytes);
 }
 
 #[test]
@@ -65,6 +73,7 @@ fn parse_defaults() {
     model User {
         firstName: String = "Hello"
         age: Int = 21
+        views: BigInt = 9007199254740993
         isPro: Boolean = false
         balance: Decimal = 1.2
         averageGrade: Float = 3.4
@@ -81,6 +90,10 @@ fn parse_defaults() {
         .assert_has_field("age")
         .assert_base_type(&dml::ScalarType::Int)
         .assert_default_value(dml::Value::Int(21));
+    user_model
+        .assert_has_field("views")
+        .assert_base_type(&dml::ScalarType::BigInt)
+        .assert_default_value(dml::Value::BigInt(9007199254740993));
     user_model
         .assert_has_field("isPro")
         .assert_base_type(&dml::ScalarType::Boolean)
@@ -25,3 +25,179 @@ fn db_directive() {
     let post_model = schema.assert_has_model("Post").assert_with_db_name("posti");
     post_model.assert_has_field("text").assert_with_db_name("post_text");
 }
+
+#[test]
+fn search_directive() {
+    let dml = r#"
+    model Post {
+        title: String @search
+        text: String
+    }
+    "#;
+
+    let schema = parse_and_validate(dml);
+    let post_model = schema.assert_has_model("Post");
+    assert!(post_model.assert_has_field("title").is_searchable);
+    assert!(!post_model.assert_has_field("text").is_searchable);
+}
+
+#[test]
+fn scalar_list_directive() {
+    let dml = r#"
+    model Post {
+        tags: String[] @scalarList(strategy: NATIVE)
+        categories: String[] @scalarList(strategy: RELATION)
+    }
+    "#;
+
+    let schema = parse_and_validate(dml);
+    let post_model = schema.assert_has_model("Post");
+    assert_eq!(
+        post_model.assert_has_field("tags").scalar_list_strategy,
+        Some(dml::ScalarListStrategy::Native)
+    );
+    assert_eq!(
+        post_model.assert_has_field("categories").scalar_list_strategy,
+        Some(dml::ScalarListStrategy::Relation)
+    );
+}
+
+#[test]
+fn sequence_directive() {
+    let dml = r#"
+    model Post {
+        id: Int @primary @sequence(name: "post_seq", initialValue: 10, allocationSize: 100)
+    }
+    "#;
+
+    let schema = parse_and_validate(dml);
+    let id_info = schema
+        .assert_has_model("Post")
+        .assert_has_field("id")
+        .id_info
+        .clone()
+        .unwrap();
+
+    assert_eq!(id_info.strategy, dml::IdStrategy::Sequence);
+    assert_eq!(
+        id_info.sequence,
+        Some(dml::Sequence {
+            name: String::from("post_seq"),
+            initial_value: 10,
+            allocation_size: 100,
+        })
+    );
+}
+
+#[test]
+fn on_delete_directive() {
+    let dml = r#"
+    model User {
+        posts: Post[] @onDelete(strategy: RESTRICT)
+    }
+
+    model Post {
+        author: User
+    }
+    "#;
+
+    let schema = parse_and_validate(dml);
+    let field = schema.assert_has_model("User").assert_has_field("posts");
+
+    match field.field_type {
+        dml::FieldType::Relation(ref info) => assert_eq!(info.on_delete, dml::OnDeleteStrategy::Restrict),
+        _ => panic!("Expected a relation field"),
+    }
+}
+
+#[test]
+fn version_directive() {
+    let dml = r#"
+    model Post {
+        title: String
+        revision: Int @version
+    }
+    "#;
+
+    let schema = parse_and_validate(dml);
+    let post_model = schema.assert_has_model("Post");
+    assert!(post_model.assert_has_field("revision").is_version);
+    assert!(!post_model.assert_has_field("title").is_version);
+}
+
+#[test]
+fn soft_delete_directive() {
+    let dml = r#"
+    model Post {
+        title: String
+        deletedAt: DateTime?
+    }
+    @softDelete
+
+    model Comment {
+        text: String
+    }
+    "#;
+
+    let schema = parse_and_validate(dml);
+    assert!(schema.assert_has_model("Post").is_soft_delete);
+    assert!(!schema.assert_has_model("Comment").is_soft_delete);
+}
+
+#[test]
+fn compound_directives() {
+    let dml = r#"
+    model Membership {
+        id: ID @primary
+        teamId: String
+        userId: String
+        email: String
+        handle: String
+
+        @@id([teamId, userId])
+        @@unique([email, handle])
+    }
+    "#;
+
+    let schema = parse_and_validate(dml);
+    let model = schema.assert_has_model("Membership");
+
+    assert_eq!(model.id_fields, vec!["teamId", "userId"]);
+    assert_eq!(model.unique_fields, vec![vec!["email", "handle"]]);
+}
+
+#[test]
+fn compound_directives_need_scalar_fields() {
+    let dml = r#"
+    model Membership {
+        teamId: String
+        tags: String[]
+
+        @@unique([teamId, tags])
+    }
+    "#;
+
+    let ast = datamodel::parser::parse(&String::from(dml)).expect("Unable to parse datamodel.");
+    let errors = datamodel::validator::Validator::new().validate(&ast).unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "tags is not a scalar field of the model.");
+}
+
+#[test]
+fn compound_id_needs_an_id_field() {
+    let dml = r#"
+    model Membership {
+        teamId: String
+        userId: String
+
+        @@id([teamId, userId])
+    }
+    "#;
+
+    let ast = datamodel::parser::parse(&String::from(dml)).expect("Unable to parse datamodel.");
+    let errors = datamodel::validator::Validator::new().validate(&ast).unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "A model with @@id needs a @primary field as well.");
+}
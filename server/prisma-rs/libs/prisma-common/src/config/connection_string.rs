@@ -15,6 +15,9 @@ pub struct ConnectionStringConfig {
     pub schema: Option<String>,
     pub management_schema: Option<String>,
 
+    /// Publish mutation events to other engines on the same database
+    pub change_feed: Option<bool>,
+
     migrations: Option<bool>,
     active: Option<bool>,
 }
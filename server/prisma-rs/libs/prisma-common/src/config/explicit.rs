@@ -16,6 +16,9 @@ pub struct ExplicitConfig {
     pub database: Option<String>,
     pub management_schema: Option<String>,
 
+    /// Publish mutation events to other engines on the same database
+    pub change_feed: Option<bool>,
+
     pooled: Option<bool>,
     connection_limit: Option<u32>,
     migrations: Option<bool>,
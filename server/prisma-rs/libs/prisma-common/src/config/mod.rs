@@ -60,6 +60,15 @@ impl PrismaDatabase {
             PrismaDatabase::File(config) => config.schema.clone(),
         }
     }
+
+    /// Only available for connectors that can exchange events through the database
+    pub fn change_feed(&self) -> bool {
+        match self {
+            PrismaDatabase::Explicit(config) => config.change_feed.unwrap_or(false),
+            PrismaDatabase::ConnectionString(config) => config.change_feed.unwrap_or(false),
+            PrismaDatabase::File(_) => false,
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -71,6 +80,25 @@ pub struct PrismaConfig {
     pub rabbit_uri: Option<String>,
     pub enable_management_api: Option<bool>,
     pub databases: BTreeMap<String, PrismaDatabase>,
+
+    /// Timeout for executing a request in milliseconds
+    pub request_timeout: Option<u64>,
+
+    /// Number of parsed query documents kept for persisted queries
+    pub persisted_query_cache_size: Option<usize>,
+
+    /// File with the SHA-256 hashes of all queries that are allowed to run, one per line
+    pub persisted_query_allowlist: Option<String>,
+
+    /// Maximum nesting of selections in a query
+    pub max_query_depth: Option<usize>,
+
+    /// Maximum number of nodes a query may return, estimated from `first` and `last`
+    pub max_query_nodes: Option<u64>,
+
+    /// Page size of lists requested without `first` or `last`
+    pub default_page_size: Option<u32>,
+    pub max_page_size: Option<u32>,
 }
 
 /// Loads the config
@@ -41,7 +41,8 @@ pub trait DatabaseMigrationStepsInferrer<T> {
 }
 
 pub trait DatabaseMigrationStepApplier<T> {
-    fn apply(&self, step: T);
+    /// Applies one step, failing with the error of the database.
+    fn apply(&self, step: T) -> Result<(), String>;
 }
 
 pub trait DestructiveChangesChecker<T> {
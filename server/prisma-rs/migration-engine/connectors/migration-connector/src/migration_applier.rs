@@ -2,7 +2,8 @@ use crate::*;
 use std::sync::Arc;
 
 pub trait MigrationApplier<T> {
-    fn apply_steps(&self, migration: Migration, steps: Vec<T>);
+    /// Applies the steps until one fails, returning its error.
+    fn apply_steps(&self, migration: Migration, steps: Vec<T>) -> Result<(), String>;
 }
 
 #[allow(unused, dead_code)]
@@ -13,7 +14,7 @@ pub struct MigrationApplierImpl<T> {
 
 #[allow(unused, dead_code)]
 impl<T> MigrationApplier<T> for MigrationApplierImpl<T> {
-    fn apply_steps(&self, migration: Migration, steps: Vec<T>) {
+    fn apply_steps(&self, migration: Migration, steps: Vec<T>) -> Result<(), String> {
         // todo: refactor those procedural updates into proper domain methods on the Migration struct
         assert_eq!(migration.status, MigrationStatus::Pending); // what other states are valid here?
 
@@ -22,7 +23,16 @@ impl<T> MigrationApplier<T> for MigrationApplierImpl<T> {
         self.migration_persistence.update(&migration_updates);
 
         for step in steps {
-            self.step_applier.apply(step);
+            if let Err(error) = self.step_applier.apply(step) {
+                // The steps applied so far are not rolled back yet.
+                migration_updates.status = MigrationStatus::RollbackFailure;
+                migration_updates.errors.push(error.clone());
+                migration_updates.finished_at = Some(Migration::timestamp_without_nanos());
+                self.migration_persistence.update(&migration_updates);
+
+                return Err(error);
+            }
+
             migration_updates.applied = migration_updates.applied + 1;
             self.migration_persistence.update(&migration_updates);
         }
@@ -30,5 +40,7 @@ impl<T> MigrationApplier<T> for MigrationApplierImpl<T> {
         migration_updates.status = MigrationStatus::Success;
         migration_updates.finished_at = Some(Migration::timestamp_without_nanos());
         self.migration_persistence.update(&migration_updates);
+
+        Ok(())
     }
 }
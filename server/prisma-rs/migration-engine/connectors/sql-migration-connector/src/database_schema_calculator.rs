@@ -1,3 +1,4 @@
+use crate::sql_migration_step::{primary_key_name, search_index_name};
 use database_inspector::*;
 use datamodel::*;
 use std::collections::HashSet;
@@ -22,7 +23,33 @@ impl<'a> DatabaseSchemaCalculator<'a> {
         tables.append(&mut scalar_list_tables);
         tables.append(&mut relation_tables);
 
-        DatabaseSchema { tables }
+        DatabaseSchema {
+            tables,
+            search_indexes: self.calculate_search_indexes(),
+        }
+    }
+
+    /// A full-text index for each model with `@search` fields.
+    fn calculate_search_indexes(&self) -> Vec<SearchIndex> {
+        self.data_model
+            .models()
+            .filter_map(|model| {
+                let columns: Vec<String> = model
+                    .fields()
+                    .filter(|f| f.is_searchable)
+                    .map(|f| f.name.clone())
+                    .collect();
+
+                match columns.is_empty() {
+                    true => None,
+                    false => Some(SearchIndex {
+                        name: search_index_name(&model.name),
+                        table: model.name.clone(),
+                        columns,
+                    }),
+                }
+            })
+            .collect()
     }
 
     fn calculate_model_tables(&self) -> Vec<Table> {
@@ -42,10 +69,46 @@ impl<'a> DatabaseSchemaCalculator<'a> {
                         _ => None,
                     })
                     .collect();
+
+                // A compound id becomes the primary key of the table.
+                let mut indexes = match model.id_fields.is_empty() {
+                    true => Vec::new(),
+                    false => vec![Index {
+                        name: primary_key_name(&model.name),
+                        columns: model.id_fields.clone(),
+                        unique: true,
+                        condition: None,
+                    }],
+                };
+
+                // Soft-deleted rows don't take part in unique constraints.
+                let condition = model
+                    .deleted_at_field()
+                    .map(|field| format!("\"{}\" IS NULL", field.name));
+
+                // With a compound primary key, the id field is unique only.
+                let unique_fields = model
+                    .fields()
+                    .filter(|f| match f.id_info {
+                        Some(_) => !model.id_fields.is_empty(),
+                        None => f.is_unique,
+                    })
+                    .map(|f| vec![f.name.clone()])
+                    .chain(model.unique_fields.iter().cloned());
+
+                for columns in unique_fields {
+                    indexes.push(Index {
+                        name: format!("{}.{}", model.name, columns.join("_")),
+                        columns: columns,
+                        unique: true,
+                        condition: condition.clone(),
+                    });
+                }
+
                 Table {
                     name: model.name.clone(),
                     columns: columns,
-                    indexes: Vec::new(),
+                    indexes: indexes,
                 }
             })
             .collect()
@@ -58,6 +121,7 @@ impl<'a> DatabaseSchemaCalculator<'a> {
             let list_fields: Vec<&Field> = model
                 .fields()
                 .filter(|f| f.arity == FieldArity::List && is_scalar(f))
+                .filter(|f| f.scalar_list_strategy != Some(ScalarListStrategy::Native))
                 .collect();
             for field in list_fields {
                 let id_field = id_field(&model); // todo: find actual id field
@@ -243,11 +307,13 @@ fn scalar_type(field: &Field) -> &ScalarType {
 fn column_type(scalar_type: &ScalarType) -> ColumnType {
     match scalar_type {
         ScalarType::Int => ColumnType::Int,
+        ScalarType::BigInt => ColumnType::BigInt,
         ScalarType::Float => ColumnType::Float,
+        ScalarType::Decimal => ColumnType::Decimal,
         ScalarType::Boolean => ColumnType::Boolean,
         ScalarType::Enum => ColumnType::String,
         ScalarType::String => ColumnType::String,
         ScalarType::DateTime => ColumnType::DateTime,
-        ScalarType::Decimal => unimplemented!(),
+        ScalarType::Bytes => ColumnType::Bytes,
     }
 }
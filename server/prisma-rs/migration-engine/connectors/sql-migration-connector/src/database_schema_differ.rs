@@ -24,6 +24,13 @@ impl DatabaseSchemaDiffer {
         result.append(&mut wrap_as_step(self.alter_tables(), |x| {
             SqlMigrationStep::AlterTable(x)
         }));
+        // Indexes come last, as they may cover columns added above.
+        result.append(&mut wrap_as_step(self.create_indexes(), |x| {
+            SqlMigrationStep::CreateIndex(x)
+        }));
+        result.append(&mut wrap_as_step(self.create_search_indexes(), |x| {
+            SqlMigrationStep::CreateSearchIndex(x)
+        }));
         result
     }
 
@@ -34,7 +41,7 @@ impl DatabaseSchemaDiffer {
                 let primary_columns = next_table
                     .indexes
                     .iter()
-                    .find(|i| i.unique)
+                    .find(|i| i.name == primary_key_name(&next_table.name))
                     .map(|i| i.columns.clone())
                     .unwrap_or(Vec::new());
 
@@ -49,6 +56,39 @@ impl DatabaseSchemaDiffer {
         result
     }
 
+    fn create_indexes(&self) -> Vec<CreateIndex> {
+        let mut result = Vec::new();
+        for next_table in &self.next.tables {
+            let previous_table = self.previous.table(&next_table.name);
+            for index in &next_table.indexes {
+                let exists = previous_table.map_or(false, |t| t.indexes.iter().any(|i| i.name == index.name));
+                if index.name != primary_key_name(&next_table.name) && !exists {
+                    result.push(CreateIndex {
+                        table: next_table.name.clone(),
+                        name: index.name.clone(),
+                        columns: index.columns.clone(),
+                        unique: index.unique,
+                        condition: index.condition.clone(),
+                    });
+                }
+            }
+        }
+        result
+    }
+
+    fn create_search_indexes(&self) -> Vec<CreateSearchIndex> {
+        let mut result = Vec::new();
+        for next_index in &self.next.search_indexes {
+            if !self.previous.has_search_index(&next_index.name) {
+                result.push(CreateSearchIndex {
+                    table: next_index.table.clone(),
+                    columns: next_index.columns.clone(),
+                });
+            }
+        }
+        result
+    }
+
     fn drop_tables(&self) -> Vec<DropTable> {
         let mut result = Vec::new();
         for previous_table in &self.previous.tables {
@@ -141,9 +181,12 @@ impl DatabaseSchemaDiffer {
         match inspector_type {
             database_inspector::ColumnType::Boolean => ColumnType::Boolean,
             database_inspector::ColumnType::Int => ColumnType::Int,
+            database_inspector::ColumnType::BigInt => ColumnType::BigInt,
             database_inspector::ColumnType::Float => ColumnType::Float,
+            database_inspector::ColumnType::Decimal => ColumnType::Decimal,
             database_inspector::ColumnType::String => ColumnType::String,
             database_inspector::ColumnType::DateTime => ColumnType::DateTime,
+            database_inspector::ColumnType::Bytes => ColumnType::Bytes,
         }
     }
 }
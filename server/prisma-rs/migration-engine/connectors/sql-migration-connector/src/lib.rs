@@ -35,6 +35,8 @@ impl SqlMigrationConnector {
         let sql_database_migration_steps_inferrer = Arc::new(SqlDatabaseMigrationStepsInferrer {
             inspector: Box::new(DatabaseInspectorImpl::new(Self::new_conn(&schema_name))),
             schema_name: schema_name.to_string(),
+            // SQLite has neither enum types nor array columns.
+            native_types: false,
         });
         let database_step_applier = Arc::new(SqlDatabaseStepApplier::new(
             Self::new_conn(&schema_name),
@@ -11,6 +11,9 @@ use std::collections::HashMap;
 pub struct SqlDatabaseMigrationStepsInferrer {
     pub inspector: Box<DatabaseInspector>,
     pub schema_name: String,
+    /// Whether the database has enum types, array columns and sequences
+    /// (PostgreSQL).
+    pub native_types: bool,
 }
 
 #[allow(unused, dead_code)]
@@ -18,7 +21,14 @@ impl DatabaseMigrationStepsInferrer<SqlMigrationStep> for SqlDatabaseMigrationSt
     fn infer(&self, previous: &Schema, next: &Schema, steps: Vec<MigrationStep>) -> Vec<SqlMigrationStep> {
         let current_database_schema = self.inspector.introspect(&self.schema_name);
         let expected_database_schema = DatabaseSchemaCalculator::calculate(next);
-        let steps = DatabaseSchemaDiffer::diff(current_database_schema, expected_database_schema);
+        let mut steps = DatabaseSchemaDiffer::diff(current_database_schema, expected_database_schema);
+        if self.native_types {
+            keep_native_columns(&mut steps, next);
+            steps.append(&mut native_type_steps(previous, next));
+            steps.append(&mut wrap_as_step(create_sequences(previous, next), |x| {
+                SqlMigrationStep::CreateSequence(x)
+            }));
+        }
         steps
         // let creates: Vec<CreateModelOrField> = steps
         //     .into_iter()
@@ -78,14 +88,112 @@ fn column_type(ft: FieldType) -> ColumnType {
             ScalarType::Boolean => ColumnType::Boolean,
             ScalarType::String => ColumnType::String,
             ScalarType::Int => ColumnType::Int,
+            ScalarType::BigInt => ColumnType::BigInt,
             ScalarType::Float => ColumnType::Float,
+            ScalarType::Decimal => ColumnType::Decimal,
             ScalarType::DateTime => ColumnType::DateTime,
+            ScalarType::Bytes => ColumnType::Bytes,
             _ => unimplemented!(),
         },
         _ => panic!("Only scalar types are supported here"),
     }
 }
 
+/// Sequences of `@sequence` ids that are new in `next`.
+fn create_sequences(previous: &Schema, next: &Schema) -> Vec<CreateSequence> {
+    let sequences = |schema: &Schema| -> Vec<Sequence> {
+        schema
+            .models()
+            .flat_map(|model| model.fields())
+            .filter_map(|f| f.id_info.as_ref().and_then(|info| info.sequence.clone()))
+            .collect()
+    };
+    let existing = sequences(previous);
+
+    sequences(next)
+        .into_iter()
+        .filter(|seq| existing.iter().all(|e| e.name != seq.name))
+        .map(|seq| CreateSequence {
+            name: seq.name,
+            initial_value: seq.initial_value,
+            allocation_size: seq.allocation_size,
+        })
+        .collect()
+}
+
+/// Enum types and the enum and array columns the calculated schema leaves out,
+/// for the enums, models and fields that are new in `next`.
+fn native_type_steps(previous: &Schema, next: &Schema) -> Vec<SqlMigrationStep> {
+    let create_enums = next
+        .enums()
+        .filter(|e| previous.find_enum(&e.name).is_none())
+        .map(|e| CreateEnum {
+            name: e.name.clone(),
+            values: e.values.clone(),
+        });
+
+    let add_columns = next.models().flat_map(|model| {
+        model
+            .fields()
+            .filter(move |f| {
+                previous
+                    .find_model(&model.name)
+                    .and_then(|m| m.find_field(&f.name))
+                    .is_none()
+            })
+            .filter_map(move |f| {
+                native_column_type(f).map(|tpe| AddNativeColumn {
+                    table: model.name.clone(),
+                    column: f.name.clone(),
+                    tpe,
+                    required: f.arity == FieldArity::Required,
+                })
+            })
+    });
+
+    let mut steps = wrap_as_step(create_enums.collect(), |x| SqlMigrationStep::CreateEnum(x));
+    steps.append(&mut wrap_as_step(add_columns.collect(), |x| {
+        SqlMigrationStep::AddNativeColumn(x)
+    }));
+    steps
+}
+
+fn native_column_type(field: &Field) -> Option<NativeColumnType> {
+    let is_list = field.arity == FieldArity::List;
+
+    match field.field_type {
+        FieldType::Enum(ref name) if is_list => Some(NativeColumnType::EnumArray(name.clone())),
+        FieldType::Enum(ref name) => Some(NativeColumnType::Enum(name.clone())),
+        FieldType::Base(_) if is_list && field.scalar_list_strategy == Some(ScalarListStrategy::Native) => {
+            Some(NativeColumnType::Array(column_type(field.field_type.clone())))
+        }
+        _ => None,
+    }
+}
+
+/// The calculated schema doesn't know the enum and array columns, the differ
+/// must not drop them from the tables that have them.
+fn keep_native_columns(steps: &mut Vec<SqlMigrationStep>, next: &Schema) {
+    for step in steps.iter_mut() {
+        if let SqlMigrationStep::AlterTable(alter_table) = step {
+            let model = match next.find_model(&alter_table.table) {
+                Some(model) => model,
+                None => continue,
+            };
+
+            alter_table.changes.retain(|change| match change {
+                TableChange::DropColumn(drop) => model.find_field(&drop.name).and_then(native_column_type).is_none(),
+                _ => true,
+            });
+        }
+    }
+
+    steps.retain(|step| match step {
+        SqlMigrationStep::AlterTable(alter_table) => !alter_table.changes.is_empty(),
+        _ => true,
+    });
+}
+
 pub fn wrap_as_step<T, F>(steps: Vec<T>, mut wrap_fn: F) -> Vec<SqlMigrationStep>
 where
     F: FnMut(T) -> SqlMigrationStep,
@@ -20,7 +20,7 @@ impl SqlDatabaseStepApplier {
 
 #[allow(unused, dead_code)]
 impl DatabaseMigrationStepApplier<SqlMigrationStep> for SqlDatabaseStepApplier {
-    fn apply(&self, step: SqlMigrationStep) {
+    fn apply(&self, step: SqlMigrationStep) -> Result<(), String> {
         let mut migration = BarrelMigration::new().schema(self.schema_name.clone());
 
         match dbg!(step) {
@@ -44,10 +44,36 @@ impl DatabaseMigrationStepApplier<SqlMigrationStep> for SqlDatabaseStepApplier {
                     }
                 });
             }
+            SqlMigrationStep::CreateSearchIndex(index) => {
+                let sql_string = self.search_index_sql(&index);
+                return self.connection.execute_batch(&sql_string).map_err(|e| e.to_string());
+            }
+            SqlMigrationStep::CreateIndex(index) => {
+                let sql_string = self.index_sql(&index);
+                return self
+                    .connection
+                    .execute(&sql_string, NO_PARAMS)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string());
+            }
+            SqlMigrationStep::CreateEnum(create_enum) => {
+                let sql_string = self.create_enum_sql(&create_enum);
+                return self.connection.execute_batch(&sql_string).map_err(|e| e.to_string());
+            }
+            SqlMigrationStep::CreateSequence(sequence) => {
+                let sql_string = self.create_sequence_sql(&sequence);
+                return self.connection.execute_batch(&sql_string).map_err(|e| e.to_string());
+            }
+            SqlMigrationStep::AddNativeColumn(add_column) => {
+                let sql_string = self.add_native_column_sql(&add_column);
+                return self.connection.execute_batch(&sql_string).map_err(|e| e.to_string());
+            }
             x => panic!(format!("{:?} not implemented yet here", x)),
         };
         let sql_string = dbg!(self.make_sql_string(migration));
-        dbg!(self.connection.execute(&sql_string, NO_PARAMS)).unwrap();
+        dbg!(self.connection.execute(&sql_string, NO_PARAMS))
+            .map(|_| ())
+            .map_err(|e| e.to_string())
     }
 }
 
@@ -56,6 +82,108 @@ impl SqlDatabaseStepApplier {
         // TODO: this should pattern match on the connector type once we have this information available
         migration.make::<barrel::backend::Sqlite>()
     }
+
+    fn create_enum_sql(&self, create_enum: &CreateEnum) -> String {
+        let values: Vec<String> = create_enum
+            .values
+            .iter()
+            .map(|v| format!("'{}'", v.replace('\'', "''")))
+            .collect();
+
+        format!(
+            "CREATE TYPE {}.{} AS ENUM ({});",
+            quote(&self.schema_name),
+            quote(&create_enum.name),
+            values.join(", ")
+        )
+    }
+
+    fn create_sequence_sql(&self, sequence: &CreateSequence) -> String {
+        // A cache of at least one value is the Postgres default
+        let cache = std::cmp::max(sequence.allocation_size, 1);
+
+        format!(
+            "CREATE SEQUENCE {}.{} START WITH {} CACHE {};",
+            quote(&self.schema_name),
+            quote(&sequence.name),
+            sequence.initial_value,
+            cache
+        )
+    }
+
+    fn add_native_column_sql(&self, add_column: &AddNativeColumn) -> String {
+        let tpe = match add_column.tpe {
+            NativeColumnType::Enum(ref name) => format!("{}.{}", quote(&self.schema_name), quote(name)),
+            NativeColumnType::EnumArray(ref name) => format!("{}.{}[]", quote(&self.schema_name), quote(name)),
+            NativeColumnType::Array(ref tpe) => format!("{}[]", postgres_type(tpe)),
+        };
+        let not_null = if add_column.required { " NOT NULL" } else { "" };
+
+        format!(
+            "ALTER TABLE {}.{} ADD COLUMN {} {}{};",
+            quote(&self.schema_name),
+            quote(&add_column.table),
+            quote(&add_column.column),
+            tpe,
+            not_null
+        )
+    }
+
+    fn index_sql(&self, index: &CreateIndex) -> String {
+        let columns: Vec<String> = index.columns.iter().map(|col| format!("\"{}\"", col)).collect();
+        let condition = match index.condition {
+            Some(ref condition) => format!(" WHERE {}", condition),
+            None => String::new(),
+        };
+
+        format!(
+            "CREATE {unique}INDEX \"{schema}\".\"{name}\" ON \"{table}\"({columns}){condition}",
+            unique = if index.unique { "UNIQUE " } else { "" },
+            schema = self.schema_name,
+            name = index.name,
+            table = index.table,
+            columns = columns.join(", "),
+            condition = condition,
+        )
+    }
+
+    /// An FTS5 table reading its content from the model table, kept in sync
+    /// by triggers. Existing rows are indexed right away.
+    fn search_index_sql(&self, index: &CreateSearchIndex) -> String {
+        let fts = search_index_name(&index.table);
+        let quoted = |prefix: &str| -> String {
+            index
+                .columns
+                .iter()
+                .map(|col| format!("{}\"{}\"", prefix, col))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        let (columns, new_values, old_values) = (quoted(""), quoted("new."), quoted("old."));
+
+        format!(
+            r#"
+            CREATE VIRTUAL TABLE "{schema}"."{fts}" USING fts5({columns}, content='{table}', content_rowid='rowid');
+            INSERT INTO "{schema}"."{fts}"("{fts}") VALUES ('rebuild');
+            CREATE TRIGGER "{schema}"."{fts}_insert" AFTER INSERT ON "{table}" BEGIN
+                INSERT INTO "{fts}"(rowid, {columns}) VALUES (new.rowid, {new_values});
+            END;
+            CREATE TRIGGER "{schema}"."{fts}_delete" AFTER DELETE ON "{table}" BEGIN
+                INSERT INTO "{fts}"("{fts}", rowid, {columns}) VALUES ('delete', old.rowid, {old_values});
+            END;
+            CREATE TRIGGER "{schema}"."{fts}_update" AFTER UPDATE ON "{table}" BEGIN
+                INSERT INTO "{fts}"("{fts}", rowid, {columns}) VALUES ('delete', old.rowid, {old_values});
+                INSERT INTO "{fts}"(rowid, {columns}) VALUES (new.rowid, {new_values});
+            END;
+            "#,
+            schema = self.schema_name,
+            fts = fts,
+            table = index.table,
+            columns = columns,
+            new_values = new_values,
+            old_values = old_values,
+        )
+    }
 }
 
 fn column_description_to_barrel_type(column_description: &ColumnDescription) -> barrel::types::Type {
@@ -64,7 +192,28 @@ fn column_description_to_barrel_type(column_description: &ColumnDescription) ->
         ColumnType::DateTime => barrel::types::date(),
         ColumnType::Float => barrel::types::float(),
         ColumnType::Int => barrel::types::integer(),
+        ColumnType::BigInt => barrel::types::custom("BIGINT"),
+        ColumnType::Decimal => barrel::types::custom("NUMERIC"),
         ColumnType::String => barrel::types::text(),
+        ColumnType::Bytes => barrel::types::binary(),
     };
     tpe.nullable(!column_description.required)
 }
+
+fn postgres_type(tpe: &ColumnType) -> &'static str {
+    match tpe {
+        ColumnType::Boolean => "boolean",
+        ColumnType::DateTime => "timestamp(3)",
+        ColumnType::Float => "double precision",
+        ColumnType::Int => "integer",
+        ColumnType::BigInt => "bigint",
+        ColumnType::Decimal => "numeric",
+        ColumnType::String => "text",
+        ColumnType::Bytes => "bytea",
+    }
+}
+
+/// A quoted identifier, double quotes in the name are doubled.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
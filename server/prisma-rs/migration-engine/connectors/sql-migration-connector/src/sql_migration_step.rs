@@ -9,6 +9,11 @@ pub enum SqlMigrationStep {
     CreateTable(CreateTable),
     AlterTable(AlterTable),
     DropTable(DropTable),
+    CreateSearchIndex(CreateSearchIndex),
+    CreateIndex(CreateIndex),
+    CreateEnum(CreateEnum),
+    AddNativeColumn(AddNativeColumn),
+    CreateSequence(CreateSequence),
 }
 
 #[derive(Debug, Serialize)]
@@ -23,6 +28,56 @@ pub struct DropTable {
     pub name: String,
 }
 
+/// A full-text index over the `@search` columns of a table
+#[derive(Debug, Serialize)]
+pub struct CreateSearchIndex {
+    pub table: String,
+    pub columns: Vec<String>,
+}
+
+/// A secondary index of a table, limited to the rows matching `condition`
+#[derive(Debug, Serialize)]
+pub struct CreateIndex {
+    pub table: String,
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+    pub condition: Option<String>,
+}
+
+/// A PostgreSQL enum type
+#[derive(Debug, Serialize)]
+pub struct CreateEnum {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+/// A PostgreSQL sequence for `@sequence` ids, `allocation_size` values are
+/// cached per connection.
+#[derive(Debug, Serialize)]
+pub struct CreateSequence {
+    pub name: String,
+    pub initial_value: i32,
+    pub allocation_size: i32,
+}
+
+/// A column whose type the calculated schema can't describe, added after the
+/// table exists.
+#[derive(Debug, Serialize)]
+pub struct AddNativeColumn {
+    pub table: String,
+    pub column: String,
+    pub tpe: NativeColumnType,
+    pub required: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub enum NativeColumnType {
+    Enum(String),
+    Array(ColumnType),
+    EnumArray(String),
+}
+
 #[derive(Debug, Serialize)]
 pub struct AlterTable {
     pub table: String,
@@ -62,8 +117,21 @@ pub struct ColumnDescription {
 #[derive(Debug, Copy, PartialEq, Eq, Clone, Serialize)]
 pub enum ColumnType {
     Int,
+    BigInt,
     Float,
+    Decimal,
     Boolean,
     String,
     DateTime,
+    Bytes,
+}
+
+/// The index backing the primary key of a table
+pub fn primary_key_name(table: &str) -> String {
+    format!("{}_pkey", table)
+}
+
+/// The FTS5 table holding the full-text index of a table
+pub fn search_index_name(table: &str) -> String {
+    format!("{}_fts", table)
 }
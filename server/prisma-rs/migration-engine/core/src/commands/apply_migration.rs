@@ -40,16 +40,20 @@ impl MigrationCommand for ApplyMigrationCommand {
         migration.database_steps = database_steps_json.to_string();
         let saved_migration = connector.migration_persistence().create(migration);
 
-        connector
+        let general_errors = match connector
             .migration_applier()
-            .apply_steps(saved_migration, database_migration_steps);
+            .apply_steps(saved_migration, database_migration_steps)
+        {
+            Ok(()) => Vec::new(),
+            Err(error) => vec![error],
+        };
 
         ApplyMigrationOutput {
             datamodel_steps: self.input.steps.clone(),
             database_steps: database_steps_json,
             errors: Vec::new(),
             warnings: Vec::new(),
-            general_errors: Vec::new(),
+            general_errors,
         }
     }
 }
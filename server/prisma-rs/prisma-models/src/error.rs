@@ -8,6 +8,9 @@ pub enum DomainError {
     #[fail(display = "Field `{}` on model `{}` not found", name, model)]
     FieldNotFound { name: String, model: String },
 
+    #[fail(display = "Compound unique `{}` on model `{}` not found", name, model)]
+    CompoundUniqueNotFound { name: String, model: String },
+
     #[fail(display = "Relation `{}` not found", name)]
     RelationNotFound { name: String },
 
@@ -39,6 +39,9 @@ pub enum TypeIdentifier {
     GraphQLID,
     UUID,
     Int,
+    BigInt,
+    Decimal,
+    Bytes,
     Relation,
 }
 
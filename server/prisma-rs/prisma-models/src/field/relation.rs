@@ -148,6 +148,15 @@ impl RelationField {
         parts.into()
     }
 
+    /// What happens to the related records when a record of this field's
+    /// model is deleted.
+    pub fn on_delete(&self) -> OnDelete {
+        match self.relation_side {
+            RelationSide::A => self.relation().model_a_on_delete,
+            RelationSide::B => self.relation().model_b_on_delete,
+        }
+    }
+
     pub fn related_model(&self) -> ModelRef {
         match self.relation_side {
             RelationSide::A => self.relation().model_b(),
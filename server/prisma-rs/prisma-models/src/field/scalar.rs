@@ -46,6 +46,8 @@ pub struct ScalarField {
 pub enum FieldBehaviour {
     CreatedAt,
     UpdatedAt,
+    /// Incremented on every update for optimistic concurrency control.
+    Version,
     Id {
         strategy: IdStrategy,
         sequence: Option<Sequence>,
@@ -66,6 +68,8 @@ pub enum IdStrategy {
 pub enum ScalarListStrategy {
     Embedded,
     Relation,
+    /// An array column in the model's table, PostgreSQL only.
+    Native,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq)]
@@ -122,8 +126,15 @@ impl ScalarField {
         }
     }
 
+    pub fn is_version(&self) -> bool {
+        match self.behaviour {
+            Some(FieldBehaviour::Version) => true,
+            _ => false,
+        }
+    }
+
     pub fn is_writable(&self) -> bool {
-        !self.is_id() && !self.is_created_at() && !self.is_updated_at()
+        !self.is_id() && !self.is_created_at() && !self.is_updated_at() && !self.is_version()
     }
 
     pub fn db_name(&self) -> &str {
@@ -146,6 +157,21 @@ impl ScalarField {
         }
     }
 
+    /// `Auto` for fields without Id behaviour, e.g. in legacy data models.
+    pub fn id_strategy(&self) -> IdStrategy {
+        match self.behaviour {
+            Some(FieldBehaviour::Id { strategy, .. }) => strategy,
+            _ => IdStrategy::Auto,
+        }
+    }
+
+    pub fn id_sequence(&self) -> Option<&Sequence> {
+        match self.behaviour {
+            Some(FieldBehaviour::Id { ref sequence, .. }) => sequence.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn scalar_list_behaviour_clone(&self) -> Option<FieldBehaviour> {
         match self.behaviour {
             Some(ref b) => match b {
@@ -156,6 +182,16 @@ impl ScalarField {
         }
     }
 
+    /// Lists kept in an array column instead of a `ScalarListTable`.
+    pub fn is_native_list(&self) -> bool {
+        match self.behaviour {
+            Some(FieldBehaviour::ScalarList {
+                strategy: ScalarListStrategy::Native,
+            }) => self.is_list,
+            _ => false,
+        }
+    }
+
     pub fn scalar_list_table<'a>(&'a self) -> ScalarListTable<'a> {
         ScalarListTable::new(self)
     }
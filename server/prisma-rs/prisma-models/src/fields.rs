@@ -5,6 +5,8 @@ use std::{
     sync::{Arc, Weak},
 };
 
+static DELETED_AT_FIELD: &str = "deletedAt";
+
 #[derive(Debug)]
 pub struct Fields {
     pub all: Vec<Field>,
@@ -14,6 +16,8 @@ pub struct Fields {
     model: ModelWeakRef,
     created_at: OnceCell<Option<Arc<ScalarField>>>,
     updated_at: OnceCell<Option<Arc<ScalarField>>>,
+    version: OnceCell<Option<Arc<ScalarField>>>,
+    deleted_at: OnceCell<Option<Arc<ScalarField>>>,
 }
 
 impl Fields {
@@ -25,6 +29,8 @@ impl Fields {
             relation: OnceCell::new(),
             created_at: OnceCell::new(),
             updated_at: OnceCell::new(),
+            version: OnceCell::new(),
+            deleted_at: OnceCell::new(),
             model,
         }
     }
@@ -62,6 +68,29 @@ impl Fields {
         })
     }
 
+    pub fn version(&self) -> &Option<Arc<ScalarField>> {
+        self.version.get_or_init(|| {
+            self.scalar_weak()
+                .iter()
+                .map(|sf| sf.upgrade().unwrap())
+                .find(|sf| sf.is_version())
+        })
+    }
+
+    /// The `deletedAt` field of a model with soft deletes.
+    pub fn deleted_at(&self) -> &Option<Arc<ScalarField>> {
+        self.deleted_at.get_or_init(|| {
+            if !self.model().is_soft_delete {
+                return None;
+            }
+
+            self.scalar_weak()
+                .iter()
+                .map(|sf| sf.upgrade().unwrap())
+                .find(|sf| sf.name == DELETED_AT_FIELD)
+        })
+    }
+
     pub fn scalar(&self) -> Vec<Arc<ScalarField>> {
         self.scalar_weak().iter().map(|f| f.upgrade().unwrap()).collect()
     }
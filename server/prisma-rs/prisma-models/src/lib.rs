@@ -12,6 +12,8 @@ mod fields;
 mod model;
 mod node;
 mod order_by;
+#[cfg(feature = "sql")]
+mod placeholder;
 mod prisma_args;
 mod prisma_value;
 mod project;
@@ -28,6 +30,8 @@ pub use fields::*;
 pub use model::*;
 pub use node::*;
 pub use order_by::*;
+#[cfg(feature = "sql")]
+pub use placeholder::*;
 pub use prisma_args::*;
 pub use prisma_args::*;
 pub use prisma_value::*;
@@ -13,6 +13,12 @@ pub struct ModelTemplate {
     pub name: String,
     pub stable_identifier: String,
     pub is_embedded: bool,
+    #[serde(default)]
+    pub is_soft_delete: bool,
+    #[serde(default)]
+    pub compound_uniques: Vec<Vec<String>>,
+    #[serde(default)]
+    pub id_fields: Vec<String>,
     pub fields: Vec<FieldTemplate>,
     pub manifestation: Option<ModelManifestation>,
 }
@@ -22,6 +28,13 @@ pub struct Model {
     pub name: String,
     pub stable_identifier: String,
     pub is_embedded: bool,
+    /// Deletes only set `deletedAt`, reads leave such records out.
+    pub is_soft_delete: bool,
+    /// Names of the fields that are unique together, from `@@unique` and
+    /// `@@id`.
+    pub compound_uniques: Vec<Vec<String>>,
+    /// Names of the fields of `@@id`, empty if the model has none.
+    pub id_fields: Vec<String>,
     pub manifestation: Option<ModelManifestation>,
 
     fields: OnceCell<Fields>,
@@ -42,6 +55,9 @@ impl ModelTemplate {
             name: self.name,
             stable_identifier: self.stable_identifier,
             is_embedded: self.is_embedded,
+            is_soft_delete: self.is_soft_delete,
+            compound_uniques: self.compound_uniques,
+            id_fields: self.id_fields,
             fields: OnceCell::new(),
             manifestation: self.manifestation,
             internal_data_model: internal_data_model,
@@ -69,8 +85,12 @@ impl PartialEq for Model {
 }
 
 impl Model {
-    pub fn generate_id(&self) -> GraphqlId {
-        match self.fields().id().type_identifier {
+    /// A new id for a record, `None` if the database generates it or the client
+    /// has to provide one.
+    pub fn generate_id(&self) -> Option<GraphqlId> {
+        let id_field = self.fields().id();
+
+        match (id_field.id_strategy(), id_field.type_identifier) {
             // This will panic when:
             //
             // - System time goes backwards
@@ -78,10 +98,9 @@ impl Model {
             // - Time cannot be converted to a string.
             //
             // Panic is a better choice than bubbling this up
-            TypeIdentifier::GraphQLID => GraphqlId::String(cuid::cuid().unwrap()),
-            TypeIdentifier::UUID => GraphqlId::UUID(Uuid::new_v4()),
-            TypeIdentifier::Int => panic!("Cannot generate integer ids."),
-            t => panic!("You shouldn't even use ids of type {:?}", t),
+            (IdStrategy::Auto, TypeIdentifier::GraphQLID) => Some(GraphqlId::String(cuid::cuid().unwrap())),
+            (IdStrategy::Auto, TypeIdentifier::UUID) => Some(GraphqlId::UUID(Uuid::new_v4())),
+            _ => None,
         }
     }
 
@@ -89,6 +108,13 @@ impl Model {
         (self.internal_data_model().db_name.as_str(), self.db_name()).into()
     }
 
+    /// The name of the full-text index of the model's searchable fields. Only
+    /// a separate table on SQLite, where it is an FTS5 virtual table in the
+    /// model's database.
+    pub fn search_index_name(&self) -> String {
+        format!("{}_fts", self.db_name())
+    }
+
     pub fn fields(&self) -> &Fields {
         self.fields
             .get()
@@ -117,4 +143,32 @@ impl Model {
     pub fn id_column(&self) -> Column {
         self.fields().id().as_column()
     }
+
+    /// The fields of a compound unique, by its name: the field names joined
+    /// with `_`, like `firstName_lastName`.
+    pub fn find_compound_unique(&self, name: &str) -> DomainResult<Vec<Arc<ScalarField>>> {
+        let names = self
+            .compound_uniques
+            .iter()
+            .find(|names| names.join("_") == name)
+            .ok_or_else(|| DomainError::CompoundUniqueNotFound {
+                name: name.to_string(),
+                model: self.name.clone(),
+            })?;
+
+        names.iter().map(|name| self.fields().find_from_scalar(name)).collect()
+    }
+
+    /// The fields identifying a record outside of the engine, the `@@id`
+    /// fields if there are any, the id field otherwise.
+    pub fn identifying_fields(&self) -> DomainResult<Vec<Arc<ScalarField>>> {
+        if self.id_fields.is_empty() {
+            return Ok(vec![self.fields().id()]);
+        }
+
+        self.id_fields
+            .iter()
+            .map(|name| self.fields().find_from_scalar(name))
+            .collect()
+    }
 }
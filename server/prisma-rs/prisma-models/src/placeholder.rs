@@ -0,0 +1,50 @@
+//! The query AST has no node for SQL it doesn't know about, like parameters of
+//! types it can't bind or dialect specific expressions. Such parts travel
+//! through the AST as a column named after their encoded form, and the SQL
+//! connector swaps them for real SQL when rendering the statement.
+
+use prisma_query::ast::Column;
+use serde::Serialize;
+
+/// Starts the name of every placeholder column, followed by the kind of the
+/// placeholder, an underscore and the hex encoded JSON payload.
+pub const PLACEHOLDER_PREFIX: &str = "__prisma_";
+
+/// The kind of a placeholder holding a `PrismaValue` the AST can't bind.
+pub const VALUE_PLACEHOLDER: &str = "value";
+
+/// A column standing in for the `payload` of the given `kind`.
+pub fn placeholder_column<T>(kind: &str, payload: &T) -> Column
+where
+    T: Serialize,
+{
+    // UNWRAP: The payloads are plain data, with string keys only.
+    let json = serde_json::to_vec(payload).unwrap();
+    let hex: String = json.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let name = format!("{}{}_{}", PLACEHOLDER_PREFIX, kind, hex);
+
+    Column::from(name.as_str())
+}
+
+/// The kind and the JSON payload of a placeholder column name, `None` if the
+/// name is not a placeholder.
+pub fn placeholder_payload(name: &str) -> Option<(&str, Vec<u8>)> {
+    if !name.starts_with(PLACEHOLDER_PREFIX) {
+        return None;
+    }
+
+    let mut parts = name[PLACEHOLDER_PREFIX.len()..].splitn(2, '_');
+    let kind = parts.next()?;
+    let hex = parts.next()?;
+
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+
+    let json: Option<Vec<u8>> = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect();
+
+    json.map(|json| (kind, json))
+}
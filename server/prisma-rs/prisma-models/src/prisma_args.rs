@@ -1,21 +1,48 @@
 use crate::prelude::{ModelRef, PrismaValue};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::{btree_map::Keys, BTreeMap};
 
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct PrismaArgs {
     pub args: BTreeMap<String, PrismaValue>,
+    pub operations: BTreeMap<String, NumericOperation>,
+}
+
+/// An update computed by the database from the current value of a numeric
+/// field, avoiding a read before the write.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum NumericOperation {
+    Increment(PrismaValue),
+    Decrement(PrismaValue),
+    Multiply(PrismaValue),
+}
+
+/// A change to a scalar list, keeping the values it doesn't involve in place.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum ScalarListOperation {
+    /// Appends the values to the end of the list.
+    Push(Vec<PrismaValue>),
+    /// Prepends the values to the start of the list.
+    Unshift(Vec<PrismaValue>),
+    /// Removes every occurrence of the values.
+    Remove(Vec<PrismaValue>),
+    /// Removes all values.
+    Unset,
 }
 
 impl From<BTreeMap<String, PrismaValue>> for PrismaArgs {
     fn from(args: BTreeMap<String, PrismaValue>) -> Self {
-        Self { args }
+        Self {
+            args,
+            operations: BTreeMap::new(),
+        }
     }
 }
 
 impl PrismaArgs {
     pub fn new() -> Self {
-        Self { args: BTreeMap::new() }
+        Self::default()
     }
 
     pub fn insert<T, V>(&mut self, key: T, arg: V)
@@ -26,6 +53,18 @@ impl PrismaArgs {
         self.args.insert(key.into(), arg.into());
     }
 
+    pub fn insert_operation<T>(&mut self, key: T, operation: NumericOperation)
+    where
+        T: Into<String>,
+    {
+        self.operations.insert(key.into(), operation);
+    }
+
+    /// True if neither values nor operations are given.
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty() && self.operations.is_empty()
+    }
+
     pub fn has_arg_for(&self, field: &str) -> bool {
         self.args.contains_key(field)
     }
@@ -42,32 +81,46 @@ impl PrismaArgs {
         self.args.keys()
     }
 
-    pub fn add_datetimes(&mut self, model: ModelRef) {
-        if !self.args.is_empty() {
-            let now = PrismaValue::DateTime(Utc::now());
-
-            match (model.fields().created_at(), model.fields().updated_at()) {
-                (Some(created_at), Some(updated_at)) => {
-                    self.args.insert(created_at.name.clone(), now.clone());
-                    self.args.insert(updated_at.name.clone(), now);
-                }
-                (Some(created_at), None) => {
-                    self.args.insert(created_at.name.clone(), now);
-                }
-                (None, Some(updated_at)) => {
-                    self.args.insert(updated_at.name.clone(), now);
-                }
-                (None, None) => (),
+    /// Sets the `createdAt` and `updatedAt` fields of a new record.
+    pub fn add_datetimes(&mut self, model: ModelRef, now: DateTime<Utc>) {
+        let now = PrismaValue::DateTime(now);
+
+        match (model.fields().created_at(), model.fields().updated_at()) {
+            (Some(created_at), Some(updated_at)) => {
+                self.args.insert(created_at.name.clone(), now.clone());
+                self.args.insert(updated_at.name.clone(), now);
+            }
+            (Some(created_at), None) => {
+                self.args.insert(created_at.name.clone(), now);
             }
+            (None, Some(updated_at)) => {
+                self.args.insert(updated_at.name.clone(), now);
+            }
+            (None, None) => (),
         }
     }
 
-    pub fn update_datetimes(&mut self, model: ModelRef, list_causes_update: bool) {
-        if !self.args.is_empty() || list_causes_update {
+    /// Sets the `updatedAt` field if the update changes anything.
+    pub fn update_datetimes(&mut self, model: ModelRef, list_causes_update: bool, now: DateTime<Utc>) {
+        if !self.is_empty() || list_causes_update {
             if let Some(field) = model.fields().updated_at() {
-                self.args
-                    .insert(field.name.to_string(), PrismaValue::DateTime(Utc::now()));
+                self.args.insert(field.name.to_string(), PrismaValue::DateTime(now));
             }
         }
     }
+
+    /// Starts the version of a new record at 1.
+    pub fn add_version(&mut self, model: ModelRef) {
+        if let Some(field) = model.fields().version() {
+            self.args.insert(field.name.clone(), PrismaValue::Int(1));
+        }
+    }
+
+    /// Increments the version of an updated record.
+    pub fn increment_version(&mut self, model: ModelRef) {
+        if let Some(field) = model.fields().version() {
+            let operation = NumericOperation::Increment(PrismaValue::Int(1));
+            self.operations.insert(field.name.clone(), operation);
+        }
+    }
 }
@@ -1,14 +1,19 @@
-use crate::{DomainError, DomainResult};
+use crate::{DomainError, DomainResult, TypeIdentifier};
 use chrono::prelude::*;
 use graphql_parser::query::{Number, Value as GraphqlValue};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{convert::TryFrom, fmt};
+use std::{convert::TryFrom, fmt, str::FromStr};
 use uuid::Uuid;
 
+pub use rust_decimal::Decimal;
+
 #[cfg(feature = "sql")]
 use prisma_query::ast::*;
 
+#[cfg(feature = "sql")]
+use crate::{placeholder_column, VALUE_PLACEHOLDER};
+
 pub type PrismaListValue = Option<Vec<PrismaValue>>;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -19,10 +24,12 @@ pub enum GraphqlId {
 }
 
 impl GraphqlId {
+    /// GraphQL ints are 32 bits, larger ids are passed on as strings.
     pub fn to_value(&self) -> GraphqlValue {
         match self {
             GraphqlId::String(s) => GraphqlValue::String(s.clone()),
-            GraphqlId::Int(i) => GraphqlValue::Int(Number::from((*i) as i32)), // This could cause issues!
+            GraphqlId::Int(i) if *i <= i32::max_value() as usize => GraphqlValue::Int(Number::from(*i as i32)),
+            GraphqlId::Int(i) => GraphqlValue::String(i.to_string()),
             GraphqlId::UUID(u) => GraphqlValue::String(u.to_string()),
         }
     }
@@ -37,6 +44,9 @@ pub enum PrismaValue {
     Enum(String),
     Json(Value),
     Int(i64),
+    BigInt(i64),
+    Decimal(Decimal),
+    Bytes(Vec<u8>),
     Relation(usize),
     Null,
     Uuid(Uuid),
@@ -59,26 +69,77 @@ impl PrismaValue {
             GraphqlValue::Float(f) => PrismaValue::Float(f.clone()),
             GraphqlValue::Int(i) => PrismaValue::Int(i.as_i64().unwrap()),
             GraphqlValue::Null => PrismaValue::Null,
-            GraphqlValue::String(s) => Self::str_as_json(s)
-                .or_else(|| Self::str_as_datetime(s))
-                .unwrap_or(PrismaValue::String(s.clone())),
+            GraphqlValue::String(s) => PrismaValue::String(s.clone()),
             GraphqlValue::List(l) => PrismaValue::List(Some(l.iter().map(|i| Self::from_value(i)).collect())),
             GraphqlValue::Object(obj) if obj.contains_key("set") => Self::from_value(obj.get("set").unwrap()),
             value => panic!(format!("Unable to make {:?} to PrismaValue", value)),
         }
     }
 
-    fn str_as_json(s: &str) -> Option<PrismaValue> {
-        serde_json::from_str(s).ok().map(|j| PrismaValue::Json(j))
+    /// Like `from_value`, but for a field of the given type. Decimals and big
+    /// integers can't be told apart from floats and ints without it, and are
+    /// accepted as strings as well, the only lossless way to send them. The
+    /// same goes for ints that don't fit the 32 bits of a GraphQL `Int`. Bytes
+    /// are sent base64 encoded, and strings only become dates or JSON if the
+    /// field is one.
+    pub fn from_typed_value(v: &GraphqlValue, type_identifier: TypeIdentifier) -> DomainResult<Self> {
+        match (type_identifier, v) {
+            (_, GraphqlValue::Null) => Ok(PrismaValue::Null),
+            (_, GraphqlValue::List(l)) => l
+                .iter()
+                .map(|i| Self::from_typed_value(i, type_identifier))
+                .collect::<DomainResult<Vec<PrismaValue>>>()
+                .map(|values| PrismaValue::List(Some(values))),
+            (_, GraphqlValue::Object(obj)) if obj.contains_key("set") => {
+                Self::from_typed_value(obj.get("set").unwrap(), type_identifier)
+            }
+            (TypeIdentifier::Decimal, GraphqlValue::String(s)) => Decimal::from_str(s)
+                .map(PrismaValue::Decimal)
+                .map_err(|_| DomainError::ConversionFailure("String", "Decimal")),
+            (TypeIdentifier::Decimal, GraphqlValue::Int(i)) => i
+                .as_i64()
+                .map(|i| PrismaValue::Decimal(Decimal::from(i)))
+                .ok_or_else(|| DomainError::ConversionFailure("Int", "Decimal")),
+            (TypeIdentifier::Decimal, GraphqlValue::Float(f)) => Decimal::from_str(&f.to_string())
+                .map(PrismaValue::Decimal)
+                .map_err(|_| DomainError::ConversionFailure("Float", "Decimal")),
+            (TypeIdentifier::BigInt, GraphqlValue::String(s)) => s
+                .parse::<i64>()
+                .map(PrismaValue::BigInt)
+                .map_err(|_| DomainError::ConversionFailure("String", "BigInt")),
+            (TypeIdentifier::BigInt, GraphqlValue::Int(i)) => i
+                .as_i64()
+                .map(PrismaValue::BigInt)
+                .ok_or_else(|| DomainError::ConversionFailure("Int", "BigInt")),
+            (TypeIdentifier::Int, GraphqlValue::String(s)) => s
+                .parse::<i64>()
+                .map(PrismaValue::Int)
+                .map_err(|_| DomainError::ConversionFailure("String", "Int")),
+            (TypeIdentifier::Bytes, GraphqlValue::String(s)) => base64::decode(s)
+                .map(PrismaValue::Bytes)
+                .map_err(|_| DomainError::ConversionFailure("String", "Bytes")),
+            (TypeIdentifier::DateTime, GraphqlValue::String(s)) => Self::str_as_datetime(s)
+                .map(PrismaValue::DateTime)
+                .ok_or_else(|| DomainError::ConversionFailure("String", "DateTime")),
+            (TypeIdentifier::Json, GraphqlValue::String(s)) => serde_json::from_str(s)
+                .map(PrismaValue::Json)
+                .map_err(|_| DomainError::ConversionFailure("String", "Json")),
+            (TypeIdentifier::DateTime, _) => Err(DomainError::ConversionFailure("GraphqlValue", "DateTime")),
+            (TypeIdentifier::Bytes, _) => Err(DomainError::ConversionFailure("GraphqlValue", "Bytes")),
+            (TypeIdentifier::Decimal, _) => Err(DomainError::ConversionFailure("GraphqlValue", "Decimal")),
+            (TypeIdentifier::BigInt, _) => Err(DomainError::ConversionFailure("GraphqlValue", "BigInt")),
+            (_, v) => Ok(Self::from_value(v)),
+        }
     }
 
-    // If you look at this and think: "What's up with Z?" then you're asking the right question.
-    // Feel free to try and fix it for cases with AND without Z.
-    fn str_as_datetime(s: &str) -> Option<PrismaValue> {
-        let fmt = "%Y-%m-%dT%H:%M:%S%.3f";
-        Utc.datetime_from_str(s.trim_end_matches("Z"), fmt)
+    /// RFC 3339, with any offset and fractional precision. Dates without a
+    /// time and date times without an offset are taken to be UTC.
+    fn str_as_datetime(s: &str) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f").map(|dt| Utc.from_utc_datetime(&dt)))
+            .or_else(|_| NaiveDate::parse_from_str(s, "%Y-%m-%d").map(|d| Utc.from_utc_datetime(&d.and_hms(0, 0, 0))))
             .ok()
-            .map(|dt| PrismaValue::DateTime(DateTime::<Utc>::from_utc(dt.naive_utc(), Utc)))
     }
 }
 
@@ -92,6 +153,9 @@ impl fmt::Display for PrismaValue {
             PrismaValue::Enum(x) => x.fmt(f),
             PrismaValue::Json(x) => x.fmt(f),
             PrismaValue::Int(x) => x.fmt(f),
+            PrismaValue::BigInt(x) => x.fmt(f),
+            PrismaValue::Decimal(x) => x.fmt(f),
+            PrismaValue::Bytes(x) => base64::encode(x).fmt(f),
             PrismaValue::Relation(x) => x.fmt(f),
             PrismaValue::Null => "null".fmt(f),
             PrismaValue::Uuid(x) => x.fmt(f),
@@ -150,6 +214,18 @@ impl From<i64> for PrismaValue {
     }
 }
 
+impl From<Decimal> for PrismaValue {
+    fn from(d: Decimal) -> Self {
+        PrismaValue::Decimal(d)
+    }
+}
+
+impl From<Vec<u8>> for PrismaValue {
+    fn from(b: Vec<u8>) -> Self {
+        PrismaValue::Bytes(b)
+    }
+}
+
 impl From<Uuid> for PrismaValue {
     fn from(s: Uuid) -> Self {
         PrismaValue::Uuid(s)
@@ -219,7 +295,7 @@ impl TryFrom<PrismaValue> for i64 {
 
     fn try_from(value: PrismaValue) -> DomainResult<i64> {
         match value {
-            PrismaValue::Int(i) => Ok(i),
+            PrismaValue::Int(i) | PrismaValue::BigInt(i) => Ok(i),
             _ => Err(DomainError::ConversionFailure("PrismaValue", "i64")),
         }
     }
@@ -254,11 +330,17 @@ impl From<PrismaValue> for DatabaseValue {
             PrismaValue::Enum(e) => e.into(),
             PrismaValue::Json(j) => j.to_string().into(),
             PrismaValue::Int(i) => (i as i64).into(),
+            PrismaValue::BigInt(i) => i.into(),
+            // Bound as text, a `NUMERIC` column would reject it.
+            PrismaValue::Decimal(d) => placeholder_column(VALUE_PLACEHOLDER, &PrismaValue::Decimal(d)).into(),
+            // The AST has no binary parameters.
+            PrismaValue::Bytes(b) => placeholder_column(VALUE_PLACEHOLDER, &PrismaValue::Bytes(b)).into(),
             PrismaValue::Relation(i) => (i as i64).into(),
             PrismaValue::Null => DatabaseValue::Parameterized(ParameterizedValue::Null),
             PrismaValue::Uuid(u) => u.into(),
             PrismaValue::GraphqlId(id) => id.into(),
-            PrismaValue::List(Some(l)) => l.into(),
+            // A row of values otherwise, not an array.
+            PrismaValue::List(Some(l)) => placeholder_column(VALUE_PLACEHOLDER, &PrismaValue::List(Some(l))).into(),
             PrismaValue::List(_) => panic!("List values are not supported here"),
         }
     }
@@ -293,3 +375,46 @@ impl From<Uuid> for GraphqlId {
         GraphqlId::UUID(uuid)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(s: &str) -> DomainResult<PrismaValue> {
+        PrismaValue::from_typed_value(&GraphqlValue::String(s.to_string()), TypeIdentifier::DateTime)
+    }
+
+    #[test]
+    fn datetimes_are_parsed_as_rfc3339() {
+        let expected = PrismaValue::DateTime(Utc.ymd(2019, 5, 1).and_hms_milli(10, 30, 0, 120));
+
+        assert_eq!(expected, datetime("2019-05-01T10:30:00.120Z").unwrap());
+        assert_eq!(expected, datetime("2019-05-01T10:30:00.12Z").unwrap());
+        assert_eq!(expected, datetime("2019-05-01T12:30:00.120+02:00").unwrap());
+        assert_eq!(expected, datetime("2019-05-01T10:30:00.120").unwrap());
+    }
+
+    #[test]
+    fn dates_are_midnight_utc() {
+        let expected = PrismaValue::DateTime(Utc.ymd(2019, 5, 1).and_hms(0, 0, 0));
+        assert_eq!(expected, datetime("2019-05-01").unwrap());
+    }
+
+    #[test]
+    fn strings_are_only_coerced_for_their_field_type() {
+        let value = GraphqlValue::String("2019-05-01T10:30:00Z".to_string());
+
+        assert_eq!(
+            PrismaValue::String("2019-05-01T10:30:00Z".to_string()),
+            PrismaValue::from_typed_value(&value, TypeIdentifier::String).unwrap()
+        );
+
+        let value = GraphqlValue::String("{}".to_string());
+
+        assert_eq!(
+            PrismaValue::String("{}".to_string()),
+            PrismaValue::from_typed_value(&value, TypeIdentifier::String).unwrap()
+        );
+        assert!(datetime("yesterday").is_err());
+    }
+}
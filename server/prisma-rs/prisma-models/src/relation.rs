@@ -11,20 +11,29 @@ pub type RelationWeakRef = Weak<Relation>;
 pub enum OnDelete {
     SetNull,
     Cascade,
+    /// Deleting fails while related records exist.
+    Restrict,
 }
 
 impl OnDelete {
     pub fn is_cascade(&self) -> bool {
         match self {
             OnDelete::Cascade => true,
-            OnDelete::SetNull => false,
+            _ => false,
         }
     }
 
     pub fn is_set_null(&self) -> bool {
         match self {
-            OnDelete::Cascade => false,
             OnDelete::SetNull => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_restrict(&self) -> bool {
+        match self {
+            OnDelete::Restrict => true,
+            _ => false,
         }
     }
 }
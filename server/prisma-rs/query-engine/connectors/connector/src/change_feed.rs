@@ -0,0 +1,20 @@
+use crate::ConnectorResult;
+
+/// Methods for exchanging mutation events between all query engines
+/// connected to the same database.
+pub trait ChangeFeed {
+    /// Publish a payload to every listener. Must only be called after the
+    /// write that caused it has been committed.
+    fn notify(&self, payload: &str) -> ConnectorResult<()>;
+
+    /// True if any engine has subscribers for the model. Writes only collect
+    /// and publish event data if someone listens for it. Called for every
+    /// write, so implementations should not ask the database each time.
+    fn has_listeners(&self, model_name: &str) -> ConnectorResult<bool>;
+
+    /// Blocks the current thread and hands every received payload to `f`,
+    /// including the ones published by this engine. The models that have
+    /// subscribers on this engine are polled from `models` and announced to
+    /// the writers of all engines. Returns when the listening connection fails.
+    fn listen(&self, models: &Fn() -> Vec<String>, f: &mut FnMut(String)) -> ConnectorResult<()>;
+}
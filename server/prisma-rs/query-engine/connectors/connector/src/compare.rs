@@ -1,4 +1,4 @@
-use crate::filter::Filter;
+use crate::filter::{Filter, JsonCondition};
 use prisma_models::PrismaValue;
 
 /// Comparing methods for scalar fields.
@@ -58,6 +58,15 @@ pub trait ScalarCompare {
     fn greater_than_or_equals<T>(&self, val: T) -> Filter
     where
         T: Into<PrismaValue>;
+
+    fn search<T>(&self, val: T) -> Filter
+    where
+        T: Into<PrismaValue>;
+}
+
+/// Comparison methods for the values inside Json fields.
+pub trait JsonCompare {
+    fn json_path(&self, path: Vec<String>, condition: JsonCondition) -> Filter;
 }
 
 /// Comparison methods for relational fields.
@@ -87,13 +96,13 @@ pub trait ScalarListCompare {
     where
         T: Into<PrismaValue>;
 
-    fn contains_every_element<T>(&self, filter: Vec<T>) -> Filter
+    fn contains_every_element<T>(&self, values: Vec<T>) -> Filter
     where
-        T: Into<Filter>;
+        T: Into<PrismaValue>;
 
-    fn contains_some_element<T>(&self, filter: Vec<T>) -> Filter
+    fn contains_some_element<T>(&self, values: Vec<T>) -> Filter
     where
-        T: Into<Filter>;
+        T: Into<PrismaValue>;
 
     fn contains_none(&self) -> Filter;
 }
@@ -0,0 +1,90 @@
+//! The deadline of the request executing on the current thread.
+//!
+//! A request is executed synchronously on one thread, so connectors read the
+//! deadline from here instead of it being passed through every query.
+
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    static DEADLINE: Cell<Option<Instant>> = Cell::new(None);
+}
+
+/// Runs `f` with a deadline of `timeout` from now, `None` runs it without one
+pub fn with_timeout<F, T>(timeout: Option<Duration>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let _guard = DeadlineGuard::set(timeout.map(|timeout| Instant::now() + timeout));
+    f()
+}
+
+/// Time left until the deadline, zero if it passed and `None` if there is none
+pub fn time_left() -> Option<Duration> {
+    DEADLINE.with(|deadline| deadline.get()).map(|deadline| {
+        let now = Instant::now();
+
+        if deadline > now {
+            deadline - now
+        } else {
+            Duration::from_secs(0)
+        }
+    })
+}
+
+/// Restores the previous deadline, even if `f` panics
+struct DeadlineGuard {
+    previous: Option<Instant>,
+}
+
+impl DeadlineGuard {
+    fn set(deadline: Option<Instant>) -> Self {
+        let previous = DEADLINE.with(|d| d.replace(deadline));
+        Self { previous }
+    }
+}
+
+impl Drop for DeadlineGuard {
+    fn drop(&mut self) {
+        DEADLINE.with(|d| d.set(self.previous));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_deadline_without_a_timeout() {
+        assert_eq!(None, time_left());
+        assert_eq!(None, with_timeout(None, time_left));
+    }
+
+    #[test]
+    fn time_left_is_bounded_by_the_timeout() {
+        let left = with_timeout(Some(Duration::from_secs(60)), time_left).unwrap();
+
+        assert!(left <= Duration::from_secs(60));
+        assert!(left > Duration::from_secs(0));
+    }
+
+    #[test]
+    fn time_left_is_zero_after_the_deadline() {
+        let left = with_timeout(Some(Duration::from_secs(0)), time_left);
+
+        assert_eq!(Some(Duration::from_secs(0)), left);
+    }
+
+    #[test]
+    fn nested_deadlines_restore_the_previous_one() {
+        with_timeout(Some(Duration::from_secs(60)), || {
+            with_timeout(None, || assert_eq!(None, time_left()));
+
+            assert!(time_left().unwrap() > Duration::from_secs(0));
+        });
+
+        assert_eq!(None, time_left());
+    }
+}
@@ -30,12 +30,22 @@ impl fmt::Display for NodeSelectorInfo {
     }
 }
 
+/// Compound selectors list their fields separated by commas, with their
+/// values in a list.
 impl From<&NodeSelector> for NodeSelectorInfo {
     fn from(ns: &NodeSelector) -> Self {
+        let names: Vec<&str> = ns.fields.iter().map(|(field, _)| field.name.as_str()).collect();
+        let mut values: Vec<PrismaValue> = ns.fields.iter().map(|(_, value)| value.clone()).collect();
+
+        let value = match values.len() {
+            1 => values.pop().unwrap(),
+            _ => PrismaValue::List(Some(values)),
+        };
+
         Self {
-            model: ns.field.model().name.clone(),
-            field: ns.field.name.clone(),
-            value: ns.value.clone(),
+            model: ns.model().name.clone(),
+            field: names.join(", "),
+            value,
         }
     }
 }
@@ -66,12 +76,21 @@ pub enum ConnectorError {
     #[fail(display = "Field cannot be null: {}", field)]
     FieldCannotBeNull { field: String },
 
+    #[fail(display = "Field is maintained by the database and can't be set: {}", field)]
+    FieldIsReadOnly { field: String },
+
     #[fail(display = "{}", _0)]
     DomainError(DomainError),
 
     #[fail(display = "Node not found: {}", _0)]
     NodeNotFoundForWhere(NodeSelectorInfo),
 
+    #[fail(display = "Version conflict, expected version {} for {}", expected, record)]
+    VersionConflict {
+        record: NodeSelectorInfo,
+        expected: PrismaValue,
+    },
+
     #[fail(
         display = "Violating a relation {} between {} and {}",
         relation_name, model_a_name, model_b_name
@@ -99,6 +118,9 @@ pub enum ConnectorError {
 
     #[fail(display = "Database creation error: {}", _0)]
     DatabaseCreationError(&'static str),
+
+    #[fail(display = "The query did not finish within the request timeout.")]
+    QueryTimeout,
 }
 
 impl From<DomainError> for ConnectorError {
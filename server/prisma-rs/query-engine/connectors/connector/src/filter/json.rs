@@ -0,0 +1,44 @@
+use super::{Filter, QueryMode, ScalarCondition, ScalarFilter};
+use crate::compare::JsonCompare;
+use prisma_models::ScalarField;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// A condition on the value found at `path` inside a Json field
+#[derive(Debug, Clone)]
+pub struct JsonPathCondition {
+    pub path: Vec<String>,
+    pub condition: JsonCondition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum JsonCondition {
+    /// Value at the path equals the given value
+    Equals(Value),
+
+    /// Value at the path is a string containing the given string
+    StringContains(String),
+
+    /// Value at the path is an array containing the given value
+    ArrayContains(Value),
+
+    /// Value at the path is less than the given value
+    LessThan(Value),
+
+    /// Value at the path is greater than the given value
+    GreaterThan(Value),
+
+    /// Something is found at the path, even a JSON `null`
+    Exists,
+}
+
+impl JsonCompare for Arc<ScalarField> {
+    fn json_path(&self, path: Vec<String>, condition: JsonCondition) -> Filter {
+        Filter::from(ScalarFilter {
+            field: Arc::clone(self),
+            condition: ScalarCondition::JsonPath(JsonPathCondition { path, condition }),
+            mode: QueryMode::Default,
+        })
+    }
+}
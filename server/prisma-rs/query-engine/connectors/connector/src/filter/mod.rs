@@ -4,11 +4,13 @@
 //! [ScalarCompare](/connector/trait.ScalarCompare.html) and
 //! [RelationCompare](/connector/trait.RelationCompare.html).
 
+mod json;
 mod list;
 mod node_selector;
 mod relation;
 mod scalar;
 
+pub use json::*;
 pub use list::*;
 pub use node_selector::*;
 pub use relation::*;
@@ -73,9 +75,21 @@ impl From<bool> for Filter {
 
 impl From<NodeSelector> for Filter {
     fn from(node_selector: NodeSelector) -> Self {
-        Filter::Scalar(ScalarFilter {
-            field: node_selector.field,
-            condition: ScalarCondition::Equals(node_selector.value),
-        })
+        let mut filters: Vec<Filter> = node_selector
+            .fields
+            .into_iter()
+            .map(|(field, value)| {
+                Filter::Scalar(ScalarFilter {
+                    field,
+                    condition: ScalarCondition::Equals(value),
+                    mode: QueryMode::Default,
+                })
+            })
+            .collect();
+
+        match filters.len() {
+            1 => filters.pop().unwrap(),
+            _ => Filter::and(filters),
+        }
     }
 }
@@ -1,10 +1,11 @@
 use prisma_models::prelude::*;
 use std::sync::Arc;
 
+/// Selects one record by the value of a unique field, or by the values of
+/// all fields of a compound unique.
 #[derive(Debug, Clone)]
 pub struct NodeSelector {
-    pub field: Arc<ScalarField>,
-    pub value: PrismaValue,
+    pub fields: Vec<(Arc<ScalarField>, PrismaValue)>,
 }
 
 impl<T> From<(Arc<ScalarField>, T)> for NodeSelector
@@ -12,10 +13,7 @@ where
     T: Into<PrismaValue>,
 {
     fn from(tup: (Arc<ScalarField>, T)) -> NodeSelector {
-        NodeSelector {
-            field: tup.0,
-            value: tup.1.into(),
-        }
+        NodeSelector::new(tup.0, tup.1)
     }
 }
 
@@ -25,8 +23,20 @@ impl NodeSelector {
         T: Into<PrismaValue>,
     {
         Self {
-            field: field,
-            value: value.into(),
+            fields: vec![(field, value.into())],
         }
     }
+
+    pub fn compound(fields: Vec<(Arc<ScalarField>, PrismaValue)>) -> Self {
+        assert!(!fields.is_empty(), "A compound selector needs at least one field.");
+        Self { fields }
+    }
+
+    pub fn model(&self) -> ModelRef {
+        self.fields[0].0.model()
+    }
+
+    pub fn is_compound(&self) -> bool {
+        self.fields.len() > 1
+    }
 }
@@ -2,15 +2,18 @@
 #![macro_use]
 extern crate failure_derive;
 
+pub mod deadline;
 pub mod error;
 pub mod filter;
 pub mod mutaction;
 
+mod change_feed;
 mod compare;
 mod data_resolver;
 mod database_mutaction_executor;
 mod query_arguments;
 
+pub use change_feed::*;
 pub use compare::*;
 pub use data_resolver::*;
 pub use database_mutaction_executor::*;
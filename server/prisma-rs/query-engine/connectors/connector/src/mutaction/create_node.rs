@@ -19,8 +19,29 @@ pub struct NestedCreateNode {
     pub nested_mutactions: NestedMutactions,
 }
 
+/// Creates many records of one model at once, without nested mutactions.
+#[derive(Debug, Clone)]
+pub struct CreateNodes {
+    pub model: ModelRef,
+    pub records: Vec<CreateNodesRecord>,
+    /// Leave out records violating a unique constraint instead of failing.
+    pub skip_duplicates: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateNodesRecord {
+    pub non_list_args: PrismaArgs,
+    pub list_args: Vec<(String, PrismaListValue)>,
+}
+
 impl From<CreateNode> for DatabaseMutaction {
     fn from(cn: CreateNode) -> DatabaseMutaction {
         DatabaseMutaction::TopLevel(TopLevelDatabaseMutaction::CreateNode(cn))
     }
 }
+
+impl From<CreateNodes> for DatabaseMutaction {
+    fn from(cns: CreateNodes) -> DatabaseMutaction {
+        DatabaseMutaction::TopLevel(TopLevelDatabaseMutaction::CreateNodes(cns))
+    }
+}
@@ -5,6 +5,7 @@ use std::sync::Arc;
 #[derive(Debug, Clone)]
 pub struct DeleteNode {
     pub where_: NodeSelector,
+    pub expected_version: Option<PrismaValue>,
 }
 
 #[derive(Debug, Clone)]
@@ -40,6 +40,7 @@ pub enum TopLevelDatabaseMutaction {
     UpdateNode(UpdateNode),
     DeleteNode(DeleteNode),
     UpsertNode(UpsertNode),
+    CreateNodes(CreateNodes),
     UpdateNodes(UpdateNodes),
     DeleteNodes(DeleteNodes),
     ResetData(ResetData),
@@ -52,6 +53,7 @@ pub enum NestedDatabaseMutaction {
     UpsertNode(NestedUpsertNode),
     DeleteNode(NestedDeleteNode),
     Connect(NestedConnect),
+    ConnectOrCreate(NestedConnectOrCreate),
     Disconnect(NestedDisconnect),
     Set(NestedSet),
     UpdateNodes(NestedUpdateNodes),
@@ -65,12 +67,28 @@ pub struct NestedMutactions {
     pub upserts: Vec<NestedUpsertNode>,
     pub deletes: Vec<NestedDeleteNode>,
     pub connects: Vec<NestedConnect>,
+    pub connect_or_creates: Vec<NestedConnectOrCreate>,
     pub disconnects: Vec<NestedDisconnect>,
     pub sets: Vec<NestedSet>,
     pub update_manys: Vec<NestedUpdateNodes>,
     pub delete_manys: Vec<NestedDeleteNodes>,
 }
 
+impl NestedMutactions {
+    pub fn is_empty(&self) -> bool {
+        self.creates.is_empty()
+            && self.updates.is_empty()
+            && self.upserts.is_empty()
+            && self.deletes.is_empty()
+            && self.connects.is_empty()
+            && self.connect_or_creates.is_empty()
+            && self.disconnects.is_empty()
+            && self.sets.is_empty()
+            && self.update_manys.is_empty()
+            && self.delete_manys.is_empty()
+    }
+}
+
 // SET
 
 #[derive(Debug, Clone)]
@@ -88,6 +106,18 @@ pub struct NestedConnect {
     pub top_is_create: bool,
 }
 
+// CONNECT OR CREATE
+
+/// Connects the record matching `where_`, or creates a new one to connect if
+/// no record matches.
+#[derive(Debug, Clone)]
+pub struct NestedConnectOrCreate {
+    pub relation_field: RelationFieldRef,
+    pub where_: NodeSelector,
+    pub create: NestedCreateNode,
+    pub top_is_create: bool,
+}
+
 // DISCONNECT
 
 #[derive(Debug, Clone)]
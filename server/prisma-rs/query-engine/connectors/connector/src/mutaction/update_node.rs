@@ -7,7 +7,9 @@ pub struct UpdateNode {
     pub where_: NodeSelector,
     pub non_list_args: PrismaArgs,
     pub list_args: Vec<(String, PrismaListValue)>,
+    pub list_operations: Vec<(String, ScalarListOperation)>,
     pub nested_mutactions: NestedMutactions,
+    pub expected_version: Option<PrismaValue>,
 }
 
 #[derive(Debug, Clone)]
@@ -16,6 +18,7 @@ pub struct NestedUpdateNode {
     pub where_: Option<NodeSelector>,
     pub non_list_args: PrismaArgs,
     pub list_args: Vec<(String, PrismaListValue)>,
+    pub list_operations: Vec<(String, ScalarListOperation)>,
     pub nested_mutactions: NestedMutactions,
 }
 
@@ -25,6 +28,7 @@ pub struct UpdateNodes {
     pub filter: Filter,
     pub non_list_args: PrismaArgs,
     pub list_args: Vec<(String, PrismaListValue)>,
+    pub list_operations: Vec<(String, ScalarListOperation)>,
 }
 
 #[derive(Debug, Clone)]
@@ -33,4 +37,5 @@ pub struct NestedUpdateNodes {
     pub filter: Option<Filter>,
     pub non_list_args: PrismaArgs,
     pub list_args: Vec<(String, PrismaListValue)>,
+    pub list_operations: Vec<(String, ScalarListOperation)>,
 }
@@ -1,5 +1,6 @@
 use crate::filter::{Filter, NodeSelector};
 use prisma_models::*;
+use std::sync::Arc;
 
 #[derive(Debug, Default, Clone)]
 pub struct QueryArguments {
@@ -10,6 +11,18 @@ pub struct QueryArguments {
     pub last: Option<u32>,
     pub filter: Option<Filter>,
     pub order_by: Option<OrderBy>,
+    pub relevance: Option<Relevance>,
+    /// Soft-deleted records are left out unless asked for.
+    pub include_deleted: bool,
+}
+
+/// Orders the records by how well they match a full-text search, used instead
+/// of `order_by`.
+#[derive(Debug, Clone)]
+pub struct Relevance {
+    pub field: Arc<ScalarField>,
+    pub query: String,
+    pub sort_order: SortOrder,
 }
 
 impl QueryArguments {
@@ -1,10 +1,21 @@
 mod postgresql;
 mod sqlite;
 
-use crate::Transactional;
+use crate::{error::SqlError, SqlResult, Transactional};
+use connector::{deadline, ChangeFeed, ConnectorResult};
+use std::time::Duration;
+
 pub use postgresql::*;
 pub use sqlite::*;
 
+/// Time left for the current request, fails if the request timed out already
+fn time_left() -> SqlResult<Option<Duration>> {
+    match deadline::time_left() {
+        Some(left) if left == Duration::from_secs(0) => Err(SqlError::QueryTimeout),
+        left => Ok(left),
+    }
+}
+
 /// A wrapper for relational databases due to trait restrictions. Implements the
 /// needed traits.
 pub struct SqlDatabase<T>
@@ -22,3 +33,20 @@ where
         Self { executor }
     }
 }
+
+impl<T> ChangeFeed for SqlDatabase<T>
+where
+    T: Transactional + ChangeFeed,
+{
+    fn notify(&self, payload: &str) -> ConnectorResult<()> {
+        self.executor.notify(payload)
+    }
+
+    fn has_listeners(&self, model_name: &str) -> ConnectorResult<bool> {
+        self.executor.has_listeners(model_name)
+    }
+
+    fn listen(&self, models: &Fn() -> Vec<String>, f: &mut FnMut(String)) -> ConnectorResult<()> {
+        self.executor.listen(models, f)
+    }
+}
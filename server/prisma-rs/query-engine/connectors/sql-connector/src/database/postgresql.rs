@@ -1,32 +1,60 @@
 use crate::{
-    error::SqlError, MutationBuilder, RawQuery, SqlId, SqlResult, SqlRow, ToSqlRow, Transaction, Transactional,
+    error::SqlError,
+    expression::{self, Parameter, SqlFamily},
+    MutationBuilder, RawQuery, SqlId, SqlResult, SqlRow, ToSqlRow, Transaction, Transactional,
 };
 use chrono::{DateTime, NaiveDateTime, Utc};
-use connector::{error::*, ConnectorResult};
+use connector::{error::*, filter::NodeSelector, ChangeFeed, ConnectorResult};
 use native_tls::TlsConnector;
 use postgres::{
-    types::{FromSql, ToSql, Type as PostgresType},
+    fallible_iterator::FallibleIterator,
+    types::{FromSql, IsNull, Kind, ToSql, Type as PostgresType},
     Client, Config, Row as PostgresRow, Transaction as PostgresTransaction,
 };
 use prisma_common::config::{ConnectionLimit, ConnectionStringConfig, ExplicitConfig, PrismaDatabase};
-use prisma_models::{GraphqlId, PrismaValue, ProjectRef, TypeIdentifier};
+use prisma_models::{GraphqlId, PrismaValue, ProjectRef, Sequence, TypeIdentifier};
 use prisma_query::{
-    ast::{Query, Select},
+    ast::{Insert, Query, Select, Update},
     visitor::{self, Visitor},
 };
 use r2d2_postgres::PostgresConnectionManager;
 use rust_decimal::Decimal;
 use serde_json::{Map, Number, Value};
-use std::{convert::TryFrom, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    error::Error,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 use tokio_postgres::config::SslMode;
 use tokio_postgres_native_tls::MakeTlsConnector;
 use uuid::Uuid;
 
 type Pool = r2d2::Pool<PostgresConnectionManager<MakeTlsConnector>>;
 
+/// Longer identifiers are truncated by Postgres, `pg_notify` rejects longer channel names
+const MAX_IDENTIFIER_LENGTH: usize = 63;
+
+/// How often the change feed listener announces its models while no notifications arrive
+const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(1);
+
 /// The World's Most Advanced Open Source Relational Database
 pub struct PostgreSql {
     pool: Pool,
+
+    /// Kept around to open the dedicated change feed connection
+    config: Config,
+    tls: MakeTlsConnector,
+
+    /// The `NOTIFY` channel for mutation events, one per schema
+    channel: String,
+
+    /// When listeners were last looked up per model, and if there were any.
+    /// Listeners take up to `ANNOUNCE_INTERVAL` to be announced anyway, so
+    /// writes don't need to look them up more often.
+    listeners: Mutex<HashMap<String, (Instant, bool)>>,
 }
 
 impl TryFrom<&PrismaDatabase> for PostgreSql {
@@ -60,7 +88,7 @@ impl TryFrom<&ExplicitConfig> for PostgreSql {
             config.password(pw);
         }
 
-        Ok(Self::new(config, e.limit())?)
+        Ok(Self::new(config, e.limit(), e.schema.as_ref())?)
     }
 }
 
@@ -74,7 +102,7 @@ impl TryFrom<&ConnectionStringConfig> for PostgreSql {
         config.ssl_mode(SslMode::Prefer);
         config.dbname(db_name);
 
-        Ok(Self::new(config, s.limit())?)
+        Ok(Self::new(config, s.limit(), s.schema.as_ref())?)
     }
 }
 
@@ -85,6 +113,13 @@ impl Transactional for PostgreSql {
     {
         self.with_client(|client| {
             let mut tx = client.transaction()?;
+
+            // Reset by Postgres when the transaction ends
+            if let Some(left) = super::time_left()? {
+                let millis = left.as_secs() * 1000 + u64::from(left.subsec_millis());
+                tx.batch_execute(&format!("SET LOCAL statement_timeout = {}", millis.max(1)))?;
+            }
+
             let result = f(&mut tx);
 
             if result.is_ok() {
@@ -118,11 +153,113 @@ impl<'a> FromSql<'a> for SqlId {
     }
 }
 
+/// Values of native enum types, which `String` refuses to read. Text columns
+/// are accepted too, for enums stored the old way.
+struct EnumValue(String);
+
+impl<'a> FromSql<'a> for EnumValue {
+    fn from_sql(_: &PostgresType, raw: &'a [u8]) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(EnumValue(String::from_utf8(raw.to_vec())?))
+    }
+
+    fn accepts(ty: &PostgresType) -> bool {
+        match ty.kind() {
+            Kind::Enum(_) => true,
+            _ => <String as FromSql>::accepts(ty),
+        }
+    }
+}
+
+/// The SQL and parameters of a query, placeholders rendered for PostgreSQL.
+fn build<Q>(q: Q) -> SqlResult<(String, Vec<Parameter>)>
+where
+    Q: Into<Query>,
+{
+    let (sql, params) = visitor::Postgres::build(q);
+    expression::expand(SqlFamily::Postgres, &sql, params)
+}
+
+/// The values of expressions have no type of their own, they are converted to
+/// the type PostgreSQL inferred for the parameter.
+impl ToSql for Parameter {
+    fn to_sql(&self, ty: &PostgresType, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        match self {
+            Parameter::Query(value) => value.to_sql_checked(ty, out),
+            Parameter::Value(value) => value_to_sql(value, ty, out),
+        }
+    }
+
+    fn accepts(_: &PostgresType) -> bool {
+        true
+    }
+
+    fn to_sql_checked(&self, ty: &PostgresType, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.to_sql(ty, out)
+    }
+}
+
+fn value_to_sql(
+    value: &PrismaValue,
+    ty: &PostgresType,
+    out: &mut Vec<u8>,
+) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+    match (value, ty.kind()) {
+        (PrismaValue::Enum(s), Kind::Enum(_)) | (PrismaValue::String(s), Kind::Enum(_)) => {
+            out.extend_from_slice(s.as_bytes());
+            return Ok(IsNull::No);
+        }
+        _ => (),
+    }
+
+    match (value, ty) {
+        (PrismaValue::Null, _) | (PrismaValue::List(None), _) => Ok(IsNull::Yes),
+        (PrismaValue::List(Some(values)), _) => {
+            let values: Vec<Parameter> = values.iter().cloned().map(Parameter::Value).collect();
+            values.to_sql_checked(ty, out)
+        }
+        (PrismaValue::String(s), _) | (PrismaValue::Enum(s), _) => s.to_sql_checked(ty, out),
+        (PrismaValue::Int(i), _) | (PrismaValue::BigInt(i), _) => int_to_sql(*i, ty, out),
+        (PrismaValue::Relation(i), _) => int_to_sql(i64::try_from(*i)?, ty, out),
+        (PrismaValue::Float(f), &PostgresType::FLOAT4) => (*f as f32).to_sql_checked(ty, out),
+        (PrismaValue::Float(f), &PostgresType::NUMERIC) => {
+            let decimal = Decimal::from_str(&f.to_string()).map_err(|e| format!("{:?}", e))?;
+            decimal.to_sql_checked(ty, out)
+        }
+        (PrismaValue::Float(f), _) => f.to_sql_checked(ty, out),
+        (PrismaValue::Decimal(d), &PostgresType::FLOAT8) => f64::from_str(&d.to_string())?.to_sql_checked(ty, out),
+        (PrismaValue::Decimal(d), _) => d.to_sql_checked(ty, out),
+        (PrismaValue::Boolean(b), _) => b.to_sql_checked(ty, out),
+        (PrismaValue::DateTime(d), &PostgresType::TIMESTAMPTZ) => d.to_sql_checked(ty, out),
+        (PrismaValue::DateTime(d), _) => d.naive_utc().to_sql_checked(ty, out),
+        (PrismaValue::Json(j), &PostgresType::JSON) | (PrismaValue::Json(j), &PostgresType::JSONB) => {
+            j.to_sql_checked(ty, out)
+        }
+        (PrismaValue::Json(j), _) => j.to_string().to_sql_checked(ty, out),
+        (PrismaValue::Uuid(u), &PostgresType::UUID) => u.to_sql_checked(ty, out),
+        (PrismaValue::Uuid(u), _) => u.to_hyphenated().to_string().to_sql_checked(ty, out),
+        (PrismaValue::Bytes(b), _) => b.to_sql_checked(ty, out),
+        (PrismaValue::GraphqlId(GraphqlId::Int(i)), _) => int_to_sql(i64::try_from(*i)?, ty, out),
+        (PrismaValue::GraphqlId(GraphqlId::String(s)), _) => s.to_sql_checked(ty, out),
+        (PrismaValue::GraphqlId(GraphqlId::UUID(u)), &PostgresType::UUID) => u.to_sql_checked(ty, out),
+        (PrismaValue::GraphqlId(GraphqlId::UUID(u)), _) => u.to_hyphenated().to_string().to_sql_checked(ty, out),
+    }
+}
+
+fn int_to_sql(i: i64, ty: &PostgresType, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+    match *ty {
+        PostgresType::INT2 => i16::try_from(i)?.to_sql_checked(ty, out),
+        PostgresType::INT4 => i32::try_from(i)?.to_sql_checked(ty, out),
+        PostgresType::FLOAT8 => (i as f64).to_sql_checked(ty, out),
+        PostgresType::NUMERIC => Decimal::from(i).to_sql_checked(ty, out),
+        _ => i.to_sql_checked(ty, out),
+    }
+}
+
 impl<'a> Transaction for PostgresTransaction<'a> {
     fn write(&mut self, q: Query) -> SqlResult<Option<GraphqlId>> {
         let id = match q {
             insert @ Query::Insert(_) => {
-                let (sql, params) = dbg!(visitor::Postgres::build(insert));
+                let (sql, params) = build(insert)?;
 
                 let params: Vec<&ToSql> = params.iter().map(|pv| pv as &ToSql).collect();
                 let stmt = self.prepare(&sql)?;
@@ -134,7 +271,7 @@ impl<'a> Transaction for PostgresTransaction<'a> {
                 })
             }
             query => {
-                let (sql, params) = dbg!(visitor::Postgres::build(query));
+                let (sql, params) = build(query)?;
                 let params: Vec<&ToSql> = params.iter().map(|pv| pv as &ToSql).collect();
 
                 let stmt = self.prepare(&sql)?;
@@ -147,8 +284,18 @@ impl<'a> Transaction for PostgresTransaction<'a> {
         Ok(id)
     }
 
+    fn write_count(&mut self, q: Query) -> SqlResult<usize> {
+        let (sql, params) = build(q)?;
+        let params: Vec<&ToSql> = params.iter().map(|pv| pv as &ToSql).collect();
+
+        let stmt = self.prepare(&sql)?;
+        let changes = self.execute(&stmt, params.as_slice())?;
+
+        Ok(changes as usize)
+    }
+
     fn filter(&mut self, q: Select, idents: &[TypeIdentifier]) -> SqlResult<Vec<SqlRow>> {
-        let (sql, params) = dbg!(visitor::Postgres::build(q));
+        let (sql, params) = build(q)?;
         let params: Vec<&ToSql> = params.iter().map(|pv| pv as &ToSql).collect();
 
         let stmt = self.prepare(&sql)?;
@@ -172,8 +319,46 @@ impl<'a> Transaction for PostgresTransaction<'a> {
         Ok(())
     }
 
+    fn next_sequence_value(&mut self, db_name: &str, sequence: &Sequence) -> SqlResult<i64> {
+        let quote = |identifier: &str| format!(r#""{}""#, identifier.replace('"', r#""""#));
+        let name = format!("{}.{}", quote(db_name), quote(&sequence.name));
+
+        let stmt = self.prepare("SELECT nextval($1::text::regclass)")?;
+        let rows = self.query(&stmt, &[&name])?;
+
+        // UNWRAP: `nextval` always returns one row or fails.
+        Ok(rows.into_iter().next().unwrap().get(0))
+    }
+
+    fn parameter_limit(&self) -> usize {
+        // Parameters are numbered with 16 bits in the wire protocol.
+        65535
+    }
+
+    fn upsert(&mut self, node_selector: &NodeSelector, insert: Insert, update: Update) -> SqlResult<(GraphqlId, bool)> {
+        let columns: Vec<&str> = node_selector.fields.iter().map(|(field, _)| field.db_name()).collect();
+        let (sql, params) =
+            expression::on_conflict_update(SqlFamily::Postgres, build(insert)?, &columns, build(update)?)?;
+
+        // Records existing before the statement have `xmax` set by the update.
+        let id_column = expression::quote(node_selector.model().fields().id().db_name());
+        let sql = format!("{} RETURNING {}, (xmax = 0)", sql, id_column);
+
+        let params: Vec<&ToSql> = params.iter().map(|pv| pv as &ToSql).collect();
+        let stmt = self.prepare(&sql)?;
+        let rows = self.query(&stmt, params.as_slice())?;
+
+        match rows.into_iter().next() {
+            Some(row) => {
+                let id: SqlId = row.get(0);
+                Ok((GraphqlId::from(id), row.get(1)))
+            }
+            None => Err(SqlError::NodeNotFoundForWhere(NodeSelectorInfo::from(node_selector))),
+        }
+    }
+
     fn raw(&mut self, q: RawQuery) -> SqlResult<Value> {
-        let stmt = self.prepare(dbg!(&q.0))?;
+        let stmt = self.prepare(&q.0)?;
 
         if q.is_select() {
             let rows = self.query(&stmt, &[])?;
@@ -363,6 +548,10 @@ impl ToSqlRow for PostgresRow {
         T: IntoIterator<Item = &'b TypeIdentifier>,
     {
         fn convert(row: &PostgresRow, i: usize, typid: &TypeIdentifier) -> SqlResult<PrismaValue> {
+            if let Kind::Array(_) = row.columns()[i].type_().kind() {
+                return convert_array(row, i, typid);
+            }
+
             let result = match typid {
                 TypeIdentifier::String => match row.try_get(i)? {
                     Some(val) => PrismaValue::String(val),
@@ -406,12 +595,24 @@ impl ToSqlRow for PostgresRow {
                     },
                     _ => PrismaValue::Int(row.try_get(i)?),
                 },
+                TypeIdentifier::BigInt => match row.try_get(i)? {
+                    Some(val) => PrismaValue::BigInt(val),
+                    None => PrismaValue::Null,
+                },
+                TypeIdentifier::Decimal => match row.try_get(i)? {
+                    Some(val) => PrismaValue::Decimal(val),
+                    None => PrismaValue::Null,
+                },
+                TypeIdentifier::Bytes => match row.try_get(i)? {
+                    Some(val) => PrismaValue::Bytes(val),
+                    None => PrismaValue::Null,
+                },
                 TypeIdentifier::Boolean => match row.try_get(i)? {
                     Some(val) => PrismaValue::Boolean(val),
                     None => PrismaValue::Null,
                 },
                 TypeIdentifier::Enum => match row.try_get(i)? {
-                    Some(val) => PrismaValue::Enum(val),
+                    Some(EnumValue(val)) => PrismaValue::Enum(val),
                     None => PrismaValue::Null,
                 },
                 TypeIdentifier::Json => match row.try_get(i)? {
@@ -437,6 +638,43 @@ impl ToSqlRow for PostgresRow {
             Ok(result)
         }
 
+        /// Lists with `ScalarListStrategy::Native`
+        fn convert_array(row: &PostgresRow, i: usize, typid: &TypeIdentifier) -> SqlResult<PrismaValue> {
+            fn list<'a, T, F>(row: &'a PostgresRow, i: usize, f: F) -> SqlResult<PrismaValue>
+            where
+                T: FromSql<'a>,
+                F: Fn(T) -> PrismaValue,
+            {
+                let values: Option<Vec<T>> = row.try_get(i)?;
+                Ok(values.map_or(PrismaValue::Null, |v| {
+                    PrismaValue::List(Some(v.into_iter().map(f).collect()))
+                }))
+            }
+
+            match typid {
+                TypeIdentifier::String => list(row, i, PrismaValue::String),
+                TypeIdentifier::Enum => list(row, i, |EnumValue(val)| PrismaValue::Enum(val)),
+                TypeIdentifier::Int => match *row.columns()[i].type_() {
+                    PostgresType::INT8_ARRAY => list(row, i, PrismaValue::Int),
+                    _ => list(row, i, |val: i32| PrismaValue::Int(val as i64)),
+                },
+                TypeIdentifier::BigInt => list(row, i, PrismaValue::BigInt),
+                TypeIdentifier::Float => list(row, i, PrismaValue::Float),
+                TypeIdentifier::Decimal => list(row, i, PrismaValue::Decimal),
+                TypeIdentifier::Boolean => list(row, i, PrismaValue::Boolean),
+                TypeIdentifier::Json => list(row, i, PrismaValue::Json),
+                TypeIdentifier::UUID => list(row, i, PrismaValue::Uuid),
+                TypeIdentifier::Bytes => list(row, i, PrismaValue::Bytes),
+                TypeIdentifier::DateTime => list(row, i, |ts: NaiveDateTime| {
+                    PrismaValue::DateTime(DateTime::<Utc>::from_utc(ts, Utc))
+                }),
+                typid => Err(SqlError::ColumnReadFailure(failure::err_msg(format!(
+                    "Lists of {:?} can't be read from an array column",
+                    typid
+                )))),
+            }
+        }
+
         let mut row = SqlRow::default();
 
         for (i, typid) in idents.into_iter().enumerate() {
@@ -447,24 +685,139 @@ impl ToSqlRow for PostgresRow {
     }
 }
 
+impl ChangeFeed for PostgreSql {
+    fn notify(&self, payload: &str) -> ConnectorResult<()> {
+        self.with_client(|client| {
+            let stmt = client.prepare("SELECT pg_notify($1, $2)")?;
+            client.execute(&stmt, &[&self.channel, &payload])?;
+
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    fn has_listeners(&self, model_name: &str) -> ConnectorResult<bool> {
+        if let Some((looked_up, has_listeners)) = self.listeners.lock().unwrap().get(model_name) {
+            if looked_up.elapsed() < ANNOUNCE_INTERVAL {
+                return Ok(*has_listeners);
+            }
+        }
+
+        let has_listeners = self.with_client(|client| {
+            let stmt = client.prepare(
+                "SELECT EXISTS (SELECT 1 FROM pg_locks WHERE locktype = 'advisory' AND objsubid = 2 \
+                 AND classid = hashtext($1)::oid AND objid = hashtext($2)::oid)",
+            )?;
+            let rows = client.query(&stmt, &[&self.channel, &model_name])?;
+
+            // UNWRAP: `EXISTS` always returns one row.
+            Ok(rows.into_iter().next().unwrap().get(0))
+        })?;
+
+        self.listeners
+            .lock()
+            .unwrap()
+            .insert(model_name.to_string(), (Instant::now(), has_listeners));
+
+        Ok(has_listeners)
+    }
+
+    fn listen(&self, models: &Fn() -> Vec<String>, f: &mut FnMut(String)) -> ConnectorResult<()> {
+        Ok(self.listen_internal(models, f)?)
+    }
+}
+
 impl PostgreSql {
-    fn new(config: Config, connections: u32) -> SqlResult<PostgreSql> {
+    fn new(config: Config, connections: u32, schema: Option<&String>) -> SqlResult<PostgreSql> {
         let mut tls_builder = TlsConnector::builder();
         tls_builder.danger_accept_invalid_certs(true); // For Heroku
 
         let tls = MakeTlsConnector::new(tls_builder.build()?);
 
-        let manager = PostgresConnectionManager::new(config, tls);
+        let manager = PostgresConnectionManager::new(config.clone(), tls.clone());
         let pool = r2d2::Pool::builder().max_size(connections).build(manager)?;
+        let channel = Self::channel_name(schema.map(|s| s.as_str()).unwrap_or("default"));
+
+        Ok(PostgreSql {
+            pool,
+            config,
+            tls,
+            channel,
+            listeners: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Schema names can already use up the identifier length, a hash of the
+    /// schema keeps the channel names of long schemas apart.
+    fn channel_name(schema: &str) -> String {
+        let channel = format!("prisma_{}", schema);
+
+        if channel.len() <= MAX_IDENTIFIER_LENGTH {
+            return channel;
+        }
+
+        // FNV-1a, all engines must agree on the name, whatever they were built with.
+        let hash = schema.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+
+        format!("prisma_{:016x}", hash)
+    }
+
+    fn quote_identifier(identifier: &str) -> String {
+        format!(r#""{}""#, identifier.replace('"', r#""""#))
+    }
+
+    /// Notifications are only delivered to the session that ran `LISTEN`,
+    /// so the listener gets its own connection outside of the pool.
+    fn listen_internal(&self, models: &Fn() -> Vec<String>, f: &mut FnMut(String)) -> SqlResult<()> {
+        let mut client = self.config.connect(self.tls.clone())?;
+        client.batch_execute(&format!("LISTEN {}", Self::quote_identifier(&self.channel)))?;
+
+        let mut announced = HashSet::new();
 
-        Ok(PostgreSql { pool })
+        loop {
+            self.announce(&mut client, &mut announced, models())?;
+
+            let notification = client.notifications().timeout_iter(ANNOUNCE_INTERVAL).next()?;
+
+            if let Some(notification) = notification {
+                f(notification.payload().to_string());
+            }
+        }
+    }
+
+    /// Writers look for the shared advisory locks of a model to find out if
+    /// anyone listens for its events. The locks belong to the listening
+    /// session, so they go away with the engine.
+    fn announce(&self, client: &mut Client, announced: &mut HashSet<String>, models: Vec<String>) -> SqlResult<()> {
+        let models: HashSet<String> = models.into_iter().collect();
+
+        for model in models.difference(announced) {
+            let stmt = client.prepare("SELECT pg_advisory_lock_shared(hashtext($1), hashtext($2))")?;
+            client.execute(&stmt, &[&self.channel, model])?;
+        }
+
+        for model in announced.difference(&models) {
+            let stmt = client.prepare("SELECT pg_advisory_unlock_shared(hashtext($1), hashtext($2))")?;
+            client.execute(&stmt, &[&self.channel, model])?;
+        }
+
+        *announced = models;
+
+        Ok(())
     }
 
     fn with_client<F, T>(&self, f: F) -> SqlResult<T>
     where
         F: FnOnce(&mut Client) -> SqlResult<T>,
     {
-        let mut client = self.pool.get()?;
+        let mut client = match super::time_left()? {
+            Some(left) => self.pool.get_timeout(left).map_err(|_| SqlError::QueryTimeout)?,
+            None => self.pool.get()?,
+        };
+
         let result = f(&mut client);
         result
     }
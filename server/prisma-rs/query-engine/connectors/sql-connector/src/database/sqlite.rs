@@ -1,17 +1,22 @@
-use crate::{MutationBuilder, RawQuery, SqlId, SqlResult, SqlRow, ToSqlRow, Transaction, Transactional};
+use crate::{
+    error::SqlError,
+    expression::{self, Parameter, SqlFamily},
+    MutationBuilder, RawQuery, SqlId, SqlResult, SqlRow, ToSqlRow, Transaction, Transactional,
+};
 use chrono::{DateTime, Utc};
-use prisma_models::{GraphqlId, PrismaValue, ProjectRef, TypeIdentifier};
+use connector::filter::NodeSelector;
+use prisma_models::{Decimal, GraphqlId, PrismaValue, ProjectRef, Sequence, TypeIdentifier};
 use prisma_query::{
-    ast::{Query, Select},
+    ast::{Insert, Query, Select, Update},
     visitor::{self, Visitor},
 };
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{
-    types::{FromSql, FromSqlResult, Type as SqliteType, ValueRef},
+    types::{FromSql, FromSqlResult, ToSql, ToSqlOutput, Type as SqliteType, Value as SqliteValue, ValueRef},
     Connection, Error as SqliteError, Row as SqliteRow, Transaction as SqliteTransaction, NO_PARAMS,
 };
 use serde_json::{Map, Number, Value};
-use std::collections::HashSet;
+use std::{collections::HashSet, str::FromStr, sync::mpsc, thread, time::Duration};
 use uuid::Uuid;
 
 type Pool = r2d2::Pool<SqliteConnectionManager>;
@@ -30,6 +35,11 @@ impl Transactional for Sqlite {
         F: FnOnce(&mut Transaction) -> SqlResult<T>,
     {
         self.with_connection(db, |ref mut conn| {
+            let watchdog = match super::time_left()? {
+                Some(left) => Some(Watchdog::start(conn, left)),
+                None => None,
+            };
+
             let mut tx = conn.transaction()?;
             tx.set_prepared_statement_cache_capacity(65536);
 
@@ -39,14 +49,51 @@ impl Transactional for Sqlite {
                 tx.commit()?;
             }
 
+            drop(watchdog);
             result
         })
     }
 }
 
+/// Interrupts the queries of a connection once the request timeout is over
+struct Watchdog {
+    done: Option<mpsc::Sender<()>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Watchdog {
+    fn start(conn: &Connection, timeout: Duration) -> Self {
+        let handle = conn.get_interrupt_handle();
+        let (done, receiver) = mpsc::channel::<()>();
+
+        let thread = thread::spawn(move || {
+            if let Err(mpsc::RecvTimeoutError::Timeout) = receiver.recv_timeout(timeout) {
+                handle.interrupt();
+            }
+        });
+
+        Self {
+            done: Some(done),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        // Disconnecting the channel stops the thread, waiting for it makes sure
+        // no interrupt hits the next user of the connection.
+        self.done.take();
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
 impl<'a> Transaction for SqliteTransaction<'a> {
     fn write(&mut self, q: Query) -> SqlResult<Option<GraphqlId>> {
-        let (sql, params) = dbg!(visitor::Sqlite::build(q));
+        let (sql, params) = build(q)?;
 
         let mut stmt = self.prepare_cached(&sql)?;
         stmt.execute(params)?;
@@ -54,8 +101,17 @@ impl<'a> Transaction for SqliteTransaction<'a> {
         Ok(Some(GraphqlId::Int(self.last_insert_rowid() as usize)))
     }
 
+    fn write_count(&mut self, q: Query) -> SqlResult<usize> {
+        let (sql, params) = build(q)?;
+
+        let mut stmt = self.prepare_cached(&sql)?;
+        let changes = stmt.execute(params)?;
+
+        Ok(changes)
+    }
+
     fn filter(&mut self, q: Select, idents: &[TypeIdentifier]) -> SqlResult<Vec<SqlRow>> {
-        let (sql, params) = dbg!(visitor::Sqlite::build(q));
+        let (sql, params) = build(q)?;
 
         let mut stmt = self.prepare_cached(&sql)?;
         let mut rows = stmt.query(params)?;
@@ -80,6 +136,38 @@ impl<'a> Transaction for SqliteTransaction<'a> {
         Ok(())
     }
 
+    fn next_sequence_value(&mut self, _: &str, sequence: &Sequence) -> SqlResult<i64> {
+        let message = format!("SQLite has no sequences, can't read from {}", sequence.name);
+        Err(SqlError::QueryError(failure::err_msg(message)))
+    }
+
+    fn parameter_limit(&self) -> usize {
+        // `SQLITE_MAX_VARIABLE_NUMBER` of SQLite versions before 3.32.
+        999
+    }
+
+    fn upsert(&mut self, node_selector: &NodeSelector, insert: Insert, update: Update) -> SqlResult<(GraphqlId, bool)> {
+        // Without `RETURNING` the existing record is read first. Another
+        // transaction writing in between makes the upsert fail as busy.
+        let existing = match self.find_id(node_selector) {
+            Ok(id) => Some(id),
+            Err(SqlError::NodeNotFoundForWhere(_)) => None,
+            Err(e) => return Err(e),
+        };
+
+        let columns: Vec<&str> = node_selector.fields.iter().map(|(field, _)| field.db_name()).collect();
+        let (sql, params) =
+            expression::on_conflict_update(SqlFamily::Sqlite, build(insert)?, &columns, build(update)?)?;
+
+        let mut stmt = self.prepare_cached(&sql)?;
+        stmt.execute(params)?;
+
+        match existing {
+            Some(id) => Ok((id, false)),
+            None => Ok((GraphqlId::Int(self.last_insert_rowid() as usize), true)),
+        }
+    }
+
     fn raw(&mut self, q: RawQuery) -> SqlResult<Value> {
         let columns: Vec<String> = self
             .prepare_cached(&q.0)?
@@ -122,6 +210,49 @@ impl<'a> Transaction for SqliteTransaction<'a> {
     }
 }
 
+/// The SQL and parameters of a query, placeholders rendered for SQLite.
+fn build<Q>(q: Q) -> SqlResult<(String, Vec<Parameter>)>
+where
+    Q: Into<Query>,
+{
+    let (sql, params) = visitor::Sqlite::build(q);
+    expression::expand(SqlFamily::Sqlite, &sql, params)
+}
+
+/// Values are stored like the visitor stores them: date times as milliseconds
+/// since the epoch, ids and UUIDs as text. SQLite has no arrays.
+impl ToSql for Parameter {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput> {
+        let value = match self {
+            Parameter::Query(value) => return value.to_sql(),
+            Parameter::Value(value) => value,
+        };
+
+        let value = match value {
+            PrismaValue::Null | PrismaValue::List(None) => SqliteValue::Null,
+            PrismaValue::String(s) | PrismaValue::Enum(s) => SqliteValue::Text(s.clone()),
+            PrismaValue::Int(i) | PrismaValue::BigInt(i) => SqliteValue::Integer(*i),
+            PrismaValue::Relation(i) => SqliteValue::Integer(*i as i64),
+            PrismaValue::Float(f) => SqliteValue::Real(*f),
+            PrismaValue::Decimal(d) => SqliteValue::Text(d.to_string()),
+            PrismaValue::Boolean(b) => SqliteValue::Integer(*b as i64),
+            PrismaValue::DateTime(d) => SqliteValue::Integer(d.timestamp_millis()),
+            PrismaValue::Json(j) => SqliteValue::Text(j.to_string()),
+            PrismaValue::Uuid(u) => SqliteValue::Text(u.to_hyphenated().to_string()),
+            PrismaValue::Bytes(b) => SqliteValue::Blob(b.clone()),
+            PrismaValue::GraphqlId(GraphqlId::Int(i)) => SqliteValue::Integer(*i as i64),
+            PrismaValue::GraphqlId(GraphqlId::String(s)) => SqliteValue::Text(s.clone()),
+            PrismaValue::GraphqlId(GraphqlId::UUID(u)) => SqliteValue::Text(u.to_hyphenated().to_string()),
+            PrismaValue::List(Some(_)) => {
+                let message = "SQLite has no array columns, lists can't be bound as one value";
+                return Err(SqliteError::ToSqlConversionFailure(message.into()));
+            }
+        };
+
+        Ok(ToSqlOutput::Owned(value))
+    }
+}
+
 impl FromSql for SqlId {
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         value
@@ -151,6 +282,22 @@ impl<'a, 'stmt> ToSqlRow for SqliteRow<'a, 'stmt> {
                 }),
                 TypeIdentifier::Float => row.get_checked(i).map(|val| PrismaValue::Float(val)),
                 TypeIdentifier::Int => row.get_checked(i).map(|val| PrismaValue::Int(val)),
+                TypeIdentifier::BigInt => row.get_checked(i).map(|val| PrismaValue::BigInt(val)),
+                TypeIdentifier::Decimal => row.get_checked(i).and_then(|val| {
+                    // `NUMERIC` keeps text that would lose precision as a real
+                    let text = match val {
+                        SqliteValue::Integer(int) => int.to_string(),
+                        SqliteValue::Real(real) => real.to_string(),
+                        SqliteValue::Text(text) => text,
+                        SqliteValue::Null => return Err(SqliteError::InvalidColumnType(i, SqliteType::Null)),
+                        SqliteValue::Blob(_) => return Err(SqliteError::InvalidColumnType(i, SqliteType::Blob)),
+                    };
+
+                    Decimal::from_str(&text).map(PrismaValue::Decimal).map_err(|err| {
+                        SqliteError::FromSqlConversionFailure(i as usize, SqliteType::Text, Box::new(err))
+                    })
+                }),
+                TypeIdentifier::Bytes => row.get_checked(i).map(|val| PrismaValue::Bytes(val)),
                 TypeIdentifier::Boolean => row.get_checked(i).map(|val| PrismaValue::Boolean(val)),
                 TypeIdentifier::Enum => row.get_checked(i).map(|val| PrismaValue::Enum(val)),
                 TypeIdentifier::Json => row.get_checked(i).and_then(|val| {
@@ -241,7 +388,11 @@ impl Sqlite {
     where
         F: FnOnce(&mut Connection) -> SqlResult<T>,
     {
-        let mut conn = self.pool.get()?;
+        let mut conn = match super::time_left()? {
+            Some(left) => self.pool.get_timeout(left).map_err(|_| SqlError::QueryTimeout)?,
+            None => self.pool.get()?,
+        };
+
         self.attach_database(&mut conn, db)?;
 
         let result = f(&mut conn);
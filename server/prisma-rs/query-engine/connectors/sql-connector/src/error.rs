@@ -1,6 +1,6 @@
 use connector::error::*;
 use failure::{Error, Fail};
-use prisma_models::prelude::DomainError;
+use prisma_models::prelude::{DomainError, PrismaValue};
 
 #[cfg(feature = "sqlite")]
 use rusqlite;
@@ -34,12 +34,21 @@ pub enum SqlError {
     #[fail(display = "Field cannot be null: {}", field)]
     FieldCannotBeNull { field: String },
 
+    #[fail(display = "Field is maintained by the database and can't be set: {}", field)]
+    FieldIsReadOnly { field: String },
+
     #[fail(display = "{}", _0)]
     DomainError(DomainError),
 
     #[fail(display = "Node not found: {}", _0)]
     NodeNotFoundForWhere(NodeSelectorInfo),
 
+    #[fail(display = "Version conflict, expected version {} for {}", expected, record)]
+    VersionConflict {
+        record: NodeSelectorInfo,
+        expected: PrismaValue,
+    },
+
     #[fail(
         display = "Violating a relation {} between {} and {}",
         relation_name, model_a_name, model_b_name
@@ -67,6 +76,9 @@ pub enum SqlError {
 
     #[fail(display = "Database creation error: {}", _0)]
     DatabaseCreationError(&'static str),
+
+    #[fail(display = "The query did not finish within the request timeout.")]
+    QueryTimeout,
 }
 
 impl From<SqlError> for ConnectorError {
@@ -81,8 +93,10 @@ impl From<SqlError> for ConnectorError {
             SqlError::InvalidConnectionArguments => ConnectorError::InvalidConnectionArguments,
             SqlError::ColumnReadFailure(e) => ConnectorError::ColumnReadFailure(e),
             SqlError::FieldCannotBeNull { field } => ConnectorError::FieldCannotBeNull { field },
+            SqlError::FieldIsReadOnly { field } => ConnectorError::FieldIsReadOnly { field },
             SqlError::DomainError(e) => ConnectorError::DomainError(e),
             SqlError::NodeNotFoundForWhere(info) => ConnectorError::NodeNotFoundForWhere(info),
+            SqlError::VersionConflict { record, expected } => ConnectorError::VersionConflict { record, expected },
             SqlError::RelationViolation {
                 relation_name,
                 model_a_name,
@@ -108,6 +122,7 @@ impl From<SqlError> for ConnectorError {
             SqlError::ConversionError(e) => ConnectorError::ConversionError(e),
             SqlError::DatabaseCreationError(e) => ConnectorError::DatabaseCreationError(e),
             SqlError::QueryError(e) => ConnectorError::QueryError(e),
+            SqlError::QueryTimeout => ConnectorError::QueryTimeout,
         }
     }
 }
@@ -164,6 +179,15 @@ impl From<rusqlite::Error> for SqlError {
                 }
             }
 
+            // Interrupted by the request timeout
+            rusqlite::Error::SqliteFailure(
+                ffi::Error {
+                    code: ffi::ErrorCode::OperationInterrupted,
+                    extended_code: _,
+                },
+                _,
+            ) => SqlError::QueryTimeout,
+
             e => SqlError::QueryError(e.into()),
         }
     }
@@ -197,6 +221,8 @@ impl From<tokio_postgres::error::Error> for SqlError {
                     field_name: format!("{}.{}", table, field),
                 }
             }
+            // query_canceled, raised by `statement_timeout`
+            Some("57014") => SqlError::QueryTimeout,
             _ => SqlError::QueryError(e.into()),
         }
     }
@@ -0,0 +1,757 @@
+use crate::{error::SqlError, SqlResult};
+use connector::filter::JsonCondition;
+use prisma_models::{
+    placeholder_column, placeholder_payload, Model, NumericOperation, PrismaValue, ScalarField, ScalarListOperation,
+    VALUE_PLACEHOLDER,
+};
+use prisma_query::ast::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The placeholder kind of a `SqlExpression`.
+const EXPRESSION_PLACEHOLDER: &str = "expr";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SqlFamily {
+    Postgres,
+    Sqlite,
+}
+
+/// A column in an expression, qualified with an alias or with the database and
+/// table names.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnRef {
+    table: Vec<String>,
+    name: String,
+}
+
+impl ColumnRef {
+    pub fn new(field: &ScalarField, alias: Option<String>) -> Self {
+        let table = match alias {
+            Some(alias) => vec![alias],
+            None => {
+                let model = field.model();
+                let db_name = model.internal_data_model().db_name.clone();
+
+                vec![db_name, model.db_name().to_string()]
+            }
+        };
+
+        Self {
+            table,
+            name: field.db_name().to_string(),
+        }
+    }
+
+    /// The column by its name only, for statements changing a single table.
+    pub fn unqualified(field: &ScalarField) -> Self {
+        Self {
+            table: Vec::new(),
+            name: field.db_name().to_string(),
+        }
+    }
+
+    fn render(&self) -> String {
+        quote_path(self.table.iter().chain(Some(&self.name)))
+    }
+
+    /// The `rowid` of the column's table, SQLite only.
+    fn row_id(&self) -> String {
+        match self.table.len() {
+            0 => String::from("rowid"),
+            _ => format!("{}.rowid", quote_path(self.table.iter())),
+        }
+    }
+}
+
+/// The database and the name of the full-text index of a model.
+pub fn search_index(model: &Model) -> Vec<String> {
+    let db_name = model.internal_data_model().db_name.clone();
+    vec![db_name, model.search_index_name()]
+}
+
+/// SQL the query AST has no words for. An expression goes into the AST as a
+/// placeholder column and is rendered for the dialect with the statement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SqlExpression {
+    /// A string column containing, starting or ending with the value. `LIKE`
+    /// with the backslash as escape character, `ILIKE` for insensitive matches
+    /// on PostgreSQL. SQLite's `LIKE` ignores the case, so sensitive matches
+    /// use `GLOB` there.
+    Like {
+        column: ColumnRef,
+        value: String,
+        position: LikePosition,
+        insensitive: bool,
+    },
+
+    /// String equality ignoring the case.
+    InsensitiveEquals { column: ColumnRef, value: PrismaValue },
+
+    /// Full-text search with a plain text query, matching records having all
+    /// of its words. `to_tsvector(..) @@ plainto_tsquery(..)` on PostgreSQL, a
+    /// lookup from the FTS5 index table on SQLite.
+    Search {
+        column: ColumnRef,
+        index: Vec<String>,
+        query: String,
+    },
+
+    /// How well a record matches a full-text search, higher is better. `NULL`
+    /// on SQLite for records not matching at all.
+    Rank {
+        column: ColumnRef,
+        index: Vec<String>,
+        query: String,
+    },
+
+    /// Whether an array column has all of the values (`@>`) or only some of
+    /// them (`&&`). PostgreSQL only, SQLite has no array columns.
+    ArrayContains {
+        column: ColumnRef,
+        values: Vec<PrismaValue>,
+        every: bool,
+    },
+
+    /// An array column that is `NULL` or has no elements. PostgreSQL only.
+    ArrayIsEmpty { column: ColumnRef },
+
+    /// A condition on the value at a path inside a Json column, which holds
+    /// the JSON as text. `#>` and `@>` on PostgreSQL, `json_extract`,
+    /// `json_type` and `json_each` on SQLite.
+    JsonPath {
+        column: ColumnRef,
+        path: Vec<String>,
+        condition: JsonCondition,
+    },
+
+    /// The current value of a numeric column changed by the operation, for
+    /// the `SET` of an update.
+    Arithmetic {
+        column: ColumnRef,
+        operation: NumericOperation,
+    },
+
+    /// The current value of an array column changed by the list operation,
+    /// for the `SET` of an update. PostgreSQL only.
+    ArrayUpdate {
+        column: ColumnRef,
+        operation: ScalarListOperation,
+    },
+}
+
+impl SqlExpression {
+    /// The placeholder, to use where the AST takes a column or a value.
+    pub fn into_column(self) -> Column {
+        placeholder_column(EXPRESSION_PLACEHOLDER, &self)
+    }
+
+    /// A condition holding when the expression is true.
+    pub fn into_condition(self) -> ConditionTree {
+        ConditionTree::single(self.into_column().equals(true))
+    }
+
+    fn render(&self, params: &mut Params) -> String {
+        match (self, params.family) {
+            (
+                SqlExpression::Like {
+                    column,
+                    value,
+                    position,
+                    insensitive: true,
+                },
+                SqlFamily::Sqlite,
+            ) => format!(
+                r"{} LIKE {} ESCAPE '\'",
+                column.render(),
+                params.bind(position.pattern("%", &escape_like(value)))
+            ),
+            (
+                SqlExpression::Like {
+                    column,
+                    value,
+                    position,
+                    insensitive: false,
+                },
+                SqlFamily::Sqlite,
+            ) => format!(
+                "{} GLOB {}",
+                column.render(),
+                params.bind(position.pattern("*", &escape_glob(value)))
+            ),
+            (
+                SqlExpression::Like {
+                    column,
+                    value,
+                    position,
+                    insensitive,
+                },
+                SqlFamily::Postgres,
+            ) => {
+                let operator = if *insensitive { "ILIKE" } else { "LIKE" };
+                let pattern = params.bind(position.pattern("%", &escape_like(value)));
+
+                format!(r"{} {} {} ESCAPE '\'", column.render(), operator, pattern)
+            }
+            (SqlExpression::InsensitiveEquals { column, value }, SqlFamily::Sqlite) => {
+                format!("{} = {} COLLATE NOCASE", column.render(), params.bind(value.clone()))
+            }
+            (SqlExpression::InsensitiveEquals { column, value }, SqlFamily::Postgres) => {
+                format!("LOWER({}) = LOWER({})", column.render(), params.bind(value.clone()))
+            }
+            (SqlExpression::Search { column, query, .. }, SqlFamily::Postgres) => format!(
+                "to_tsvector({}) @@ plainto_tsquery({})",
+                column.render(),
+                params.bind(query.as_str())
+            ),
+            (SqlExpression::Search { column, index, query }, SqlFamily::Sqlite) => match fts5_query(column, query) {
+                Some(query) => format!(
+                    "{} IN (SELECT rowid FROM {} WHERE {} MATCH {})",
+                    column.row_id(),
+                    quote_path(index.iter()),
+                    quote(&index[1]),
+                    params.bind(query)
+                ),
+                None => String::from("0 = 1"),
+            },
+            (SqlExpression::Rank { column, query, .. }, SqlFamily::Postgres) => format!(
+                "ts_rank(to_tsvector({}), plainto_tsquery({}))",
+                column.render(),
+                params.bind(query.as_str())
+            ),
+            // FTS5 ranks with `bm25`, lower is better.
+            (SqlExpression::Rank { column, index, query }, SqlFamily::Sqlite) => match fts5_query(column, query) {
+                Some(query) => format!(
+                    "SELECT -rank FROM {} WHERE {} MATCH {} AND rowid = {}",
+                    quote_path(index.iter()),
+                    quote(&index[1]),
+                    params.bind(query),
+                    column.row_id()
+                ),
+                None => String::from("NULL"),
+            },
+            (SqlExpression::ArrayContains { column, values, every }, _) => format!(
+                "{} {} {}",
+                column.render(),
+                if *every { "@>" } else { "&&" },
+                params.bind(PrismaValue::List(Some(values.clone())))
+            ),
+            (SqlExpression::ArrayIsEmpty { column }, _) => {
+                format!("{} IS NULL OR cardinality({}) = 0", column.render(), column.render())
+            }
+            (
+                SqlExpression::JsonPath {
+                    column,
+                    path,
+                    condition,
+                },
+                SqlFamily::Postgres,
+            ) => postgres_json_condition(column, path, condition, params),
+            (
+                SqlExpression::JsonPath {
+                    column,
+                    path,
+                    condition,
+                },
+                SqlFamily::Sqlite,
+            ) => sqlite_json_condition(column, path, condition, params),
+            (SqlExpression::Arithmetic { column, operation }, _) => {
+                let (operator, value) = match operation {
+                    NumericOperation::Increment(value) => ("+", value),
+                    NumericOperation::Decrement(value) => ("-", value),
+                    NumericOperation::Multiply(value) => ("*", value),
+                };
+
+                format!("{} {} {}", column.render(), operator, params.bind(value.clone()))
+            }
+            (SqlExpression::ArrayUpdate { column, operation }, _) => match operation {
+                ScalarListOperation::Push(values) => format!(
+                    "array_cat({}, {})",
+                    column.render(),
+                    params.bind(PrismaValue::List(Some(values.clone())))
+                ),
+                ScalarListOperation::Unshift(values) => format!(
+                    "array_cat({}, {})",
+                    params.bind(PrismaValue::List(Some(values.clone()))),
+                    column.render()
+                ),
+                ScalarListOperation::Remove(values) => values.iter().fold(column.render(), |acc, value| {
+                    format!("array_remove({}, {})", acc, params.bind(value.clone()))
+                }),
+                ScalarListOperation::Unset => String::from("NULL"),
+            },
+        }
+    }
+}
+
+fn postgres_json_condition(
+    column: &ColumnRef,
+    path: &[String],
+    condition: &JsonCondition,
+    params: &mut Params,
+) -> String {
+    let path = || {
+        PrismaValue::List(Some(
+            path.iter().map(|segment| PrismaValue::from(segment.as_str())).collect(),
+        ))
+    };
+    let at = |params: &mut Params| format!("({}::jsonb #> {})", column.render(), params.bind(path()));
+
+    match condition {
+        JsonCondition::Equals(value) => format!("{} = {}", at(params), params.bind(PrismaValue::Json(value.clone()))),
+        JsonCondition::StringContains(s) => format!(
+            r"jsonb_typeof({}) = 'string' AND ({}::jsonb #>> {}) LIKE {} ESCAPE '\'",
+            at(params),
+            column.render(),
+            params.bind(path()),
+            params.bind(format!("%{}%", escape_like(s)))
+        ),
+        JsonCondition::ArrayContains(value) => format!(
+            "jsonb_typeof({}) = 'array' AND {} @> {}",
+            at(params),
+            at(params),
+            params.bind(PrismaValue::Json(Value::Array(vec![value.clone()])))
+        ),
+        JsonCondition::LessThan(value) | JsonCondition::GreaterThan(value) => {
+            let operator = match condition {
+                JsonCondition::LessThan(_) => "<",
+                _ => ">",
+            };
+
+            let json_type = match value {
+                Value::Number(_) => "number",
+                Value::String(_) => "string",
+                _ => return String::from("FALSE"),
+            };
+
+            format!(
+                "jsonb_typeof({}) = '{}' AND {} {} {}",
+                at(params),
+                json_type,
+                at(params),
+                operator,
+                params.bind(PrismaValue::Json(value.clone()))
+            )
+        }
+        JsonCondition::Exists => format!("{} IS NOT NULL", at(params)),
+    }
+}
+
+fn sqlite_json_condition(
+    column: &ColumnRef,
+    path: &[String],
+    condition: &JsonCondition,
+    params: &mut Params,
+) -> String {
+    let path = sqlite_json_path(path);
+    let at = JsonTarget::Path(column, &path);
+
+    match condition {
+        JsonCondition::Equals(value) => sqlite_json_equals(&at, value, params),
+        JsonCondition::StringContains(s) => format!(
+            r"{} = 'text' AND {} LIKE {} ESCAPE '\'",
+            at.json_type(params),
+            at.value(params),
+            params.bind(format!("%{}%", escape_like(s)))
+        ),
+        JsonCondition::ArrayContains(value) => format!(
+            "{} = 'array' AND EXISTS (SELECT 1 FROM json_each({}, {}) WHERE {})",
+            at.json_type(params),
+            column.render(),
+            params.bind(path.as_str()),
+            sqlite_json_equals(&JsonTarget::Element, value, params)
+        ),
+        JsonCondition::LessThan(value) | JsonCondition::GreaterThan(value) => {
+            let operator = match condition {
+                JsonCondition::LessThan(_) => "<",
+                _ => ">",
+            };
+
+            match value {
+                Value::Number(_) | Value::String(_) => {
+                    let json_type = at.json_type(params);
+                    let same_type = sqlite_json_type_of(value);
+                    let left = at.value(params);
+
+                    format!(
+                        "{} IN ({}) AND {} {} {}",
+                        json_type,
+                        same_type,
+                        left,
+                        operator,
+                        params.bind(sqlite_json_scalar(value))
+                    )
+                }
+                _ => String::from("0 = 1"),
+            }
+        }
+        JsonCondition::Exists => format!("{} IS NOT NULL", at.json_type(params)),
+    }
+}
+
+/// Where a JSON value is read from on SQLite: a path inside a column, or the
+/// current element of `json_each`.
+enum JsonTarget<'a> {
+    Path(&'a ColumnRef, &'a str),
+    Element,
+}
+
+impl<'a> JsonTarget<'a> {
+    /// The value as SQL, objects and arrays being minified JSON text.
+    fn value(&self, params: &mut Params) -> String {
+        match self {
+            JsonTarget::Path(column, path) => format!("json_extract({}, {})", column.render(), params.bind(*path)),
+            JsonTarget::Element => String::from("\"value\""),
+        }
+    }
+
+    fn json_type(&self, params: &mut Params) -> String {
+        match self {
+            JsonTarget::Path(column, path) => format!("json_type({}, {})", column.render(), params.bind(*path)),
+            JsonTarget::Element => String::from("\"type\""),
+        }
+    }
+}
+
+/// SQLite reads booleans as integers and `null` as `NULL`, so the JSON type
+/// is compared too.
+fn sqlite_json_equals(at: &JsonTarget, value: &Value, params: &mut Params) -> String {
+    match value {
+        Value::Null => format!("{} = 'null'", at.json_type(params)),
+        Value::Bool(b) => format!("{} = '{}'", at.json_type(params), b),
+        Value::Array(_) | Value::Object(_) => format!(
+            "{} IN ('array', 'object') AND {} = json({})",
+            at.json_type(params),
+            at.value(params),
+            params.bind(value.to_string())
+        ),
+        scalar => {
+            let json_type = at.json_type(params);
+            let left = at.value(params);
+
+            format!(
+                "{} IN ({}) AND {} = {}",
+                json_type,
+                sqlite_json_type_of(scalar),
+                left,
+                params.bind(sqlite_json_scalar(scalar))
+            )
+        }
+    }
+}
+
+fn sqlite_json_type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Number(_) => "'integer', 'real'",
+        _ => "'text'",
+    }
+}
+
+fn sqlite_json_scalar(value: &Value) -> PrismaValue {
+    match value {
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => PrismaValue::Int(i),
+            None => PrismaValue::Float(n.as_f64().unwrap_or(0.0)),
+        },
+        Value::String(s) => PrismaValue::from(s.as_str()),
+        value => PrismaValue::from(value.to_string()),
+    }
+}
+
+/// `$.a[0]."b c"`, segments of digits only being array indexes.
+fn sqlite_json_path(path: &[String]) -> String {
+    path.iter().fold(String::from("$"), |acc, segment| {
+        if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+            format!("{}[{}]", acc, segment)
+        } else {
+            format!("{}.\"{}\"", acc, segment.replace('"', "\\\""))
+        }
+    })
+}
+
+/// Where the value of a `Like` expression has to be found in the column.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum LikePosition {
+    Anywhere,
+    Start,
+    End,
+}
+
+impl LikePosition {
+    /// The pattern for an escaped value, with the wildcard where other
+    /// characters may be.
+    fn pattern(self, wildcard: &str, escaped: &str) -> String {
+        match self {
+            LikePosition::Anywhere => format!("{}{}{}", wildcard, escaped, wildcard),
+            LikePosition::Start => format!("{}{}", escaped, wildcard),
+            LikePosition::End => format!("{}{}", wildcard, escaped),
+        }
+    }
+}
+
+/// User input must not act as a wildcard in `LIKE` patterns. The patterns
+/// declare the backslash as their escape character.
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// `GLOB` has no escape character, its wildcards match themselves in a set.
+fn escape_glob(value: &str) -> String {
+    value.chars().fold(String::with_capacity(value.len()), |mut acc, c| {
+        match c {
+            '*' | '?' | '[' => {
+                acc.push('[');
+                acc.push(c);
+                acc.push(']');
+            }
+            c => acc.push(c),
+        }
+
+        acc
+    })
+}
+
+/// A parameter of a rendered statement. The values of expressions are bound
+/// by the connectors, converting them to the type the database expects.
+#[derive(Debug)]
+pub enum Parameter {
+    Query(ParameterizedValue),
+    Value(PrismaValue),
+}
+
+/// Collects the values of one expression while rendering it.
+struct Params {
+    family: SqlFamily,
+
+    /// The number of the first parameter on PostgreSQL
+    first: usize,
+    values: Vec<PrismaValue>,
+}
+
+impl Params {
+    fn bind<T>(&mut self, value: T) -> String
+    where
+        T: Into<PrismaValue>,
+    {
+        self.values.push(value.into());
+
+        match self.family {
+            SqlFamily::Postgres => format!("${}", self.first + self.values.len() - 1),
+            SqlFamily::Sqlite => String::from("?"),
+        }
+    }
+}
+
+/// Replaces the placeholders in a statement built by the visitor of the given
+/// family, returning the final SQL with all of its parameters.
+///
+/// PostgreSQL numbers its parameters, so the values of the expressions are
+/// appended to the parameters of the visitor. SQLite binds by position and the
+/// parameters are merged in the order they appear in the statement.
+pub fn expand(family: SqlFamily, sql: &str, params: Vec<ParameterizedValue>) -> SqlResult<(String, Vec<Parameter>)> {
+    if !sql.contains(prisma_models::PLACEHOLDER_PREFIX) {
+        return Ok((sql.to_string(), params.into_iter().map(Parameter::Query).collect()));
+    }
+
+    let query_param_count = params.len();
+    let mut query_params = params.into_iter();
+    let mut parameters = Vec::new();
+    let mut values = Vec::new();
+    let mut result = String::with_capacity(sql.len());
+    let mut rest = sql;
+
+    while let Some(start) = rest.find(|c| c == '\'' || c == '"' || c == '`' || c == '?') {
+        let (before, tail) = rest.split_at(start);
+        result.push_str(before);
+
+        let delimiter = tail.as_bytes()[0];
+
+        if delimiter == b'?' {
+            if family == SqlFamily::Sqlite {
+                parameters.push(Parameter::Query(next_param(&mut query_params)?));
+            }
+
+            result.push('?');
+            rest = &tail[1..];
+            continue;
+        }
+
+        let end = quoted_length(tail, delimiter);
+        let quoted = &tail[..end];
+        let identifier = quoted.get(1..end.saturating_sub(1)).unwrap_or("");
+
+        match (delimiter, placeholder_payload(identifier)) {
+            (b'"', Some((kind, payload))) | (b'`', Some((kind, payload))) => {
+                let mut params = Params {
+                    family,
+                    first: query_param_count + values.len() + 1,
+                    values: Vec::new(),
+                };
+
+                result.push_str(&render_placeholder(kind, &payload, &mut params)?);
+
+                match family {
+                    SqlFamily::Postgres => values.append(&mut params.values),
+                    SqlFamily::Sqlite => parameters.extend(params.values.into_iter().map(Parameter::Value)),
+                }
+            }
+            _ => result.push_str(quoted),
+        }
+
+        rest = &tail[end..];
+    }
+
+    result.push_str(rest);
+
+    match family {
+        SqlFamily::Postgres => {
+            parameters.extend(query_params.map(Parameter::Query));
+            parameters.extend(values.into_iter().map(Parameter::Value));
+        }
+        SqlFamily::Sqlite if query_params.next().is_some() => {
+            return Err(parameter_mismatch());
+        }
+        SqlFamily::Sqlite => (),
+    }
+
+    Ok((result, parameters))
+}
+
+/// Turns a rendered `INSERT` into an upsert, updating the record it conflicts
+/// with on the columns with the `SET` of a rendered `UPDATE` of the same table.
+pub fn on_conflict_update(
+    family: SqlFamily,
+    insert: (String, Vec<Parameter>),
+    columns: &[&str],
+    update: (String, Vec<Parameter>),
+) -> SqlResult<(String, Vec<Parameter>)> {
+    let (insert_sql, mut parameters) = insert;
+    let (update_sql, update_parameters) = update;
+
+    let set = match update_sql.find(" SET ") {
+        Some(start) => &update_sql[start + 1..],
+        None => {
+            return Err(SqlError::QueryError(failure::err_msg(
+                "The update of an upsert sets no columns",
+            )))
+        }
+    };
+
+    let set = match family {
+        SqlFamily::Postgres => shift_parameters(set, parameters.len()),
+        SqlFamily::Sqlite => set.to_string(),
+    };
+
+    let target: Vec<String> = columns.iter().map(|column| quote(column)).collect();
+    let sql = format!("{} ON CONFLICT ({}) DO UPDATE {}", insert_sql, target.join(", "), set);
+
+    parameters.extend(update_parameters);
+
+    Ok((sql, parameters))
+}
+
+/// Renumbers the parameters of a PostgreSQL statement to follow `offset`
+/// other parameters.
+fn shift_parameters(sql: &str, offset: usize) -> String {
+    let mut result = String::with_capacity(sql.len());
+    let mut rest = sql;
+
+    while let Some(start) = rest.find(|c| c == '\'' || c == '"' || c == '$') {
+        let (before, tail) = rest.split_at(start);
+        result.push_str(before);
+
+        if tail.starts_with('$') {
+            let digits = tail[1..].find(|c: char| !c.is_ascii_digit()).unwrap_or(tail.len() - 1);
+
+            match tail[1..1 + digits].parse::<usize>() {
+                Ok(number) => result.push_str(&format!("${}", number + offset)),
+                Err(_) => result.push('$'),
+            }
+
+            rest = &tail[1 + digits..];
+            continue;
+        }
+
+        let end = quoted_length(tail, tail.as_bytes()[0]);
+        result.push_str(&tail[..end]);
+        rest = &tail[end..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+fn render_placeholder(kind: &str, payload: &[u8], params: &mut Params) -> SqlResult<String> {
+    match kind {
+        EXPRESSION_PLACEHOLDER => {
+            let expression: SqlExpression = serde_json::from_slice(payload)?;
+            Ok(format!("({})", expression.render(params)))
+        }
+        VALUE_PLACEHOLDER => {
+            let value: PrismaValue = serde_json::from_slice(payload)?;
+            Ok(params.bind(value))
+        }
+        kind => Err(SqlError::QueryError(failure::err_msg(format!(
+            "Unknown placeholder kind {}",
+            kind
+        )))),
+    }
+}
+
+fn next_param(params: &mut Iterator<Item = ParameterizedValue>) -> SqlResult<ParameterizedValue> {
+    params.next().ok_or_else(parameter_mismatch)
+}
+
+fn parameter_mismatch() -> SqlError {
+    SqlError::QueryError(failure::err_msg(
+        "The statement doesn't have the same number of parameters as the query",
+    ))
+}
+
+/// The length of the quoted part at the start of `s`, delimiters included. A
+/// doubled delimiter stands for itself.
+fn quoted_length(s: &str, delimiter: u8) -> usize {
+    let bytes = s.as_bytes();
+    let mut i = 1;
+
+    while i < bytes.len() {
+        if bytes[i] == delimiter {
+            if bytes.get(i + 1) == Some(&delimiter) {
+                i += 2;
+                continue;
+            }
+
+            return i + 1;
+        }
+
+        i += 1;
+    }
+
+    bytes.len()
+}
+
+/// Every word of a plain text query as a phrase of its own, restricted to the
+/// column. `None` if the query has no words.
+fn fts5_query(column: &ColumnRef, query: &str) -> Option<String> {
+    let phrases: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("{} : {}", quote(&column.name), quote(word)))
+        .collect();
+
+    match phrases.len() {
+        0 => None,
+        _ => Some(phrases.join(" AND ")),
+    }
+}
+
+fn quote_path<'a, I>(parts: I) -> String
+where
+    I: Iterator<Item = &'a String>,
+{
+    parts.map(|part| quote(part)).collect::<Vec<String>>().join(".")
+}
+
+/// The identifier in double quotes.
+pub fn quote(identifier: &str) -> String {
+    format!(r#""{}""#, identifier.replace('"', r#""""#))
+}
@@ -1,3 +1,7 @@
+use crate::{
+    expression::{search_index, ColumnRef, LikePosition, SqlExpression},
+    query_builder::QueryBuilder,
+};
 use connector::filter::*;
 use prisma_models::prelude::*;
 use prisma_query::ast::*;
@@ -78,8 +82,9 @@ trait AliasedSelect {
     /// alias if provided, otherwise using the fully qualified path.
     ///
     /// Alias should be used only when nesting, making the top level queries
-    /// more explicit.
-    fn aliased_sel(self, alias: Option<Alias>) -> Select;
+    /// more explicit. The `parent` column is compared against the result of
+    /// the select, to-one relations correlate the select with it.
+    fn aliased_sel(self, alias: Option<Alias>, parent: Column) -> Select;
 }
 
 impl AliasedCondition for Filter {
@@ -120,6 +125,7 @@ impl AliasedCondition for Filter {
                 }
             },
             Filter::Scalar(filter) => filter.aliased_cond(alias),
+            Filter::ScalarList(filter) => filter.aliased_cond(alias),
             Filter::OneRelationIsNull(filter) => filter.aliased_cond(alias),
             Filter::Relation(filter) => filter.aliased_cond(alias),
             Filter::BoolFilter(b) => {
@@ -142,17 +148,43 @@ impl AliasedCondition for ScalarFilter {
             None => self.field.as_column(),
         };
 
+        let insensitive = self.mode == QueryMode::Insensitive;
+        let column_ref = ColumnRef::new(&self.field, alias.map(|a| a.to_string(None)));
+
+        let insensitive_equals = |value: PrismaValue| {
+            SqlExpression::InsensitiveEquals {
+                column: column_ref.clone(),
+                value,
+            }
+            .into_condition()
+        };
+
+        let like = |value: PrismaValue, position: LikePosition| {
+            SqlExpression::Like {
+                column: column_ref.clone(),
+                value: value.to_string(),
+                position,
+                insensitive,
+            }
+            .into_condition()
+        };
+
         let condition = match self.condition {
             ScalarCondition::Equals(PrismaValue::Null) => column.is_null(),
             ScalarCondition::NotEquals(PrismaValue::Null) => column.is_not_null(),
+            ScalarCondition::Equals(value @ PrismaValue::String(_)) if insensitive => return insensitive_equals(value),
+            ScalarCondition::NotEquals(value @ PrismaValue::String(_)) if insensitive => {
+                return insensitive_equals(value).not()
+            }
             ScalarCondition::Equals(value) => column.equals(value),
             ScalarCondition::NotEquals(value) => column.not_equals(value),
-            ScalarCondition::Contains(value) => column.like(format!("{}", value)),
-            ScalarCondition::NotContains(value) => column.not_like(format!("{}", value)),
-            ScalarCondition::StartsWith(value) => column.begins_with(format!("{}", value)),
-            ScalarCondition::NotStartsWith(value) => column.not_begins_with(format!("{}", value)),
-            ScalarCondition::EndsWith(value) => column.ends_into(format!("{}", value)),
-            ScalarCondition::NotEndsWith(value) => column.not_ends_into(format!("{}", value)),
+            // The patterns declare their escape character, which the AST can't.
+            ScalarCondition::Contains(value) => return like(value, LikePosition::Anywhere),
+            ScalarCondition::NotContains(value) => return like(value, LikePosition::Anywhere).not(),
+            ScalarCondition::StartsWith(value) => return like(value, LikePosition::Start),
+            ScalarCondition::NotStartsWith(value) => return like(value, LikePosition::Start).not(),
+            ScalarCondition::EndsWith(value) => return like(value, LikePosition::End),
+            ScalarCondition::NotEndsWith(value) => return like(value, LikePosition::End).not(),
             ScalarCondition::LessThan(value) => column.less_than(value),
             ScalarCondition::LessThanOrEquals(value) => column.less_than_or_equals(value),
             ScalarCondition::GreaterThan(value) => column.greater_than(value),
@@ -169,12 +201,87 @@ impl AliasedCondition for ScalarFilter {
             },
             ScalarCondition::In(None) => column.is_null(),
             ScalarCondition::NotIn(None) => column.is_not_null(),
+            ScalarCondition::Search(value) => {
+                return SqlExpression::Search {
+                    column: column_ref.clone(),
+                    index: search_index(&self.field.model()),
+                    query: format!("{}", value),
+                }
+                .into_condition()
+            }
+            ScalarCondition::JsonPath(json) => {
+                return SqlExpression::JsonPath {
+                    column: column_ref.clone(),
+                    path: json.path,
+                    condition: json.condition,
+                }
+                .into_condition()
+            }
         };
 
         ConditionTree::single(condition)
     }
 }
 
+impl AliasedCondition for ScalarListFilter {
+    /// Conversion from a `ScalarListFilter` to a query condition tree. Aliased when in a nested `SELECT`.
+    fn aliased_cond(self, alias: Option<Alias>) -> ConditionTree {
+        if self.field.is_native_list() {
+            let column = ColumnRef::new(&self.field, alias.as_ref().map(|a| a.to_string(None)));
+            return native_list_condition(column, self.condition);
+        }
+
+        let id_column = match alias {
+            Some(ref alias) => self.field.model().id_column().table(alias.to_string(None)),
+            None => self.field.model().id_column(),
+        };
+
+        let list_table = self.field.scalar_list_table();
+        let node_ids = |values: Option<Vec<PrismaValue>>| {
+            let select = Select::from_table(list_table.table()).column(list_table.node_id_column());
+
+            match values {
+                Some(values) => select.so_that(list_table.value_column().in_selection(values)),
+                None => select,
+            }
+        };
+
+        match self.condition {
+            ScalarListCondition::Contains(value) => id_column.in_selection(node_ids(Some(vec![value]))).into(),
+            ScalarListCondition::ContainsEvery(values) => values
+                .into_iter()
+                .map(|value| ConditionTree::from(id_column.clone().in_selection(node_ids(Some(vec![value])))))
+                .fold(ConditionTree::NoCondition, |acc, cond| match acc {
+                    ConditionTree::NoCondition => cond,
+                    acc => ConditionTree::and(acc, cond),
+                }),
+            ScalarListCondition::ContainsSome(ref values) if values.is_empty() => ConditionTree::NegativeCondition,
+            ScalarListCondition::ContainsSome(values) => id_column.in_selection(node_ids(Some(values))).into(),
+            ScalarListCondition::ContainsNone => id_column.not_in_selection(node_ids(None)).into(),
+        }
+    }
+}
+
+/// Array columns, compared with `@>` and `&&`
+fn native_list_condition(column: ColumnRef, condition: ScalarListCondition) -> ConditionTree {
+    let contains = |values: Vec<PrismaValue>, every: bool| {
+        SqlExpression::ArrayContains {
+            column: column.clone(),
+            values,
+            every,
+        }
+        .into_condition()
+    };
+
+    match condition {
+        ScalarListCondition::Contains(value) => contains(vec![value], true),
+        ScalarListCondition::ContainsEvery(values) => contains(values, true),
+        ScalarListCondition::ContainsSome(ref values) if values.is_empty() => ConditionTree::NegativeCondition,
+        ScalarListCondition::ContainsSome(values) => contains(values, false),
+        ScalarListCondition::ContainsNone => SqlExpression::ArrayIsEmpty { column }.into_condition(),
+    }
+}
+
 impl AliasedCondition for RelationFilter {
     /// Conversion from a `RelationFilter` to a query condition tree. Aliased when in a nested `SELECT`.
     fn aliased_cond(self, alias: Option<Alias>) -> ConditionTree {
@@ -186,7 +293,7 @@ impl AliasedCondition for RelationFilter {
         };
 
         let condition = self.condition.clone();
-        let sub_select = self.aliased_sel(alias.map(|a| a.inc(AliasMode::Table)));
+        let sub_select = self.aliased_sel(alias.map(|a| a.inc(AliasMode::Table)), column.clone());
 
         let comparison = match condition {
             RelationCondition::EveryRelatedNode => column.not_in_selection(sub_select),
@@ -201,7 +308,7 @@ impl AliasedCondition for RelationFilter {
 
 impl AliasedSelect for RelationFilter {
     /// The subselect part of the `RelationFilter` `ConditionTree`.
-    fn aliased_sel(self, alias: Option<Alias>) -> Select {
+    fn aliased_sel(self, alias: Option<Alias>, parent: Column) -> Select {
         let alias = alias.unwrap_or(Alias::default());
         let condition = self.condition.clone();
         let relation = self.field.relation();
@@ -209,6 +316,15 @@ impl AliasedSelect for RelationFilter {
         let this_column = self.field.relation_column().table(alias.to_string(None));
         let other_column = self.field.opposite_column().table(alias.to_string(None));
 
+        // A to-one relation has one link per record at most, looked up with
+        // the record of the parent instead of selecting all the links.
+        let correlate = |conditions: ConditionTree| match condition {
+            RelationCondition::ToOneRelatedNode => {
+                ConditionTree::and(this_column.clone().equals(parent.clone()), conditions)
+            }
+            _ => conditions,
+        };
+
         // Normalize filter tree
         let compacted = match *self.nested_filter {
             Filter::And(mut filters) => {
@@ -231,7 +347,7 @@ impl AliasedSelect for RelationFilter {
         match compacted {
             Filter::Relation(filter) => {
                 let sub_condition = filter.condition.clone();
-                let sub_select = filter.aliased_sel(Some(alias.inc(AliasMode::Table)));
+                let sub_select = filter.aliased_sel(Some(alias.inc(AliasMode::Table)), other_column.clone());
 
                 let tree: ConditionTree = match sub_condition {
                     RelationCondition::EveryRelatedNode => other_column.not_in_selection(sub_select),
@@ -243,9 +359,27 @@ impl AliasedSelect for RelationFilter {
 
                 let conditions = tree.invert_if(condition.invert_of_subselect());
 
+                // Without a join to the related table, soft-deleted records are
+                // left out through their ids.
+                let related_model = self.field.related_model();
+                let conditions = match related_model.is_soft_delete {
+                    true => {
+                        let live_ids = Select::from_table(related_model.table())
+                            .column(related_model.id_column())
+                            .so_that(QueryBuilder::exclude_deleted(
+                                &related_model,
+                                ConditionTree::NoCondition,
+                            ));
+
+                        let live_column = self.field.opposite_column().table(alias.to_string(None));
+                        ConditionTree::and(conditions, live_column.in_selection(live_ids))
+                    }
+                    false => conditions,
+                };
+
                 Select::from_table(relation.relation_table().alias(alias.to_string(None)))
-                    .column(this_column)
-                    .so_that(conditions)
+                    .column(this_column.clone())
+                    .so_that(correlate(conditions))
             }
             nested_filter => {
                 let tree = nested_filter.aliased_cond(Some(alias.flip(AliasMode::Join)));
@@ -265,10 +399,16 @@ impl AliasedSelect for RelationFilter {
 
                 let table = relation.relation_table().alias(alias.to_string(Some(AliasMode::Table)));
 
+                let conditions = QueryBuilder::exclude_deleted_aliased(
+                    &self.field.related_model(),
+                    Some(alias.to_string(Some(AliasMode::Join))),
+                    tree.invert_if(condition.invert_of_subselect()),
+                );
+
                 Select::from_table(table)
-                    .column(this_column)
+                    .column(this_column.clone())
                     .inner_join(join)
-                    .so_that(tree.invert_if(condition.invert_of_subselect()))
+                    .so_that(correlate(conditions))
             }
         }
     }
@@ -277,25 +417,33 @@ impl AliasedSelect for RelationFilter {
 impl AliasedCondition for OneRelationIsNullFilter {
     /// Conversion from a `OneRelationIsNullFilter` to a query condition tree. Aliased when in a nested `SELECT`.
     fn aliased_cond(self, alias: Option<Alias>) -> ConditionTree {
-        let alias = alias.map(|a| a.to_string(None));
-
         let condition = if self.field.relation_is_inlined_in_parent() {
-            self.field.as_column().opt_table(alias.clone()).is_null()
+            self.field
+                .as_column()
+                .opt_table(alias.map(|a| a.to_string(None)))
+                .is_null()
         } else {
+            // The link is in the table of the related model or in a relation
+            // table, looked up for the record only.
             let relation = self.field.relation();
+            let link_alias = alias
+                .map(|a| a.inc(AliasMode::Table))
+                .unwrap_or_default()
+                .to_string(None);
+
+            let id_column = self
+                .field
+                .model()
+                .id_column()
+                .opt_table(alias.map(|a| a.to_string(None)));
 
             let column = relation
                 .column_for_relation_side(self.field.relation_side)
-                .opt_table(alias.clone());
-
-            let table = Table::from(relation.relation_table());
-            let relation_table = match alias {
-                Some(ref alias) => table.alias(alias.to_string()),
-                None => table,
-            };
+                .table(link_alias.clone());
 
-            let select = Select::from_table(relation_table).column(column);
-            let id_column = self.field.model().id_column().opt_table(alias.clone());
+            let select = Select::from_table(relation.relation_table().alias(link_alias))
+                .column(column.clone())
+                .so_that(column.equals(id_column.clone()));
 
             id_column.not_in_selection(select)
         };
@@ -9,6 +9,7 @@
 mod cursor_condition;
 mod database;
 mod error;
+mod expression;
 mod filter_conversion;
 mod mutaction;
 mod ordering;
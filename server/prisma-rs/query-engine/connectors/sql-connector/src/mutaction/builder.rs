@@ -1,25 +1,47 @@
+use chrono::{DateTime, Utc};
+use connector::filter::NodeSelector;
 use prisma_models::prelude::*;
 use prisma_query::ast::*;
+use std::{cmp, collections::BTreeMap, convert::TryFrom, sync::Arc};
 
-use crate::{error::SqlError, SqlResult};
+use crate::{
+    error::SqlError,
+    expression::{ColumnRef, SqlExpression},
+    SqlResult,
+};
 
 pub struct MutationBuilder;
 
 impl MutationBuilder {
-    const PARAMETER_LIMIT: usize = 10000;
+    pub fn create_node(model: ModelRef, args: PrismaArgs) -> (Insert, Option<GraphqlId>) {
+        let model_id = model.fields().id();
+        let (insert, return_id) = Self::insert_node(Arc::clone(&model), args);
+
+        (insert.returning(vec![model_id.as_column()]), return_id)
+    }
 
-    pub fn create_node(model: ModelRef, mut args: PrismaArgs) -> (Insert, Option<GraphqlId>) {
+    /// The insert of `create_node` without reading back the id.
+    fn insert_node(model: ModelRef, mut args: PrismaArgs) -> (Insert, Option<GraphqlId>) {
         let model_id = model.fields().id();
 
         let return_id = match args.get_field_value(&model_id.name) {
-            _ if model_id.is_auto_generated => None,
             Some(PrismaValue::Null) | None => {
-                let id = model.generate_id();
-                args.insert(model_id.name.as_ref(), id.clone());
-                Some(id)
+                args.take_field_value(&model_id.name);
+
+                // Without an id the database generates one, read back from the insert.
+                let id = if model_id.is_auto_generated {
+                    None
+                } else {
+                    model.generate_id()
+                };
+
+                if let Some(ref id) = id {
+                    args.insert(model_id.name.as_ref(), id.clone());
+                }
+
+                id
             }
-            Some(PrismaValue::GraphqlId(id)) => Some(id.clone()),
-            _ => None,
+            Some(value) => GraphqlId::try_from(value).ok(),
         };
 
         let fields: Vec<&Field> = model
@@ -39,7 +61,63 @@ impl MutationBuilder {
             .into_iter()
             .fold(base, |acc, (name, value)| acc.value(name.into_owned(), value));
 
-        (Insert::from(insert).returning(vec![model_id.as_column()]), return_id)
+        (Insert::from(insert), return_id)
+    }
+
+    /// Multi-row inserts of records with ids already set, records giving
+    /// values for the same fields sharing the statements. Every statement
+    /// stays within `parameter_limit`.
+    pub fn create_nodes(
+        model: ModelRef,
+        records: Vec<PrismaArgs>,
+        parameter_limit: usize,
+        skip_duplicates: bool,
+    ) -> Vec<Query> {
+        let fields = model.fields();
+        let mut groups: BTreeMap<Vec<usize>, Vec<PrismaArgs>> = BTreeMap::new();
+
+        for args in records {
+            let key = fields
+                .all
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| args.has_arg_for(field.name()))
+                .map(|(i, _)| i)
+                .collect();
+
+            groups.entry(key).or_insert_with(Vec::new).push(args);
+        }
+
+        let mut inserts = Vec::new();
+
+        for (key, mut records) in groups {
+            let columns: Vec<String> = key.iter().map(|i| fields.all[*i].db_name().into_owned()).collect();
+            let chunk_size = cmp::max(parameter_limit / cmp::max(columns.len(), 1), 1);
+
+            for chunk in records.chunks_mut(chunk_size) {
+                let base = Insert::multi_into(model.table(), columns.clone());
+
+                let insert = chunk.iter_mut().fold(base, |acc, args| {
+                    let values: Vec<PrismaValue> = key
+                        .iter()
+                        .map(|i| args.take_field_value(fields.all[*i].name()).unwrap())
+                        .collect();
+
+                    acc.values(values)
+                });
+
+                let insert = Insert::from(insert);
+
+                let query: Query = match skip_duplicates {
+                    true => insert.on_conflict(OnConflict::DoNothing).into(),
+                    false => insert.into(),
+                };
+
+                inserts.push(query);
+            }
+        }
+
+        inserts
     }
 
     pub fn create_relation(field: RelationFieldRef, parent_id: &GraphqlId, child_id: &GraphqlId) -> Query {
@@ -114,15 +192,149 @@ impl MutationBuilder {
         Some(result)
     }
 
+    /// Multi-row inserts of the list values of many records, within
+    /// `parameter_limit`.
+    pub fn create_scalar_list_values(
+        scalar_list_table: &ScalarListTable,
+        list_values: &[(GraphqlId, &PrismaListValue)],
+        parameter_limit: usize,
+    ) -> Vec<Insert> {
+        let rows: Vec<(i64, PrismaValue, GraphqlId)> = list_values
+            .iter()
+            .flat_map(|(id, list_value)| {
+                let values = list_value.as_ref().map(|l| l.as_slice()).unwrap_or(&[]);
+                let positions = (1..=values.len()).map(|v| (v * 1000) as i64);
+
+                positions
+                    .zip(values.iter())
+                    .map(move |(position, value)| (position, value.clone(), id.clone()))
+            })
+            .collect();
+
+        Self::create_scalar_list_rows(scalar_list_table, rows, parameter_limit)
+    }
+
+    /// Multi-row inserts of `(position, value, node id)` rows, within
+    /// `parameter_limit`.
+    pub fn create_scalar_list_rows(
+        scalar_list_table: &ScalarListTable,
+        rows: Vec<(i64, PrismaValue, GraphqlId)>,
+        parameter_limit: usize,
+    ) -> Vec<Insert> {
+        let columns = vec![
+            ScalarListTable::POSITION_FIELD_NAME,
+            ScalarListTable::VALUE_FIELD_NAME,
+            ScalarListTable::NODE_ID_FIELD_NAME,
+        ];
+
+        rows.chunks(cmp::max(parameter_limit / columns.len(), 1))
+            .map(|chunk| {
+                let insert = Insert::multi_into(scalar_list_table.table(), columns.clone());
+
+                chunk.iter().fold(insert, |acc, row| acc.values(row.clone())).into()
+            })
+            .collect()
+    }
+
+    /// The column value of a list with `ScalarListStrategy::Native`.
+    pub fn native_list_value(list_value: &PrismaListValue) -> PrismaValue {
+        match list_value {
+            Some(l) => PrismaValue::List(Some(l.clone())),
+            None => PrismaValue::Null,
+        }
+    }
+
     pub fn update_one(model: ModelRef, id: &GraphqlId, args: &PrismaArgs) -> SqlResult<Option<Update>> {
-        Self::update_many(model, &[id; 1], args).map(|updates| updates.into_iter().next())
+        Self::update_many(model, &[id; 1], args, 1).map(|updates| updates.into_iter().next())
     }
 
-    pub fn update_many(model: ModelRef, ids: &[&GraphqlId], args: &PrismaArgs) -> SqlResult<Vec<Update>> {
-        if args.args.is_empty() || ids.is_empty() {
+    pub fn update_many(
+        model: ModelRef,
+        ids: &[&GraphqlId],
+        args: &PrismaArgs,
+        parameter_limit: usize,
+    ) -> SqlResult<Vec<Update>> {
+        if args.is_empty() || ids.is_empty() {
             return Ok(Vec::new());
         }
 
+        let fields = model.fields();
+        let query = Self::update_set(&model, args, ColumnRef::unqualified)?;
+
+        let result: Vec<Update> = ids
+            .chunks(parameter_limit)
+            .into_iter()
+            .map(|ids| {
+                query
+                    .clone()
+                    .so_that(fields.id().as_column().in_selection(ids.to_vec()))
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    /// Sets `deletedAt` to `now` on the records of a model with soft deletes.
+    /// Records deleted before keep their deletion time.
+    pub fn soft_delete_many(
+        model: ModelRef,
+        ids: &[&GraphqlId],
+        now: DateTime<Utc>,
+        parameter_limit: usize,
+    ) -> Vec<Update> {
+        let field = match model.fields().deleted_at() {
+            Some(field) => Arc::clone(field),
+            None => return Vec::new(),
+        };
+
+        let id_column = model.fields().id().as_column();
+        let deleted_at = PrismaValue::DateTime(now);
+
+        ids.chunks(parameter_limit)
+            .map(|ids| {
+                let condition = id_column
+                    .clone()
+                    .in_selection(ids.to_vec())
+                    .and(field.as_column().is_null());
+
+                Update::table(model.table())
+                    .set(field.db_name(), deleted_at.clone())
+                    .so_that(condition)
+            })
+            .collect()
+    }
+
+    /// Applies the list operation to the array column of a native list,
+    /// without reading the current values.
+    pub fn update_native_list(
+        field: &ScalarFieldRef,
+        ids: &[&GraphqlId],
+        operation: &ScalarListOperation,
+        parameter_limit: usize,
+    ) -> Vec<Update> {
+        let model = field.model();
+        let id_column = model.fields().id().as_column();
+
+        let value = SqlExpression::ArrayUpdate {
+            column: ColumnRef::unqualified(field),
+            operation: operation.clone(),
+        };
+
+        ids.chunks(parameter_limit)
+            .map(|ids| {
+                Update::table(model.table())
+                    .set(field.db_name(), value.clone().into_column())
+                    .so_that(id_column.clone().in_selection(ids.to_vec()))
+            })
+            .collect()
+    }
+
+    /// An update of the model's table setting the values and applying the
+    /// numeric operations of the arguments to the columns from `column`.
+    fn update_set<F>(model: &ModelRef, args: &PrismaArgs, column: F) -> SqlResult<Update>
+    where
+        F: Fn(&ScalarField) -> ColumnRef,
+    {
         let fields = model.fields();
         let mut query = Update::table(model.table());
 
@@ -138,24 +350,66 @@ impl MutationBuilder {
             query = query.set(field.db_name(), value.clone());
         }
 
-        let result: Vec<Update> = ids
-            .chunks(Self::PARAMETER_LIMIT)
-            .into_iter()
-            .map(|ids| {
-                query
-                    .clone()
-                    .so_that(fields.id().as_column().in_selection(ids.to_vec()))
-            })
-            .collect();
+        for (name, operation) in args.operations.iter() {
+            let field = fields.find_from_scalar(&name).unwrap();
 
-        Ok(result)
+            let value = SqlExpression::Arithmetic {
+                column: column(&field),
+                operation: operation.clone(),
+            };
+
+            query = query.set(field.db_name(), value.into_column());
+        }
+
+        Ok(query)
     }
 
-    pub fn delete_many(model: ModelRef, ids: &[&GraphqlId]) -> Vec<Delete> {
+    /// The insert of a record and the update of the existing record selected
+    /// by the node selector, for `Transaction::upsert`. PostgreSQL sees the
+    /// inserted values in the update too, the current values are read from
+    /// qualified columns. Without arguments the update sets the selected
+    /// values again.
+    pub fn upsert_node(
+        node_selector: &NodeSelector,
+        create_args: PrismaArgs,
+        update_args: &PrismaArgs,
+    ) -> SqlResult<(Insert, Update, Option<GraphqlId>)> {
+        let model = node_selector.model();
+        let (insert, return_id) = Self::insert_node(Arc::clone(&model), create_args);
+        let mut update = Self::update_set(&model, update_args, |field| ColumnRef::new(field, None))?;
+
+        if update_args.is_empty() {
+            for (field, value) in node_selector.fields.iter() {
+                update = update.set(field.db_name(), value.clone());
+            }
+        }
+
+        Ok((insert, update, return_id))
+    }
+
+    /// Touches the record only if it still has the expected version, locking
+    /// it for the rest of the transaction. Changes no rows on a conflict.
+    pub fn lock_version(model: ModelRef, id: &GraphqlId, expected: &PrismaValue) -> Option<Update> {
+        let fields = model.fields();
+
+        fields.version().as_ref().map(|field| {
+            let conditions = fields
+                .id()
+                .as_column()
+                .equals(id.clone())
+                .and(field.as_column().equals(expected.clone()));
+
+            Update::table(model.table())
+                .set(field.db_name(), DatabaseValue::from(Column::from(field.db_name())))
+                .so_that(conditions)
+        })
+    }
+
+    pub fn delete_many(model: ModelRef, ids: &[&GraphqlId], parameter_limit: usize) -> Vec<Delete> {
         let mut deletes = Vec::new();
 
-        for chunk in ids.chunks(Self::PARAMETER_LIMIT).into_iter() {
-            for lf in model.fields().scalar_list() {
+        for chunk in ids.chunks(parameter_limit).into_iter() {
+            for lf in model.fields().scalar_list().iter().filter(|lf| !lf.is_native_list()) {
                 let scalar_list_table = lf.scalar_list_table();
                 let condition = scalar_list_table.node_id_column().in_selection(chunk.to_vec());
                 deletes.push(Delete::from_table(scalar_list_table.table()).so_that(condition));
@@ -172,6 +426,7 @@ impl MutationBuilder {
         scalar_list_table: &ScalarListTable,
         list_value: &PrismaListValue,
         ids: Vec<GraphqlId>,
+        parameter_limit: usize,
     ) -> (Vec<Delete>, Vec<Insert>) {
         if ids.is_empty() {
             return (Vec::new(), Vec::new());
@@ -179,7 +434,7 @@ impl MutationBuilder {
 
         let deletes = {
             let ids: Vec<&GraphqlId> = ids.iter().map(|id| &*id).collect();
-            Self::delete_scalar_list_values(scalar_list_table, ids.as_slice())
+            Self::delete_scalar_list_values(scalar_list_table, ids.as_slice(), parameter_limit)
         };
 
         let inserts = match list_value {
@@ -193,12 +448,41 @@ impl MutationBuilder {
         (deletes, inserts)
     }
 
-    pub fn delete_scalar_list_values(scalar_list_table: &ScalarListTable, ids: &[&GraphqlId]) -> Vec<Delete> {
-        Self::delete_in_chunks(scalar_list_table.table(), ids, |chunk| {
+    pub fn delete_scalar_list_values(
+        scalar_list_table: &ScalarListTable,
+        ids: &[&GraphqlId],
+        parameter_limit: usize,
+    ) -> Vec<Delete> {
+        Self::delete_in_chunks(scalar_list_table.table(), ids, parameter_limit, |chunk| {
             ScalarListTable::NODE_ID_FIELD_NAME.in_selection(chunk.to_vec())
         })
     }
 
+    /// Deletes the given values from the lists of the records, wherever they
+    /// appear.
+    pub fn remove_scalar_list_values(
+        scalar_list_table: &ScalarListTable,
+        values: &[PrismaValue],
+        ids: &[&GraphqlId],
+        parameter_limit: usize,
+    ) -> Vec<Delete> {
+        if values.is_empty() {
+            return Vec::new();
+        }
+
+        let chunk_size = cmp::max(parameter_limit.saturating_sub(values.len()), 1);
+
+        ids.chunks(chunk_size)
+            .map(|chunk| {
+                let conditions = ScalarListTable::NODE_ID_FIELD_NAME
+                    .in_selection(chunk.to_vec())
+                    .and(ScalarListTable::VALUE_FIELD_NAME.in_selection(values.to_vec()));
+
+                Delete::from_table(scalar_list_table.table()).so_that(conditions)
+            })
+            .collect()
+    }
+
     pub fn truncate_tables(project: ProjectRef) -> Vec<Delete> {
         let models = project.internal_data_model().models();
         let mut deletes = Vec::new();
@@ -225,6 +509,7 @@ impl MutationBuilder {
                     .fields()
                     .scalar_list()
                     .iter()
+                    .filter(|slf| !slf.is_native_list())
                     .map(|slf| slf.scalar_list_table().table())
                     .collect();
 
@@ -238,11 +523,11 @@ impl MutationBuilder {
         deletes
     }
 
-    fn delete_in_chunks<F>(table: Table, ids: &[&GraphqlId], conditions: F) -> Vec<Delete>
+    fn delete_in_chunks<F>(table: Table, ids: &[&GraphqlId], parameter_limit: usize, conditions: F) -> Vec<Delete>
     where
         F: Fn(&[&GraphqlId]) -> Compare,
     {
-        ids.chunks(Self::PARAMETER_LIMIT)
+        ids.chunks(parameter_limit)
             .into_iter()
             .map(|chunk| Delete::from_table(table.clone()).so_that(conditions(chunk)))
             .collect()
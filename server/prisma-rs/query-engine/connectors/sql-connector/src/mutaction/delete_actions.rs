@@ -1,36 +1,92 @@
-use crate::{error::SqlError, SqlResult};
+use crate::{error::SqlError, mutaction::MutationBuilder, SqlResult};
+use chrono::{DateTime, Utc};
 use prisma_models::prelude::*;
 use prisma_query::ast::*;
+use std::{collections::HashSet, sync::Arc};
 
-/// Checks to be executed when deleting data.
+/// Checks and follow-up actions to be executed when deleting data.
 pub struct DeleteActions;
 
 impl DeleteActions {
-    /// A model can be required in another model, preventing the deletion.
-    /// Therefore we must check if any other model in the data model sets
-    /// `is_required` for this record.
+    /// The queries deleting the given records, following the `onDelete`
+    /// action of every relation they are in:
     ///
-    /// The closure is called with a `SELECT` statement to be executed in the
-    /// connector, giving the connector the possibility to return an optional
-    /// `GraphqlID` from the database, such as trying to read a row from the
-    /// `SELECT`.
-    pub fn check_relation_violations<F>(model: ModelRef, ids: &[&GraphqlId], mut f: F) -> SqlResult<()>
+    /// - `Cascade` deletes the related records, recursively
+    /// - `SetNull` removes the links to the related records
+    /// - `Restrict` fails if any related record exists
+    ///
+    /// Cascading into a model with soft deletes sets `deletedAt` to `now` on
+    /// the related records instead, they keep their own relations.
+    ///
+    /// Deleting a record other records require fails as well, unless the
+    /// requiring records are deleted in the same go. Records reached more than
+    /// once through cyclic relations are deleted only once.
+    ///
+    /// The closure is called with `SELECT` statements to be executed in the
+    /// connector, returning the ids of the related records. No statement has
+    /// more than `parameter_limit` parameters.
+    pub fn delete_cascading<F>(
+        model: ModelRef,
+        ids: &[&GraphqlId],
+        now: DateTime<Utc>,
+        parameter_limit: usize,
+        mut f: F,
+    ) -> SqlResult<Vec<Query>>
     where
-        F: FnMut(Select) -> SqlResult<Option<GraphqlId>>,
+        F: FnMut(Select) -> SqlResult<Vec<GraphqlId>>,
     {
-        for rf in model.internal_data_model().fields_requiring_model(model) {
-            let relation = rf.relation();
+        let mut deleted: HashSet<(String, GraphqlId)> = HashSet::new();
+        let mut deletions: Vec<(ModelRef, Vec<GraphqlId>)> = Vec::new();
+        let mut soft_deletions: Vec<(ModelRef, Vec<GraphqlId>)> = Vec::new();
+        let mut dependents: Vec<(RelationFieldRef, Vec<GraphqlId>)> = Vec::new();
+        let mut queue = vec![(model, ids.iter().map(|id| (*id).clone()).collect::<Vec<GraphqlId>>())];
+
+        while let Some((model, ids)) = queue.pop() {
+            let ids: Vec<GraphqlId> = ids
+                .into_iter()
+                .filter(|id| deleted.insert((model.name.clone(), id.clone())))
+                .collect();
 
-            let condition = rf
-                .opposite_column()
-                .in_selection(ids.to_vec())
-                .and(rf.relation_column().is_not_null());
+            if ids.is_empty() {
+                continue;
+            }
 
-            let select = Select::from_table(relation.relation_table())
-                .column(rf.opposite_column())
-                .so_that(condition);
+            for rf in model.fields().relation() {
+                let mut related_ids = Vec::new();
+
+                for select in Self::related_ids(&rf, &ids, parameter_limit) {
+                    related_ids.append(&mut f(select)?);
+                }
+
+                if related_ids.is_empty() {
+                    continue;
+                }
+
+                let related_field = rf.related_field();
+
+                let related_model = rf.related_model();
+
+                if rf.on_delete().is_cascade() && related_model.is_soft_delete {
+                    soft_deletions.push((related_model, related_ids));
+                } else if rf.on_delete().is_cascade() {
+                    queue.push((related_model, related_ids));
+                } else if rf.on_delete().is_restrict() || (related_field.is_required && !related_field.is_list) {
+                    dependents.push((rf, related_ids));
+                }
+            }
+
+            deletions.push((model, ids));
+        }
+
+        for (rf, related_ids) in dependents {
+            let model_name = rf.related_model().name.clone();
+
+            if related_ids
+                .into_iter()
+                .any(|id| !deleted.contains(&(model_name.clone(), id)))
+            {
+                let relation = rf.relation();
 
-            if let Some(_) = f(select)? {
                 return Err(SqlError::RelationViolation {
                     relation_name: relation.name.clone(),
                     model_a_name: relation.model_a().name.clone(),
@@ -39,6 +95,78 @@ impl DeleteActions {
             }
         }
 
-        Ok(())
+        let mut queries: Vec<Query> = deletions
+            .iter()
+            .flat_map(|(model, ids)| Self::remove_links(Arc::clone(model), ids, parameter_limit))
+            .collect();
+
+        for (model, ids) in soft_deletions.iter() {
+            let ids: Vec<&GraphqlId> = ids.iter().collect();
+
+            for update in MutationBuilder::soft_delete_many(Arc::clone(model), ids.as_slice(), now, parameter_limit) {
+                queries.push(update.into());
+            }
+        }
+
+        // The records found last depend on the ones found before them.
+        for (model, ids) in deletions.iter().rev() {
+            let ids: Vec<&GraphqlId> = ids.iter().collect();
+
+            for delete in MutationBuilder::delete_many(Arc::clone(model), ids.as_slice(), parameter_limit) {
+                queries.push(delete.into());
+            }
+        }
+
+        Ok(queries)
+    }
+
+    /// The records related to `ids` through the given field.
+    fn related_ids(rf: &RelationFieldRef, ids: &[GraphqlId], parameter_limit: usize) -> Vec<Select> {
+        let relation = rf.relation();
+
+        ids.chunks(parameter_limit)
+            .map(|chunk| {
+                let condition = rf
+                    .relation_column()
+                    .in_selection(chunk.to_vec())
+                    .and(rf.opposite_column().is_not_null());
+
+                Select::from_table(relation.relation_table())
+                    .column(rf.opposite_column())
+                    .so_that(condition)
+            })
+            .collect()
+    }
+
+    /// Links not stored in the records' own rows: relation table rows are
+    /// deleted, foreign keys in other tables set to `NULL`. Foreign keys in
+    /// cascaded records go away with them.
+    fn remove_links(model: ModelRef, ids: &[GraphqlId], parameter_limit: usize) -> Vec<Query> {
+        let mut queries = Vec::new();
+
+        for rf in model.fields().relation() {
+            let relation = rf.relation();
+
+            if rf.relation_is_inlined_in_parent() || (relation.is_inline_relation() && rf.on_delete().is_cascade()) {
+                continue;
+            }
+
+            for chunk in ids.chunks(parameter_limit) {
+                let condition = rf.relation_column().in_selection(chunk.to_vec());
+
+                let query: Query = if relation.is_inline_relation() {
+                    Update::table(relation.relation_table())
+                        .set(rf.relation_column().name, PrismaValue::Null)
+                        .so_that(condition)
+                        .into()
+                } else {
+                    Delete::from_table(relation.relation_table()).so_that(condition).into()
+                };
+
+                queries.push(query);
+            }
+        }
+
+        queries
     }
 }
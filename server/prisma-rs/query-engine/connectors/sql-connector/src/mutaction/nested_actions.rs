@@ -1,10 +1,12 @@
 mod nested_connect;
+mod nested_connect_or_create;
 mod nested_create_node;
 mod nested_delete_node;
 mod nested_disconnect;
 mod nested_set;
 
 pub use nested_connect::*;
+pub use nested_connect_or_create::*;
 pub use nested_create_node::*;
 pub use nested_delete_node::*;
 pub use nested_disconnect::*;
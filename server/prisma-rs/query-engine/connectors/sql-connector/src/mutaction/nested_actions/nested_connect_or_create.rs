@@ -0,0 +1,38 @@
+use super::*;
+use crate::SqlResult;
+use connector::mutaction::{NestedConnect, NestedConnectOrCreate};
+use prisma_models::*;
+use prisma_query::ast::*;
+use std::sync::Arc;
+
+/// Connecting an existing record follows the rules of `NestedConnect`, creating
+/// one the rules of `NestedCreateNode`.
+impl NestedActions for NestedConnectOrCreate {
+    fn relation_field(&self) -> RelationFieldRef {
+        self.relation_field.clone()
+    }
+
+    fn relation(&self) -> RelationRef {
+        self.relation_field().relation()
+    }
+
+    fn required_check(&self, parent_id: &GraphqlId) -> SqlResult<Option<(Select, ResultCheck)>> {
+        as_connect(self).required_check(parent_id)
+    }
+
+    fn parent_removal(&self, parent_id: &GraphqlId) -> Option<Query> {
+        as_connect(self).parent_removal(parent_id)
+    }
+
+    fn child_removal(&self, child_id: &GraphqlId) -> Option<Query> {
+        as_connect(self).child_removal(child_id)
+    }
+}
+
+fn as_connect(coc: &NestedConnectOrCreate) -> NestedConnect {
+    NestedConnect {
+        relation_field: Arc::clone(&coc.relation_field),
+        where_: coc.where_.clone(),
+        top_is_create: coc.top_is_create,
+    }
+}
@@ -1,3 +1,5 @@
+use crate::expression::{search_index, ColumnRef, SqlExpression};
+use connector::Relevance;
 use prisma_models::prelude::*;
 use prisma_query::ast::*;
 
@@ -16,6 +18,25 @@ impl Ordering {
         )
     }
 
+    /// Most relevant records first when descending. Ties are ordered by id.
+    pub fn by_relevance(model: ModelRef, relevance: &Relevance, reverse: bool) -> OrderVec {
+        let rank = SqlExpression::Rank {
+            column: ColumnRef::new(&relevance.field, None),
+            index: search_index(&model),
+            query: relevance.query.clone(),
+        }
+        .into_column();
+
+        let id = model.fields().id().as_column();
+
+        match (relevance.sort_order, reverse) {
+            (SortOrder::Ascending, true) => vec![rank.descend(), id.descend()],
+            (SortOrder::Descending, true) => vec![rank.ascend(), id.descend()],
+            (SortOrder::Ascending, false) => vec![rank.ascend(), id.ascend()],
+            (SortOrder::Descending, false) => vec![rank.descend(), id.ascend()],
+        }
+    }
+
     pub fn internal<C>(second_field: C, order_by: Option<&OrderBy>, reverse: bool) -> OrderVec
     where
         C: Into<Column>,
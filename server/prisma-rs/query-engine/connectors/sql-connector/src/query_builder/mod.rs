@@ -43,8 +43,10 @@ impl SelectDefinition for Select {
 impl SelectDefinition for QueryArguments {
     fn into_select(self, model: ModelRef) -> Select {
         let cursor: ConditionTree = CursorCondition::build(&self, Arc::clone(&model));
-        let order_by = self.order_by;
-        let ordering = Ordering::for_model(Arc::clone(&model), order_by.as_ref(), self.last.is_some());
+        let ordering = match self.relevance {
+            Some(ref relevance) => Ordering::by_relevance(Arc::clone(&model), relevance, self.last.is_some()),
+            None => Ordering::for_model(Arc::clone(&model), self.order_by.as_ref(), self.last.is_some()),
+        };
 
         let filter: ConditionTree = self
             .filter
@@ -57,6 +59,11 @@ impl SelectDefinition for QueryArguments {
             (filter, cursor) => ConditionTree::and(filter, cursor),
         };
 
+        let conditions = match self.include_deleted {
+            true => conditions,
+            false => QueryBuilder::exclude_deleted(&model, conditions),
+        };
+
         let (skip, limit) = match self.last.or(self.first) {
             Some(c) => (self.skip.unwrap_or(0), Some(c + 1)), // +1 to see if there's more data
             None => (self.skip.unwrap_or(0), None),
@@ -107,15 +114,28 @@ impl QueryBuilder {
     }
 
     pub fn get_scalar_list_values_by_node_ids(list_field: ScalarFieldRef, node_ids: Vec<GraphqlId>) -> Select {
+        if list_field.is_native_list() {
+            let model = list_field.model();
+            let id_column = model.fields().id().as_column();
+
+            return Select::from_table(model.table())
+                .column(id_column.clone())
+                .column(list_field.as_column())
+                .so_that(id_column.in_selection(node_ids));
+        }
+
         let table = list_field.scalar_list_table().table();
 
         // I vant to saak your blaad... - Vlad the Impaler
         let vhere = "nodeId".in_selection(node_ids);
 
+        // Values are grouped by node, in the order of their positions
         let query = Select::from_table(table)
             .column("nodeId")
             .column("value")
-            .so_that(vhere);
+            .so_that(vhere)
+            .order_by(Column::from("nodeId").ascend())
+            .order_by(Column::from("position").ascend());
 
         query
     }
@@ -138,4 +158,23 @@ impl QueryBuilder {
     pub fn count_by_table(database: &str, table: &str) -> Select {
         Select::from_table((database, table)).value(count(asterisk()))
     }
+
+    /// Adds a condition leaving out soft-deleted records, if the model has
+    /// soft deletes.
+    pub fn exclude_deleted(model: &Model, conditions: ConditionTree) -> ConditionTree {
+        Self::exclude_deleted_aliased(model, None, conditions)
+    }
+
+    /// `exclude_deleted` for a model table selected under an alias.
+    pub fn exclude_deleted_aliased(model: &Model, alias: Option<String>, conditions: ConditionTree) -> ConditionTree {
+        let is_live = match model.fields().deleted_at() {
+            Some(field) => field.as_column().opt_table(alias).is_null(),
+            None => return conditions,
+        };
+
+        match conditions {
+            ConditionTree::NoCondition => is_live.into(),
+            conditions => ConditionTree::and(conditions, is_live),
+        }
+    }
 }
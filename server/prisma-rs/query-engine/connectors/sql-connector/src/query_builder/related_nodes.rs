@@ -1,3 +1,4 @@
+use super::QueryBuilder;
 use crate::{cursor_condition::CursorCondition, filter_conversion::AliasedCondition, ordering::Ordering};
 use connector::QueryArguments;
 use prisma_models::prelude::*;
@@ -41,6 +42,11 @@ impl<'a> RelatedNodesQueryBuilder<'a> {
             .map(|f| f.aliased_cond(None))
             .unwrap_or(ConditionTree::NoCondition);
 
+        let conditions = match query_arguments.include_deleted {
+            true => conditions,
+            false => QueryBuilder::exclude_deleted(&related_model, conditions),
+        };
+
         let reverse_order = query_arguments.last.is_some();
 
         RelatedNodesQueryBuilder {
@@ -18,8 +18,8 @@ where
         node_selector: &NodeSelector,
         selected_fields: &SelectedFields,
     ) -> ConnectorResult<Option<SingleNode>> {
-        let db_name = &node_selector.field.model().internal_data_model().db_name;
-        let query = QueryBuilder::get_nodes(node_selector.field.model(), selected_fields, node_selector);
+        let db_name = &node_selector.model().internal_data_model().db_name;
+        let query = QueryBuilder::get_nodes(node_selector.model(), selected_fields, node_selector);
         let field_names = selected_fields.names();
         let idents = selected_fields.type_identifiers();
 
@@ -146,9 +146,17 @@ where
         let mut list_values = Vec::new();
 
         for (node_id, elements) in &results.into_iter().group_by(|ele| ele.node_id.clone()) {
+            // Native lists come as one array per node instead of a row per element
             let values = ScalarListValues {
                 node_id,
-                values: elements.into_iter().map(|e| e.value).collect(),
+                values: elements
+                    .into_iter()
+                    .flat_map(|e| match e.value {
+                        PrismaValue::List(Some(values)) => values,
+                        PrismaValue::List(None) | PrismaValue::Null => Vec::new(),
+                        value => vec![value],
+                    })
+                    .collect(),
             };
             list_values.push(values);
         }
@@ -36,6 +36,9 @@ pub trait Transaction {
     /// Write to the database, returning the change count and last id inserted.
     fn write(&mut self, q: Query) -> SqlResult<Option<GraphqlId>>;
 
+    /// Write to the database, returning the number of rows changed.
+    fn write_count(&mut self, q: Query) -> SqlResult<usize>;
+
     /// Select multiple rows from the database.
     fn filter(&mut self, q: Select, idents: &[TypeIdentifier]) -> SqlResult<Vec<SqlRow>>;
 
@@ -44,6 +47,17 @@ pub trait Transaction {
     /// Provides user an escape hatch for using the database directly.
     fn raw(&mut self, q: RawQuery) -> SqlResult<Value>;
 
+    /// Advance a database sequence, returning its new value.
+    fn next_sequence_value(&mut self, db_name: &str, sequence: &Sequence) -> SqlResult<i64>;
+
+    /// The maximum number of parameters the database accepts in one statement.
+    fn parameter_limit(&self) -> usize;
+
+    /// Insert, or update the record conflicting on the selected fields with the
+    /// `SET` of the update, in one statement. Returns the id of the record and
+    /// whether it was inserted, the last insert row id for inserts on SQLite.
+    fn upsert(&mut self, node_selector: &NodeSelector, insert: Insert, update: Update) -> SqlResult<(GraphqlId, bool)>;
+
     /// Insert to the database. On success returns the last insert row id.
     fn insert(&mut self, q: Insert) -> SqlResult<Option<GraphqlId>> {
         Ok(self.write(q.into())?)
@@ -65,7 +79,7 @@ pub trait Transaction {
     fn find_record(&mut self, node_selector: &NodeSelector) -> SqlResult<SingleNode> {
         use SqlError::*;
 
-        let model = node_selector.field.model();
+        let model = node_selector.model();
         let selected_fields = SelectedFields::from(Arc::clone(&model));
         let select = QueryBuilder::get_nodes(model, &selected_fields, node_selector);
         let idents = selected_fields.type_identifiers();
@@ -98,7 +112,7 @@ pub trait Transaction {
 
     /// Read the first column from the first row as an `GraphqlId`.
     fn find_id(&mut self, node_selector: &NodeSelector) -> SqlResult<GraphqlId> {
-        let model = node_selector.field.model();
+        let model = node_selector.model();
         let filter = Filter::from(node_selector.clone());
 
         let id = self
@@ -112,9 +126,11 @@ pub trait Transaction {
 
     /// Read the all columns as an `GraphqlId`
     fn filter_ids(&mut self, model: ModelRef, filter: Filter) -> SqlResult<Vec<GraphqlId>> {
+        let conditions = QueryBuilder::exclude_deleted(&model, filter.aliased_cond(None));
+
         let select = Select::from_table(model.table())
             .column(model.fields().id().as_column())
-            .so_that(filter.aliased_cond(None));
+            .so_that(conditions);
 
         self.select_ids(select)
     }
@@ -184,6 +200,8 @@ pub trait Transaction {
             None => conditions.into(),
         };
 
+        let conditions = QueryBuilder::exclude_deleted(&related_model, conditions);
+
         let select = Select::from_table(related_model.table())
             .column(related_model.fields().id().as_column())
             .so_that(conditions);
@@ -1,9 +1,10 @@
 use crate::{
+    error::SqlError,
     mutaction::{MutationBuilder, NestedActions},
     SqlResult, Transaction,
 };
-use prisma_models::{GraphqlId, ModelRef, PrismaArgs, PrismaListValue, RelationFieldRef};
-use std::sync::Arc;
+use prisma_models::{GraphqlId, IdStrategy, ModelRef, PrismaArgs, PrismaListValue, PrismaValue, RelationFieldRef};
+use std::{convert::TryFrom, sync::Arc};
 
 /// Creates a new root record and any associated list records to the database.
 pub fn execute<S>(
@@ -15,7 +16,22 @@ pub fn execute<S>(
 where
     S: AsRef<str>,
 {
-    let (insert, returned_id) = MutationBuilder::create_node(Arc::clone(&model), non_list_args.clone());
+    let mut args = non_list_args.clone();
+    let mut table_lists = Vec::new();
+
+    prepare_id(conn, &model, &mut args)?;
+
+    for (field_name, list_value) in list_args {
+        let field = model.fields().find_from_scalar(field_name.as_ref()).unwrap();
+
+        if field.is_native_list() {
+            args.insert(field.name.clone(), MutationBuilder::native_list_value(list_value));
+        } else {
+            table_lists.push((field, list_value));
+        }
+    }
+
+    let (insert, returned_id) = MutationBuilder::create_node(Arc::clone(&model), args);
     let last_id = conn.insert(insert)?;
 
     let id = match returned_id {
@@ -23,8 +39,7 @@ where
         None => last_id.unwrap(),
     };
 
-    for (field_name, list_value) in list_args {
-        let field = model.fields().find_from_scalar(field_name.as_ref()).unwrap();
+    for (field, list_value) in table_lists {
         let table = field.scalar_list_table();
 
         if let Some(insert) = MutationBuilder::create_scalar_list_value(table.table(), &list_value, &id) {
@@ -35,6 +50,49 @@ where
     Ok(id)
 }
 
+/// Takes the next sequence value for a record without an id. Other ids are
+/// generated when building the insert. Ids from a sequence can't be given by
+/// the client, `Auto` ids can.
+pub fn prepare_id(conn: &mut Transaction, model: &ModelRef, args: &mut PrismaArgs) -> SqlResult<()> {
+    let id_field = model.fields().id();
+    let strategy = id_field.id_strategy();
+
+    let has_id = match args.get_field_value(&id_field.name) {
+        Some(PrismaValue::Null) | None => false,
+        _ => true,
+    };
+
+    if has_id && strategy == IdStrategy::Sequence {
+        return Err(SqlError::FieldIsReadOnly {
+            field: id_field.name.clone(),
+        });
+    }
+
+    if has_id {
+        return Ok(());
+    }
+
+    match (strategy, id_field.id_sequence()) {
+        (IdStrategy::Sequence, Some(sequence)) => {
+            let db_name = &model.internal_data_model().db_name;
+            let value = conn.next_sequence_value(db_name, sequence)?;
+
+            let id = usize::try_from(value).map_err(|_| {
+                let message = format!("Sequence {} returned {}, ids can't be negative", sequence.name, value);
+                SqlError::ConversionError(failure::err_msg(message))
+            })?;
+
+            args.insert(id_field.name.clone(), GraphqlId::Int(id));
+
+            Ok(())
+        }
+        (IdStrategy::None, _) => Err(SqlError::FieldCannotBeNull {
+            field: id_field.name.clone(),
+        }),
+        _ => Ok(()),
+    }
+}
+
 /// Creates a new nested item related to a parent, including any associated
 /// list values, and is connected with the `parent_id` to the parent record.
 pub fn execute_nested<S>(
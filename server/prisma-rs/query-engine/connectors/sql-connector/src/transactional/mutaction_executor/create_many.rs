@@ -0,0 +1,176 @@
+use super::create;
+use crate::{mutaction::MutationBuilder, SqlResult, Transaction};
+use connector::{
+    filter::{Filter, ScalarCompare},
+    mutaction::CreateNodesRecord,
+};
+use prisma_models::{GraphqlId, ModelRef, PrismaArgs, PrismaListValue, PrismaValue};
+use prisma_query::ast::*;
+use std::{
+    collections::{BTreeMap, HashSet},
+    convert::TryFrom,
+    sync::Arc,
+};
+
+/// Creates new root records with multi-row inserts, followed by the values of
+/// their lists. The statements are split to stay within the parameter limit of
+/// the database.
+///
+/// With `skip_duplicates`, records violating a unique constraint are left out.
+/// Records with list values and an id generated by the database are created
+/// one by one, so their lists can be linked to them.
+///
+/// Will return the number of records created.
+pub fn execute(
+    conn: &mut Transaction,
+    model: ModelRef,
+    records: &[CreateNodesRecord],
+    skip_duplicates: bool,
+) -> SqlResult<usize> {
+    let mut count = 0;
+    let mut rows = Vec::new();
+    let mut table_lists: BTreeMap<String, Vec<(GraphqlId, &PrismaListValue)>> = BTreeMap::new();
+
+    for record in records {
+        let mut args = record.non_list_args.clone();
+        let mut lists = Vec::new();
+
+        for (field_name, list_value) in record.list_args.iter() {
+            let field = model.fields().find_from_scalar(field_name).unwrap();
+
+            if field.is_native_list() {
+                args.insert(field.name.clone(), MutationBuilder::native_list_value(list_value));
+            } else {
+                lists.push((field.name.clone(), list_value));
+            }
+        }
+
+        match take_id(conn, &model, &mut args)? {
+            Some(id) => {
+                for (field_name, list_value) in lists {
+                    let values = table_lists.entry(field_name).or_insert_with(Vec::new);
+                    values.push((id.clone(), list_value));
+                }
+
+                rows.push(args);
+            }
+            None if !lists.is_empty() => {
+                if skip_duplicates && is_duplicate(conn, &model, &record.non_list_args)? {
+                    continue;
+                }
+
+                create::execute(conn, Arc::clone(&model), &record.non_list_args, &record.list_args)?;
+                count += 1;
+            }
+            None => rows.push(args),
+        }
+    }
+
+    let parameter_limit = conn.parameter_limit();
+    let check_created = skip_duplicates && !table_lists.is_empty();
+
+    let existing = match check_created {
+        true => existing_ids(conn, &model, &table_lists)?,
+        false => HashSet::new(),
+    };
+
+    for insert in MutationBuilder::create_nodes(Arc::clone(&model), rows, parameter_limit, skip_duplicates) {
+        count += conn.write_count(insert)?;
+    }
+
+    // Skipped records must not get the list values given for them.
+    if check_created {
+        let created = existing_ids(conn, &model, &table_lists)?;
+
+        for values in table_lists.values_mut() {
+            values.retain(|(id, _)| created.contains(id) && !existing.contains(id));
+        }
+    }
+
+    for (field_name, values) in table_lists {
+        let field = model.fields().find_from_scalar(&field_name).unwrap();
+        let table = field.scalar_list_table();
+
+        for insert in MutationBuilder::create_scalar_list_values(&table, values.as_slice(), parameter_limit) {
+            conn.insert(insert)?;
+        }
+    }
+
+    Ok(count)
+}
+
+/// The id of a new record, taken from the arguments or generated and added to
+/// them. `None` if the database generates the id.
+fn take_id(conn: &mut Transaction, model: &ModelRef, args: &mut PrismaArgs) -> SqlResult<Option<GraphqlId>> {
+    let id_field = model.fields().id();
+    create::prepare_id(conn, model, args)?;
+
+    match args.take_field_value(&id_field.name) {
+        Some(PrismaValue::Null) | None => (),
+        Some(value) => {
+            let id = GraphqlId::try_from(&value).ok();
+            args.insert(id_field.name.clone(), value);
+
+            return Ok(id);
+        }
+    }
+
+    let id = match id_field.is_auto_generated {
+        true => None,
+        false => model.generate_id(),
+    };
+
+    if let Some(ref id) = id {
+        args.insert(id_field.name.clone(), id.clone());
+    }
+
+    Ok(id)
+}
+
+/// Whether a record with one of the unique values of `args` exists already.
+fn is_duplicate(conn: &mut Transaction, model: &ModelRef, args: &PrismaArgs) -> SqlResult<bool> {
+    let filters: Vec<Filter> = model
+        .fields()
+        .scalar()
+        .into_iter()
+        .filter(|field| field.is_unique)
+        .filter_map(|field| match args.get_field_value(&field.name) {
+            Some(PrismaValue::Null) | None => None,
+            Some(value) => Some(field.equals(value.clone())),
+        })
+        .collect();
+
+    if filters.is_empty() {
+        return Ok(false);
+    }
+
+    let ids = conn.filter_ids(Arc::clone(model), Filter::or(filters))?;
+
+    Ok(!ids.is_empty())
+}
+
+/// The ids of the records with list values already in the table.
+fn existing_ids(
+    conn: &mut Transaction,
+    model: &ModelRef,
+    table_lists: &BTreeMap<String, Vec<(GraphqlId, &PrismaListValue)>>,
+) -> SqlResult<HashSet<GraphqlId>> {
+    let ids: HashSet<GraphqlId> = table_lists
+        .values()
+        .flat_map(|values| values.iter().map(|(id, _)| id.clone()))
+        .collect();
+
+    let ids: Vec<GraphqlId> = ids.into_iter().collect();
+    let id_column = model.fields().id().as_column();
+    let mut existing = HashSet::new();
+
+    for chunk in ids.chunks(conn.parameter_limit()) {
+        let select = Select::from_table(model.table())
+            .column(id_column.clone())
+            .so_that(id_column.clone().in_selection(chunk.to_vec()));
+
+        existing.extend(conn.select_ids(select)?);
+    }
+
+    Ok(existing)
+}
@@ -1,33 +1,66 @@
+use super::versions;
 use crate::{
     error::SqlError,
     mutaction::{DeleteActions, MutationBuilder, NestedActions},
     SqlResult, Transaction,
 };
+use chrono::{DateTime, Utc};
 use connector::{error::NodeSelectorInfo, filter::NodeSelector};
-use prisma_models::{GraphqlId, RelationFieldRef, SingleNode};
+use prisma_models::{GraphqlId, ModelRef, PrismaValue, RelationFieldRef, SingleNode};
 use std::sync::Arc;
 
 /// A top level delete that removes one record. Violating any relations or a
-/// non-existing record will cause an error.
+/// non-existing record will cause an error, as will a record that no longer
+/// has the `expected_version`.
 ///
 /// Will return the deleted record if the delete was successful.
-pub fn execute(conn: &mut Transaction, node_selector: &NodeSelector) -> SqlResult<SingleNode> {
-    let model = node_selector.field.model();
+pub fn execute(
+    conn: &mut Transaction,
+    node_selector: &NodeSelector,
+    expected_version: Option<&PrismaValue>,
+    now: DateTime<Utc>,
+) -> SqlResult<SingleNode> {
+    let model = node_selector.model();
     let record = conn.find_record(node_selector)?;
     let id = record.get_id_value(Arc::clone(&model)).unwrap();
 
-    DeleteActions::check_relation_violations(Arc::clone(&model), &[&id], |select| {
-        let ids = conn.select_ids(select)?;
-        Ok(ids.into_iter().next())
-    })?;
+    versions::check(conn, &model, &id, expected_version)?;
 
-    for delete in MutationBuilder::delete_many(model, &[&id]) {
-        conn.delete(delete)?;
-    }
+    delete_records(conn, model, &[&id], now)?;
 
     Ok(record)
 }
 
+/// Deletes the records and everything their relations' `onDelete` actions
+/// ask for, failing on restricted or required relations.
+///
+/// Records of models with soft deletes only get their `deletedAt` set to
+/// `now` and keep their relations.
+pub fn delete_records(
+    conn: &mut Transaction,
+    model: ModelRef,
+    ids: &[&GraphqlId],
+    now: DateTime<Utc>,
+) -> SqlResult<()> {
+    let parameter_limit = conn.parameter_limit();
+
+    if model.is_soft_delete {
+        for update in MutationBuilder::soft_delete_many(model, ids, now, parameter_limit) {
+            conn.update(update)?;
+        }
+
+        return Ok(());
+    }
+
+    let queries = DeleteActions::delete_cascading(model, ids, now, parameter_limit, |select| conn.select_ids(select))?;
+
+    for query in queries {
+        conn.write(query)?;
+    }
+
+    Ok(())
+}
+
 /// A nested delete that removes one item related to the given `parent_id`.
 /// If no `RecordFinder` is given, will delete the first item from the
 /// table.
@@ -43,6 +76,7 @@ pub fn execute_nested(
     actions: &NestedActions,
     node_selector: &Option<NodeSelector>,
     relation_field: RelationFieldRef,
+    now: DateTime<Utc>,
 ) -> SqlResult<()> {
     if let Some(ref node_selector) = node_selector {
         conn.find_id(node_selector)?;
@@ -77,16 +111,5 @@ pub fn execute_nested(
         check.call_box(ids.into_iter().next().is_some())?;
     }
 
-    let related_model = relation_field.related_model();
-
-    DeleteActions::check_relation_violations(related_model, &[&child_id; 1], |select| {
-        let ids = conn.select_ids(select)?;
-        Ok(ids.into_iter().next())
-    })?;
-
-    for delete in MutationBuilder::delete_many(relation_field.related_model(), &[&child_id]) {
-        conn.delete(delete)?;
-    }
-
-    Ok(())
+    delete_records(conn, relation_field.related_model(), &[&child_id], now)
 }
@@ -1,7 +1,6 @@
-use crate::{
-    mutaction::{DeleteActions, MutationBuilder},
-    SqlResult, Transaction,
-};
+use super::delete;
+use crate::{SqlResult, Transaction};
+use chrono::{DateTime, Utc};
 use connector::filter::Filter;
 use prisma_models::{GraphqlId, ModelRef, RelationFieldRef};
 use std::sync::Arc;
@@ -10,7 +9,7 @@ use std::sync::Arc;
 /// any relations will cause an error.
 ///
 /// Will return the number records deleted.
-pub fn execute(conn: &mut Transaction, model: ModelRef, filter: &Filter) -> SqlResult<usize> {
+pub fn execute(conn: &mut Transaction, model: ModelRef, filter: &Filter, now: DateTime<Utc>) -> SqlResult<usize> {
     let ids = conn.filter_ids(Arc::clone(&model), filter.clone())?;
     let ids: Vec<&GraphqlId> = ids.iter().map(|id| &*id).collect();
     let count = ids.len();
@@ -19,14 +18,7 @@ pub fn execute(conn: &mut Transaction, model: ModelRef, filter: &Filter) -> SqlR
         return Ok(count);
     }
 
-    DeleteActions::check_relation_violations(Arc::clone(&model), ids.as_slice(), |select| {
-        let ids = conn.select_ids(select)?;
-        Ok(ids.into_iter().next())
-    })?;
-
-    for delete in MutationBuilder::delete_many(model, ids.as_slice()) {
-        conn.delete(delete)?;
-    }
+    delete::delete_records(conn, model, ids.as_slice(), now)?;
 
     Ok(count)
 }
@@ -39,6 +31,7 @@ pub fn execute_nested(
     parent_id: &GraphqlId,
     filter: &Option<Filter>,
     relation_field: RelationFieldRef,
+    now: DateTime<Utc>,
 ) -> SqlResult<usize> {
     let ids = conn.filter_ids_by_parents(Arc::clone(&relation_field), vec![parent_id], filter.clone())?;
     let count = ids.len();
@@ -48,16 +41,7 @@ pub fn execute_nested(
     }
 
     let ids: Vec<&GraphqlId> = ids.iter().map(|id| &*id).collect();
-    let model = relation_field.model();
-
-    DeleteActions::check_relation_violations(model, ids.as_slice(), |select| {
-        let ids = conn.select_ids(select)?;
-        Ok(ids.into_iter().next())
-    })?;
-
-    for delete in MutationBuilder::delete_many(relation_field.related_model(), ids.as_slice()) {
-        conn.delete(delete)?;
-    }
+    delete::delete_records(conn, relation_field.related_model(), ids.as_slice(), now)?;
 
     Ok(count)
 }
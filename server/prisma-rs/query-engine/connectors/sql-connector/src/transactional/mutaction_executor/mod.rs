@@ -1,12 +1,17 @@
 mod create;
+mod create_many;
 mod delete;
 mod delete_many;
 mod nested;
 mod relation;
+mod timestamps;
 mod update;
 mod update_many;
+mod upsert;
+mod versions;
 
 use crate::{database::SqlDatabase, error::SqlError, RawQuery, SqlResult, Transaction, Transactional};
+use chrono::{DateTime, Utc};
 use connector::{mutaction::*, ConnectorResult, DatabaseMutactionExecutor};
 use serde_json::Value;
 use std::sync::Arc;
@@ -18,12 +23,22 @@ where
     fn execute(
         &self,
         db_name: String,
-        mutaction: TopLevelDatabaseMutaction,
+        mut mutaction: TopLevelDatabaseMutaction,
     ) -> ConnectorResult<DatabaseMutactionResult> {
+        // Timestamps and soft deletes share the time of the transaction.
+        let now = Utc::now();
+
+        timestamps::apply(&mut mutaction, now)?;
+        versions::apply(&mut mutaction)?;
+
         let result = self.executor.with_transaction(&db_name, |conn: &mut Transaction| {
-            fn create(conn: &mut Transaction, cn: &CreateNode) -> SqlResult<DatabaseMutactionResult> {
+            fn create(
+                conn: &mut Transaction,
+                cn: &CreateNode,
+                now: DateTime<Utc>,
+            ) -> SqlResult<DatabaseMutactionResult> {
                 let parent_id = create::execute(conn, Arc::clone(&cn.model), &cn.non_list_args, &cn.list_args)?;
-                nested::execute(conn, &cn.nested_mutactions, &parent_id)?;
+                nested::execute(conn, &cn.nested_mutactions, &parent_id, now)?;
 
                 Ok(DatabaseMutactionResult {
                     identifier: Identifier::Id(parent_id),
@@ -31,9 +46,20 @@ where
                 })
             }
 
-            fn update(conn: &mut Transaction, un: &UpdateNode) -> SqlResult<DatabaseMutactionResult> {
-                let parent_id = update::execute(conn, &un.where_, &un.non_list_args, &un.list_args)?;
-                nested::execute(conn, &un.nested_mutactions, &parent_id)?;
+            fn update(
+                conn: &mut Transaction,
+                un: &UpdateNode,
+                now: DateTime<Utc>,
+            ) -> SqlResult<DatabaseMutactionResult> {
+                let parent_id = update::execute(
+                    conn,
+                    &un.where_,
+                    &un.non_list_args,
+                    &un.list_args,
+                    &un.list_operations,
+                    un.expected_version.as_ref(),
+                )?;
+                nested::execute(conn, &un.nested_mutactions, &parent_id, now)?;
 
                 Ok(DatabaseMutactionResult {
                     identifier: Identifier::Id(parent_id),
@@ -42,13 +68,24 @@ where
             }
 
             match mutaction {
-                TopLevelDatabaseMutaction::CreateNode(ref cn) => Ok(create(conn, cn)?),
-                TopLevelDatabaseMutaction::UpdateNode(ref un) => Ok(update(conn, un)?),
-                TopLevelDatabaseMutaction::UpsertNode(ref ups) => match conn.find_id(&ups.where_) {
-                    Err(_e @ SqlError::NodeNotFoundForWhere { .. }) => Ok(create(conn, &ups.create)?),
-                    Err(e) => return Err(e.into()),
-                    Ok(_) => Ok(update(conn, &ups.update)?),
+                TopLevelDatabaseMutaction::CreateNode(ref cn) => Ok(create(conn, cn, now)?),
+                TopLevelDatabaseMutaction::UpdateNode(ref un) => Ok(update(conn, un, now)?),
+                TopLevelDatabaseMutaction::UpsertNode(ref ups) => match upsert::execute(conn, ups)? {
+                    Some(result) => Ok(result),
+                    None => match conn.find_id(&ups.where_) {
+                        Err(_e @ SqlError::NodeNotFoundForWhere { .. }) => Ok(create(conn, &ups.create, now)?),
+                        Err(e) => return Err(e.into()),
+                        Ok(_) => Ok(update(conn, &ups.update, now)?),
+                    },
                 },
+                TopLevelDatabaseMutaction::CreateNodes(ref cns) => {
+                    let count = create_many::execute(conn, Arc::clone(&cns.model), &cns.records, cns.skip_duplicates)?;
+
+                    Ok(DatabaseMutactionResult {
+                        identifier: Identifier::Count(count),
+                        typ: DatabaseMutactionResultType::Many,
+                    })
+                }
                 TopLevelDatabaseMutaction::UpdateNodes(ref uns) => {
                     let count = update_many::execute(
                         conn,
@@ -56,6 +93,7 @@ where
                         &uns.filter,
                         &uns.non_list_args,
                         &uns.list_args,
+                        &uns.list_operations,
                     )?;
 
                     Ok(DatabaseMutactionResult {
@@ -64,7 +102,7 @@ where
                     })
                 }
                 TopLevelDatabaseMutaction::DeleteNode(ref dn) => {
-                    let node = delete::execute(conn, &dn.where_)?;
+                    let node = delete::execute(conn, &dn.where_, dn.expected_version.as_ref(), now)?;
 
                     Ok(DatabaseMutactionResult {
                         identifier: Identifier::Node(node),
@@ -72,7 +110,7 @@ where
                     })
                 }
                 TopLevelDatabaseMutaction::DeleteNodes(ref dns) => {
-                    let count = delete_many::execute(conn, Arc::clone(&dns.model), &dns.filter)?;
+                    let count = delete_many::execute(conn, Arc::clone(&dns.model), &dns.filter, now)?;
 
                     Ok(DatabaseMutactionResult {
                         identifier: Identifier::Count(count),
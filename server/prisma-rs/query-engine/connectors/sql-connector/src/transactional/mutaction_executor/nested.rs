@@ -1,11 +1,25 @@
 use super::{create, delete, delete_many, relation, update, update_many};
 use crate::{error::SqlError, SqlResult, Transaction};
+use chrono::{DateTime, Utc};
 use connector::mutaction::*;
 use prisma_models::GraphqlId;
+use prisma_query::ast::Query;
 use std::sync::Arc;
 
-pub fn execute(conn: &mut Transaction, mutactions: &NestedMutactions, parent_id: &GraphqlId) -> SqlResult<()> {
-    fn create(conn: &mut Transaction, parent_id: &GraphqlId, cn: &NestedCreateNode) -> SqlResult<()> {
+/// Executes the nested mutactions of the parent record, `now` is the time
+/// soft-deleted records are deleted at.
+pub fn execute(
+    conn: &mut Transaction,
+    mutactions: &NestedMutactions,
+    parent_id: &GraphqlId,
+    now: DateTime<Utc>,
+) -> SqlResult<()> {
+    fn create(
+        conn: &mut Transaction,
+        parent_id: &GraphqlId,
+        cn: &NestedCreateNode,
+        now: DateTime<Utc>,
+    ) -> SqlResult<()> {
         let parent_id = create::execute_nested(
             conn,
             parent_id,
@@ -15,12 +29,17 @@ pub fn execute(conn: &mut Transaction, mutactions: &NestedMutactions, parent_id:
             &cn.list_args,
         )?;
 
-        execute(conn, &cn.nested_mutactions, &parent_id)?;
+        execute(conn, &cn.nested_mutactions, &parent_id, now)?;
 
         Ok(())
     }
 
-    fn update(conn: &mut Transaction, parent_id: &GraphqlId, un: &NestedUpdateNode) -> SqlResult<()> {
+    fn update(
+        conn: &mut Transaction,
+        parent_id: &GraphqlId,
+        un: &NestedUpdateNode,
+        now: DateTime<Utc>,
+    ) -> SqlResult<()> {
         let parent_id = update::execute_nested(
             conn,
             parent_id,
@@ -28,27 +47,64 @@ pub fn execute(conn: &mut Transaction, mutactions: &NestedMutactions, parent_id:
             Arc::clone(&un.relation_field),
             &un.non_list_args,
             &un.list_args,
+            &un.list_operations,
         )?;
 
-        execute(conn, &un.nested_mutactions, &parent_id)?;
+        execute(conn, &un.nested_mutactions, &parent_id, now)?;
 
         Ok(())
     }
 
+    fn connect_or_create(
+        conn: &mut Transaction,
+        parent_id: &GraphqlId,
+        coc: &NestedConnectOrCreate,
+        now: DateTime<Utc>,
+    ) -> SqlResult<()> {
+        let relation_field = Arc::clone(&coc.relation_field);
+
+        match conn.find_id(&coc.where_) {
+            Ok(_) => return relation::connect(conn, parent_id, coc, &coc.where_, relation_field),
+            Err(_e @ SqlError::NodeNotFoundForWhere { .. }) => (),
+            Err(e) => return Err(e),
+        }
+
+        // Another transaction can create the record after our lookup, failing
+        // the insert on a unique constraint. We connect to that record then.
+        conn.write(Query::from("SAVEPOINT connect_or_create"))?;
+
+        match create(conn, parent_id, &coc.create, now) {
+            Ok(()) => {
+                conn.write(Query::from("RELEASE SAVEPOINT connect_or_create"))?;
+                Ok(())
+            }
+            Err(e @ SqlError::UniqueConstraintViolation { .. }) => {
+                conn.write(Query::from("ROLLBACK TO SAVEPOINT connect_or_create"))?;
+
+                // The violation was not about the record we look for.
+                match relation::connect(conn, parent_id, coc, &coc.where_, relation_field) {
+                    Err(_e @ SqlError::NodeNotFoundForWhere { .. }) => Err(e),
+                    result => result,
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
     for create_node in mutactions.creates.iter() {
-        create(conn, parent_id, create_node)?;
+        create(conn, parent_id, create_node, now)?;
     }
 
     for update_node in mutactions.updates.iter() {
-        update(conn, parent_id, update_node)?;
+        update(conn, parent_id, update_node, now)?;
     }
 
     for upsert_node in mutactions.upserts.iter() {
         let id_opt = conn.find_id_by_parent(Arc::clone(&upsert_node.relation_field), parent_id, &upsert_node.where_);
 
         match id_opt {
-            Ok(_) => update(conn, parent_id, &upsert_node.update)?,
-            Err(_e @ SqlError::NodesNotConnected { .. }) => create(conn, parent_id, &upsert_node.create)?,
+            Ok(_) => update(conn, parent_id, &upsert_node.update, now)?,
+            Err(_e @ SqlError::NodesNotConnected { .. }) => create(conn, parent_id, &upsert_node.create, now)?,
             Err(e) => return Err(e),
         }
     }
@@ -60,6 +116,7 @@ pub fn execute(conn: &mut Transaction, mutactions: &NestedMutactions, parent_id:
             delete_node,
             &delete_node.where_,
             Arc::clone(&delete_node.relation_field),
+            now,
         )?;
     }
 
@@ -73,6 +130,10 @@ pub fn execute(conn: &mut Transaction, mutactions: &NestedMutactions, parent_id:
         )?;
     }
 
+    for connect_or_create in mutactions.connect_or_creates.iter() {
+        connect_or_create(conn, parent_id, connect_or_create, now)?;
+    }
+
     for set in mutactions.sets.iter() {
         relation::set(conn, &parent_id, set, &set.wheres, Arc::clone(&set.relation_field))?;
     }
@@ -89,6 +150,7 @@ pub fn execute(conn: &mut Transaction, mutactions: &NestedMutactions, parent_id:
             Arc::clone(&update_many.relation_field),
             &update_many.non_list_args,
             &update_many.list_args,
+            &update_many.list_operations,
         )?;
     }
 
@@ -98,6 +160,7 @@ pub fn execute(conn: &mut Transaction, mutactions: &NestedMutactions, parent_id:
             &parent_id,
             &delete_many.filter,
             Arc::clone(&delete_many.relation_field),
+            now,
         )?;
     }
 
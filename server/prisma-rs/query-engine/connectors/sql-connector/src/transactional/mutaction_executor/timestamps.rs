@@ -0,0 +1,126 @@
+use crate::{error::SqlError, SqlResult};
+use chrono::{DateTime, Utc};
+use connector::mutaction::*;
+use prisma_models::{ModelRef, PrismaArgs};
+use std::sync::Arc;
+
+/// Sets `createdAt` and `updatedAt` on every record the mutaction creates or
+/// updates, including nested ones. All records share the same timestamp.
+pub fn apply(mutaction: &mut TopLevelDatabaseMutaction, now: DateTime<Utc>) -> SqlResult<()> {
+    match *mutaction {
+        TopLevelDatabaseMutaction::CreateNode(ref mut cn) => create(cn, now),
+        TopLevelDatabaseMutaction::UpdateNode(ref mut un) => update(un, now),
+        TopLevelDatabaseMutaction::UpsertNode(ref mut ups) => {
+            create(&mut ups.create, now)?;
+            update(&mut ups.update, now)
+        }
+        TopLevelDatabaseMutaction::CreateNodes(ref mut cns) => {
+            for record in cns.records.iter_mut() {
+                set_created_at(&cns.model, &mut record.non_list_args, now)?;
+            }
+
+            Ok(())
+        }
+        TopLevelDatabaseMutaction::UpdateNodes(ref mut uns) => {
+            let list_causes_update = !uns.list_args.is_empty() || !uns.list_operations.is_empty();
+            set_updated_at(&uns.model, &mut uns.non_list_args, list_causes_update, now)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn create(cn: &mut CreateNode, now: DateTime<Utc>) -> SqlResult<()> {
+    set_created_at(&cn.model, &mut cn.non_list_args, now)?;
+    nested(&mut cn.nested_mutactions, now)
+}
+
+fn update(un: &mut UpdateNode, now: DateTime<Utc>) -> SqlResult<()> {
+    let list_causes_update = !un.list_args.is_empty() || !un.list_operations.is_empty();
+
+    set_updated_at(&un.where_.model(), &mut un.non_list_args, list_causes_update, now)?;
+    nested(&mut un.nested_mutactions, now)
+}
+
+fn nested_create(cn: &mut NestedCreateNode, now: DateTime<Utc>) -> SqlResult<()> {
+    set_created_at(&cn.relation_field.related_model(), &mut cn.non_list_args, now)?;
+    nested(&mut cn.nested_mutactions, now)
+}
+
+fn nested_update(un: &mut NestedUpdateNode, now: DateTime<Utc>) -> SqlResult<()> {
+    let list_causes_update = !un.list_args.is_empty() || !un.list_operations.is_empty();
+
+    set_updated_at(
+        &un.relation_field.related_model(),
+        &mut un.non_list_args,
+        list_causes_update,
+        now,
+    )?;
+    nested(&mut un.nested_mutactions, now)
+}
+
+fn nested(mutactions: &mut NestedMutactions, now: DateTime<Utc>) -> SqlResult<()> {
+    for cn in mutactions.creates.iter_mut() {
+        nested_create(cn, now)?;
+    }
+
+    for coc in mutactions.connect_or_creates.iter_mut() {
+        nested_create(&mut coc.create, now)?;
+    }
+
+    for un in mutactions.updates.iter_mut() {
+        nested_update(un, now)?;
+    }
+
+    for ups in mutactions.upserts.iter_mut() {
+        nested_create(&mut ups.create, now)?;
+        nested_update(&mut ups.update, now)?;
+    }
+
+    for uns in mutactions.update_manys.iter_mut() {
+        let list_causes_update = !uns.list_args.is_empty() || !uns.list_operations.is_empty();
+
+        set_updated_at(
+            &uns.relation_field.related_model(),
+            &mut uns.non_list_args,
+            list_causes_update,
+            now,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn set_created_at(model: &ModelRef, args: &mut PrismaArgs, now: DateTime<Utc>) -> SqlResult<()> {
+    reject_user_values(model, args)?;
+    args.add_datetimes(Arc::clone(model), now);
+
+    Ok(())
+}
+
+fn set_updated_at(
+    model: &ModelRef,
+    args: &mut PrismaArgs,
+    list_causes_update: bool,
+    now: DateTime<Utc>,
+) -> SqlResult<()> {
+    reject_user_values(model, args)?;
+    args.update_datetimes(Arc::clone(model), list_causes_update, now);
+
+    Ok(())
+}
+
+fn reject_user_values(model: &ModelRef, args: &PrismaArgs) -> SqlResult<()> {
+    let fields = model.fields();
+
+    let read_only = fields.created_at().iter().chain(fields.updated_at().iter());
+
+    for field in read_only {
+        if args.has_arg_for(&field.name) || args.operations.contains_key(&field.name) {
+            return Err(SqlError::FieldIsReadOnly {
+                field: field.name.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
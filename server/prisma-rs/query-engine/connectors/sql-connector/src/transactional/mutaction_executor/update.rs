@@ -1,32 +1,47 @@
+use super::versions;
 use crate::{mutaction::MutationBuilder, SqlResult, Transaction};
 use connector::filter::NodeSelector;
-use prisma_models::{GraphqlId, ModelRef, PrismaArgs, PrismaListValue, RelationFieldRef};
-use std::sync::Arc;
+use prisma_models::{
+    GraphqlId, ModelRef, PrismaArgs, PrismaListValue, PrismaValue, RelationFieldRef, ScalarFieldRef,
+    ScalarListOperation, ScalarListTable, TypeIdentifier,
+};
+use prisma_query::ast::*;
+use std::{collections::HashMap, convert::TryFrom, sync::Arc};
 
-/// Updates one record and any associated list record in the database.
+/// The gap between the positions of two values in a `ScalarListTable`.
+const POSITION_STEP: i64 = 1000;
+
+/// Updates one record and any associated list record in the database. With
+/// an `expected_version`, the record must still have that version.
 pub fn execute<S>(
     conn: &mut Transaction,
     node_selector: &NodeSelector,
     non_list_args: &PrismaArgs,
     list_args: &[(S, PrismaListValue)],
+    list_operations: &[(String, ScalarListOperation)],
+    expected_version: Option<&PrismaValue>,
 ) -> SqlResult<GraphqlId>
 where
     S: AsRef<str>,
 {
-    let model = node_selector.field.model();
+    let model = node_selector.model();
     let id = conn.find_id(node_selector)?;
 
+    versions::check(conn, &model, &id, expected_version)?;
+
     if let Some(update) = MutationBuilder::update_one(Arc::clone(&model), &id, non_list_args)? {
         conn.update(update)?;
     }
 
     update_list_args(conn, &[id.clone()], Arc::clone(&model), list_args)?;
+    update_list_operations(conn, &[id.clone()], Arc::clone(&model), list_operations)?;
 
     Ok(id)
 }
 
 /// Updates a nested item related to the parent, including any associated
-/// list values.
+/// list values. Nested updates take no expected version, the record is
+/// updated whatever version it has.
 pub fn execute_nested<S>(
     conn: &mut Transaction,
     parent_id: &GraphqlId,
@@ -34,6 +49,7 @@ pub fn execute_nested<S>(
     relation_field: RelationFieldRef,
     non_list_args: &PrismaArgs,
     list_args: &[(S, PrismaListValue)],
+    list_operations: &[(String, ScalarListOperation)],
 ) -> SqlResult<GraphqlId>
 where
     S: AsRef<str>,
@@ -45,7 +61,7 @@ where
     let id = conn.find_id_by_parent(Arc::clone(&relation_field), parent_id, node_selector)?;
     let node_selector = NodeSelector::from((relation_field.related_model().fields().id(), id));
 
-    execute(conn, &node_selector, non_list_args, list_args)
+    execute(conn, &node_selector, non_list_args, list_args, list_operations, None)
 }
 
 /// Updates list args related to the given records.
@@ -60,16 +76,168 @@ where
 {
     for (field_name, list_value) in list_args {
         let field = model.fields().find_from_scalar(field_name.as_ref()).unwrap();
-        let table = field.scalar_list_table();
-        let (deletes, inserts) = MutationBuilder::update_scalar_list_values(&table, &list_value, ids.to_vec());
 
-        for delete in deletes {
-            conn.delete(delete)?;
+        if field.is_native_list() {
+            let mut args = PrismaArgs::new();
+            args.insert(field.name.clone(), MutationBuilder::native_list_value(list_value));
+
+            let ids: Vec<&GraphqlId> = ids.iter().collect();
+
+            let parameter_limit = conn.parameter_limit();
+
+            for update in MutationBuilder::update_many(Arc::clone(&model), ids.as_slice(), &args, parameter_limit)? {
+                conn.update(update)?;
+            }
+        } else {
+            let table = field.scalar_list_table();
+            let parameter_limit = conn.parameter_limit();
+            let (deletes, inserts) =
+                MutationBuilder::update_scalar_list_values(&table, &list_value, ids.to_vec(), parameter_limit);
+
+            for delete in deletes {
+                conn.delete(delete)?;
+            }
+
+            for insert in inserts {
+                conn.insert(insert)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies list operations to the given records. Values in a
+/// `ScalarListTable` are inserted or deleted without touching the rest of the
+/// list, arrays of native lists are changed in place by the database.
+pub fn update_list_operations(
+    conn: &mut Transaction,
+    ids: &[GraphqlId],
+    model: ModelRef,
+    list_operations: &[(String, ScalarListOperation)],
+) -> SqlResult<()> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    for (field_name, operation) in list_operations {
+        let field = model.fields().find_from_scalar(field_name).unwrap();
+
+        if field.is_native_list() {
+            update_native_list(conn, ids, &field, operation)?;
+        } else {
+            update_list_table(conn, ids, &field, operation)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn update_list_table(
+    conn: &mut Transaction,
+    ids: &[GraphqlId],
+    field: &ScalarFieldRef,
+    operation: &ScalarListOperation,
+) -> SqlResult<()> {
+    let table = field.scalar_list_table();
+    let id_refs: Vec<&GraphqlId> = ids.iter().collect();
+    let parameter_limit = conn.parameter_limit();
+
+    let (values, append) = match operation {
+        ScalarListOperation::Push(values) => (values, true),
+        ScalarListOperation::Unshift(values) => (values, false),
+        ScalarListOperation::Remove(values) => {
+            for delete in
+                MutationBuilder::remove_scalar_list_values(&table, values, id_refs.as_slice(), parameter_limit)
+            {
+                conn.delete(delete)?;
+            }
+
+            return Ok(());
         }
+        ScalarListOperation::Unset => {
+            for delete in MutationBuilder::delete_scalar_list_values(&table, id_refs.as_slice(), parameter_limit) {
+                conn.delete(delete)?;
+            }
 
-        for insert in inserts {
-            conn.insert(insert)?;
+            return Ok(());
         }
+    };
+
+    if values.is_empty() {
+        return Ok(());
+    }
+
+    let bounds = position_bounds(conn, &table, field, ids)?;
+    let count = values.len() as i64;
+    let mut rows = Vec::new();
+
+    for id in ids {
+        // New values go after the last or before the first position.
+        let start = match (bounds.get(id), append) {
+            (Some((_, last)), true) => last + POSITION_STEP,
+            (Some((first, _)), false) => first - POSITION_STEP * count,
+            (None, _) => POSITION_STEP,
+        };
+
+        for (i, value) in values.iter().enumerate() {
+            rows.push((start + POSITION_STEP * i as i64, value.clone(), id.clone()));
+        }
+    }
+
+    for insert in MutationBuilder::create_scalar_list_rows(&table, rows, conn.parameter_limit()) {
+        conn.insert(insert)?;
+    }
+
+    Ok(())
+}
+
+/// The first and last positions of the lists of the given records, if the
+/// lists have any values.
+fn position_bounds(
+    conn: &mut Transaction,
+    table: &ScalarListTable,
+    field: &ScalarFieldRef,
+    ids: &[GraphqlId],
+) -> SqlResult<HashMap<GraphqlId, (i64, i64)>> {
+    let idents = [field.model().fields().id().type_identifier, TypeIdentifier::Int];
+    let mut bounds: HashMap<GraphqlId, (i64, i64)> = HashMap::new();
+
+    for chunk in ids.chunks(conn.parameter_limit()) {
+        let select = Select::from_table(table.table())
+            .column(table.node_id_column())
+            .column(table.position_column())
+            .so_that(table.node_id_column().in_selection(chunk.to_vec()));
+
+        for row in conn.filter(select, &idents)? {
+            let mut values = row.values.into_iter();
+
+            let id = GraphqlId::try_from(values.next().unwrap())?;
+            let position = match values.next() {
+                Some(PrismaValue::Int(position)) => position,
+                _ => continue,
+            };
+
+            let entry = bounds.entry(id).or_insert((position, position));
+            entry.0 = entry.0.min(position);
+            entry.1 = entry.1.max(position);
+        }
+    }
+
+    Ok(bounds)
+}
+
+fn update_native_list(
+    conn: &mut Transaction,
+    ids: &[GraphqlId],
+    field: &ScalarFieldRef,
+    operation: &ScalarListOperation,
+) -> SqlResult<()> {
+    let ids: Vec<&GraphqlId> = ids.iter().collect();
+    let parameter_limit = conn.parameter_limit();
+
+    for update in MutationBuilder::update_native_list(field, ids.as_slice(), operation, parameter_limit) {
+        conn.update(update)?;
     }
 
     Ok(())
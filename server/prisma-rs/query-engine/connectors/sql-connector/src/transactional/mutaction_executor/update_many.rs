@@ -1,7 +1,7 @@
 use super::update;
 use crate::{mutaction::MutationBuilder, SqlResult, Transaction};
 use connector::filter::Filter;
-use prisma_models::{GraphqlId, ModelRef, PrismaArgs, PrismaListValue, RelationFieldRef};
+use prisma_models::{GraphqlId, ModelRef, PrismaArgs, PrismaListValue, RelationFieldRef, ScalarListOperation};
 use std::sync::Arc;
 
 /// Updates every record and any associated list records in the database
@@ -14,6 +14,7 @@ pub fn execute<S>(
     filter: &Filter,
     non_list_args: &PrismaArgs,
     list_args: &[(S, PrismaListValue)],
+    list_operations: &[(String, ScalarListOperation)],
 ) -> SqlResult<usize>
 where
     S: AsRef<str>,
@@ -27,7 +28,8 @@ where
 
     let updates = {
         let ids: Vec<&GraphqlId> = ids.iter().map(|id| &*id).collect();
-        MutationBuilder::update_many(Arc::clone(&model), ids.as_slice(), non_list_args)?
+        let parameter_limit = conn.parameter_limit();
+        MutationBuilder::update_many(Arc::clone(&model), ids.as_slice(), non_list_args, parameter_limit)?
     };
 
     for update in updates {
@@ -35,6 +37,7 @@ where
     }
 
     update::update_list_args(conn, ids.as_slice(), Arc::clone(&model), list_args)?;
+    update::update_list_operations(conn, ids.as_slice(), Arc::clone(&model), list_operations)?;
 
     Ok(count)
 }
@@ -48,6 +51,7 @@ pub fn execute_nested<S>(
     relation_field: RelationFieldRef,
     non_list_args: &PrismaArgs,
     list_args: &[(S, PrismaListValue)],
+    list_operations: &[(String, ScalarListOperation)],
 ) -> SqlResult<usize>
 where
     S: AsRef<str>,
@@ -61,14 +65,21 @@ where
 
     let updates = {
         let ids: Vec<&GraphqlId> = ids.iter().map(|id| &*id).collect();
-        MutationBuilder::update_many(relation_field.related_model(), ids.as_slice(), non_list_args)?
+        let parameter_limit = conn.parameter_limit();
+        MutationBuilder::update_many(
+            relation_field.related_model(),
+            ids.as_slice(),
+            non_list_args,
+            parameter_limit,
+        )?
     };
 
     for update in updates {
         conn.update(update)?;
     }
 
-    update::update_list_args(conn, ids.as_slice(), relation_field.model(), list_args)?;
+    update::update_list_args(conn, ids.as_slice(), relation_field.related_model(), list_args)?;
+    update::update_list_operations(conn, ids.as_slice(), relation_field.related_model(), list_operations)?;
 
     Ok(count)
 }
@@ -0,0 +1,88 @@
+use super::create;
+use crate::{mutaction::MutationBuilder, SqlResult, Transaction};
+use connector::mutaction::{DatabaseMutactionResult, DatabaseMutactionResultType, Identifier, UpsertNode};
+
+/// Upserts a root record in one `INSERT ... ON CONFLICT DO UPDATE` statement
+/// with the selected fields as the conflict target. Concurrent upserts of the
+/// same record can't both try to create it, and a conflict on another unique
+/// field fails the upsert.
+///
+/// Returns `None` without touching the database if the upsert needs the record
+/// to be read first, leaving it to a read followed by a create or an update.
+pub fn execute(conn: &mut Transaction, upsert: &UpsertNode) -> SqlResult<Option<DatabaseMutactionResult>> {
+    if !is_native(upsert) {
+        return Ok(None);
+    }
+
+    let model = upsert.where_.model();
+    let mut create_args = upsert.create.non_list_args.clone();
+    let mut update_args = upsert.update.non_list_args.clone();
+
+    for (field_name, list_value) in upsert.create.list_args.iter() {
+        create_args.insert(field_name.clone(), MutationBuilder::native_list_value(list_value));
+    }
+
+    for (field_name, list_value) in upsert.update.list_args.iter() {
+        update_args.insert(field_name.clone(), MutationBuilder::native_list_value(list_value));
+    }
+
+    create::prepare_id(conn, &model, &mut create_args)?;
+
+    let (insert, update, returned_id) = MutationBuilder::upsert_node(&upsert.where_, create_args, &update_args)?;
+    let (id, created) = conn.upsert(&upsert.where_, insert, update)?;
+
+    let (id, typ) = match (created, returned_id) {
+        (true, Some(returned_id)) => (returned_id, DatabaseMutactionResultType::Create),
+        (true, None) => (id, DatabaseMutactionResultType::Create),
+        (false, _) => (id, DatabaseMutactionResultType::Update),
+    };
+
+    Ok(Some(DatabaseMutactionResult {
+        identifier: Identifier::Id(id),
+        typ,
+    }))
+}
+
+/// Nested mutactions, lists in their own tables, list operations, expected
+/// versions and soft deletes need the record to be read first. The conflict is
+/// found through the unique index of the selected fields, so the record to
+/// create must have the values the selector selects.
+fn is_native(upsert: &UpsertNode) -> bool {
+    let model = upsert.where_.model();
+    let (create, update) = (&upsert.create, &upsert.update);
+
+    let selects_unique = match upsert.where_.fields.as_slice() {
+        [(field, _)] => field.is_id() || field.is_unique,
+        fields => {
+            let names: Vec<String> = fields.iter().map(|(field, _)| field.name.clone()).collect();
+            model.compound_uniques.contains(&names)
+        }
+    };
+
+    let native_lists = create
+        .list_args
+        .iter()
+        .chain(update.list_args.iter())
+        .all(|(field_name, _)| {
+            model
+                .fields()
+                .find_from_scalar(field_name)
+                .map(|field| field.is_native_list())
+                .unwrap_or(false)
+        });
+
+    let selects_created = upsert
+        .where_
+        .fields
+        .iter()
+        .all(|(field, value)| create.non_list_args.get_field_value(&field.name) == Some(value));
+
+    create.nested_mutactions.is_empty()
+        && update.nested_mutactions.is_empty()
+        && update.list_operations.is_empty()
+        && update.expected_version.is_none()
+        && !model.is_soft_delete
+        && selects_unique
+        && native_lists
+        && selects_created
+}
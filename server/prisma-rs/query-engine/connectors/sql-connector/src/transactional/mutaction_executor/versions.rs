@@ -0,0 +1,123 @@
+use crate::{error::SqlError, mutaction::MutationBuilder, SqlResult, Transaction};
+use connector::{error::NodeSelectorInfo, mutaction::*};
+use prisma_models::{GraphqlId, ModelRef, PrismaArgs, PrismaValue};
+use std::sync::Arc;
+
+/// Sets the version of every record the mutaction creates to 1 and
+/// increments it on every record it updates, including nested ones.
+pub fn apply(mutaction: &mut TopLevelDatabaseMutaction) -> SqlResult<()> {
+    match *mutaction {
+        TopLevelDatabaseMutaction::CreateNode(ref mut cn) => create(cn),
+        TopLevelDatabaseMutaction::UpdateNode(ref mut un) => update(un),
+        TopLevelDatabaseMutaction::UpsertNode(ref mut ups) => {
+            create(&mut ups.create)?;
+            update(&mut ups.update)
+        }
+        TopLevelDatabaseMutaction::CreateNodes(ref mut cns) => {
+            for record in cns.records.iter_mut() {
+                add_version(&cns.model, &mut record.non_list_args)?;
+            }
+
+            Ok(())
+        }
+        TopLevelDatabaseMutaction::UpdateNodes(ref mut uns) => increment_version(&uns.model, &mut uns.non_list_args),
+        _ => Ok(()),
+    }
+}
+
+fn create(cn: &mut CreateNode) -> SqlResult<()> {
+    add_version(&cn.model, &mut cn.non_list_args)?;
+    nested(&mut cn.nested_mutactions)
+}
+
+fn update(un: &mut UpdateNode) -> SqlResult<()> {
+    increment_version(&un.where_.model(), &mut un.non_list_args)?;
+    nested(&mut un.nested_mutactions)
+}
+
+fn nested_create(cn: &mut NestedCreateNode) -> SqlResult<()> {
+    add_version(&cn.relation_field.related_model(), &mut cn.non_list_args)?;
+    nested(&mut cn.nested_mutactions)
+}
+
+fn nested_update(un: &mut NestedUpdateNode) -> SqlResult<()> {
+    increment_version(&un.relation_field.related_model(), &mut un.non_list_args)?;
+    nested(&mut un.nested_mutactions)
+}
+
+fn nested(mutactions: &mut NestedMutactions) -> SqlResult<()> {
+    for cn in mutactions.creates.iter_mut() {
+        nested_create(cn)?;
+    }
+
+    for coc in mutactions.connect_or_creates.iter_mut() {
+        nested_create(&mut coc.create)?;
+    }
+
+    for un in mutactions.updates.iter_mut() {
+        nested_update(un)?;
+    }
+
+    for ups in mutactions.upserts.iter_mut() {
+        nested_create(&mut ups.create)?;
+        nested_update(&mut ups.update)?;
+    }
+
+    for uns in mutactions.update_manys.iter_mut() {
+        increment_version(&uns.relation_field.related_model(), &mut uns.non_list_args)?;
+    }
+
+    Ok(())
+}
+
+fn add_version(model: &ModelRef, args: &mut PrismaArgs) -> SqlResult<()> {
+    reject_user_values(model, args)?;
+    args.add_version(Arc::clone(model));
+
+    Ok(())
+}
+
+fn increment_version(model: &ModelRef, args: &mut PrismaArgs) -> SqlResult<()> {
+    reject_user_values(model, args)?;
+    args.increment_version(Arc::clone(model));
+
+    Ok(())
+}
+
+fn reject_user_values(model: &ModelRef, args: &PrismaArgs) -> SqlResult<()> {
+    if let Some(field) = model.fields().version() {
+        if args.has_arg_for(&field.name) || args.operations.contains_key(&field.name) {
+            return Err(SqlError::FieldIsReadOnly {
+                field: field.name.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Fails with a `VersionConflict` if an expected version is given and the
+/// record has moved on to another one. Must run before the record is
+/// changed, the check holds until the transaction ends.
+pub fn check(
+    conn: &mut Transaction,
+    model: &ModelRef,
+    id: &GraphqlId,
+    expected: Option<&PrismaValue>,
+) -> SqlResult<()> {
+    let expected = match expected {
+        Some(expected) => expected,
+        None => return Ok(()),
+    };
+
+    if let Some(update) = MutationBuilder::lock_version(Arc::clone(model), id, expected) {
+        if conn.write_count(update.into())? == 0 {
+            return Err(SqlError::VersionConflict {
+                record: NodeSelectorInfo::for_id(Arc::clone(model), id),
+                expected: expected.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
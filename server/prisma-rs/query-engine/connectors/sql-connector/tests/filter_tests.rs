@@ -0,0 +1,503 @@
+mod test_harness;
+
+use connector::{
+    filter::*, DataResolver, JsonCompare, QueryArguments, RelationCompare, Relevance, ScalarCompare, ScalarListCompare,
+};
+use prisma_models::*;
+use serde_json::json;
+use std::str::FromStr;
+use test_harness::*;
+
+fn items_database(name: &str) -> TestDatabase {
+    let item = model(
+        "Item",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            scalar_field("name", "String", true),
+        ],
+    );
+
+    let db = test_database(name, vec![item], vec![]);
+
+    for (id, name) in &[
+        ("1", "100% cotton"),
+        ("2", "1000 cotton"),
+        ("3", "wool_blend"),
+        ("4", "Wool blend"),
+    ] {
+        db.execute(&format!(
+            r#"INSERT INTO "{{db}}"."Item" ("id", "name") VALUES ('{}', '{}')"#,
+            id, name
+        ));
+    }
+
+    db
+}
+
+fn item_names(db: &TestDatabase, filter: Filter) -> Vec<PrismaValue> {
+    let model = db.model("Item");
+    let selected_fields = SelectedFields::from(model.clone());
+
+    let nodes = db
+        .database
+        .get_nodes(model, QueryArguments::from(filter), &selected_fields)
+        .unwrap();
+
+    field_values(&nodes, "name")
+}
+
+#[test]
+fn contains_matches_a_percent_sign_literally() {
+    let db = items_database("contains_matches_a_percent_sign_literally");
+    let name = db.model("Item").fields().find_from_scalar("name").unwrap();
+
+    assert_eq!(
+        item_names(&db, name.contains("100%")),
+        vec![PrismaValue::from("100% cotton")]
+    );
+}
+
+#[test]
+fn starts_with_matches_an_underscore_literally() {
+    let db = items_database("starts_with_matches_an_underscore_literally");
+    let name = db.model("Item").fields().find_from_scalar("name").unwrap();
+
+    assert_eq!(
+        item_names(&db, name.starts_with("wool_")),
+        vec![PrismaValue::from("wool_blend")]
+    );
+}
+
+#[test]
+fn starts_with_respects_the_case_by_default() {
+    let db = items_database("starts_with_respects_the_case_by_default");
+    let name = db.model("Item").fields().find_from_scalar("name").unwrap();
+
+    assert_eq!(
+        item_names(&db, name.starts_with("wool")),
+        vec![PrismaValue::from("wool_blend")]
+    );
+}
+
+#[test]
+fn insensitive_starts_with_ignores_the_case() {
+    let db = items_database("insensitive_starts_with_ignores_the_case");
+    let name = db.model("Item").fields().find_from_scalar("name").unwrap();
+
+    let filter = Filter::from(ScalarFilter {
+        field: name,
+        condition: ScalarCondition::StartsWith(PrismaValue::from("WOOL")),
+        mode: QueryMode::Insensitive,
+    });
+
+    assert_eq!(
+        item_names(&db, filter),
+        vec![PrismaValue::from("wool_blend"), PrismaValue::from("Wool blend")]
+    );
+}
+
+#[test]
+fn insensitive_equals_ignores_the_case() {
+    let db = items_database("insensitive_equals_ignores_the_case");
+    let name = db.model("Item").fields().find_from_scalar("name").unwrap();
+
+    let filter = Filter::from(ScalarFilter {
+        field: name,
+        condition: ScalarCondition::Equals(PrismaValue::from("wool BLEND")),
+        mode: QueryMode::Insensitive,
+    });
+
+    assert_eq!(item_names(&db, filter), vec![PrismaValue::from("Wool blend")]);
+}
+
+fn search_database(name: &str) -> TestDatabase {
+    let item = model(
+        "Item",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            scalar_field("name", "String", true),
+        ],
+    );
+
+    let db = test_database(name, vec![item], vec![]);
+    db.execute(r#"CREATE VIRTUAL TABLE "{db}"."Item_fts" USING fts5("name", content='Item', content_rowid='rowid')"#);
+
+    for (id, name) in &[
+        ("1", "wool socks and a cotton scarf"),
+        ("2", "cotton cotton cotton"),
+        ("3", "linen shirt"),
+    ] {
+        db.execute(&format!(
+            r#"INSERT INTO "{{db}}"."Item" ("id", "name") VALUES ('{}', '{}')"#,
+            id, name
+        ));
+    }
+
+    db.execute(r#"INSERT INTO "{db}"."Item_fts"("Item_fts") VALUES ('rebuild')"#);
+    db
+}
+
+#[test]
+fn search_orders_by_relevance() {
+    let db = search_database("search_orders_by_relevance");
+    let model = db.model("Item");
+    let name = model.fields().find_from_scalar("name").unwrap();
+    let selected_fields = SelectedFields::from(model.clone());
+
+    let query_arguments = QueryArguments {
+        filter: Some(name.search("cotton")),
+        relevance: Some(Relevance {
+            field: name,
+            query: String::from("cotton"),
+            sort_order: SortOrder::Descending,
+        }),
+        ..Default::default()
+    };
+
+    let nodes = db.database.get_nodes(model, query_arguments, &selected_fields).unwrap();
+
+    assert_eq!(
+        field_values(&nodes, "name"),
+        vec![
+            PrismaValue::from("cotton cotton cotton"),
+            PrismaValue::from("wool socks and a cotton scarf"),
+        ]
+    );
+}
+
+fn settings_database(name: &str) -> TestDatabase {
+    let item = model(
+        "Item",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            scalar_field("name", "String", true),
+            scalar_field("settings", "Json", true),
+        ],
+    );
+
+    let db = test_database(name, vec![item], vec![]);
+
+    for (id, name, settings) in &[
+        ("1", "a", r#"{"size": 2, "tags": ["red", "blue"], "label": "50% off"}"#),
+        ("2", "b", r#"{"size": "2", "tags": [], "label": "500 off"}"#),
+        ("3", "c", r#"{"size": 10, "flag": true, "tags": [1]}"#),
+    ] {
+        db.execute(&format!(
+            r#"INSERT INTO "{{db}}"."Item" ("id", "name", "settings") VALUES ('{}', '{}', '{}')"#,
+            id, name, settings
+        ));
+    }
+
+    db
+}
+
+fn settings_names(db: &TestDatabase, path: &[&str], condition: JsonCondition) -> Vec<PrismaValue> {
+    let settings = db.model("Item").fields().find_from_scalar("settings").unwrap();
+    let path = path.iter().map(|segment| segment.to_string()).collect();
+
+    item_names(db, settings.json_path(path, condition))
+}
+
+#[test]
+fn json_equals_compares_the_json_type() {
+    let db = settings_database("json_equals_compares_the_json_type");
+
+    assert_eq!(
+        settings_names(&db, &["size"], JsonCondition::Equals(json!(2))),
+        vec![PrismaValue::from("a")]
+    );
+
+    assert_eq!(
+        settings_names(&db, &["flag"], JsonCondition::Equals(json!(true))),
+        vec![PrismaValue::from("c")]
+    );
+}
+
+#[test]
+fn json_array_contains_finds_elements() {
+    let db = settings_database("json_array_contains_finds_elements");
+
+    assert_eq!(
+        settings_names(&db, &["tags"], JsonCondition::ArrayContains(json!("blue"))),
+        vec![PrismaValue::from("a")]
+    );
+
+    assert_eq!(
+        settings_names(&db, &["tags"], JsonCondition::ArrayContains(json!(1))),
+        vec![PrismaValue::from("c")]
+    );
+}
+
+#[test]
+fn json_string_contains_matches_a_percent_sign_literally() {
+    let db = settings_database("json_string_contains_matches_a_percent_sign_literally");
+
+    assert_eq!(
+        settings_names(&db, &["label"], JsonCondition::StringContains(String::from("50%"))),
+        vec![PrismaValue::from("a")]
+    );
+}
+
+#[test]
+fn json_string_contains_matches_an_underscore_literally() {
+    let db = settings_database("json_string_contains_matches_an_underscore_literally");
+
+    assert!(settings_names(&db, &["label"], JsonCondition::StringContains(String::from("5_0"))).is_empty());
+}
+
+#[test]
+fn json_greater_than_only_compares_numbers_with_numbers() {
+    let db = settings_database("json_greater_than_only_compares_numbers_with_numbers");
+
+    assert_eq!(
+        settings_names(&db, &["size"], JsonCondition::GreaterThan(json!(1))),
+        vec![PrismaValue::from("a"), PrismaValue::from("c")]
+    );
+}
+
+#[test]
+fn json_exists_finds_the_path() {
+    let db = settings_database("json_exists_finds_the_path");
+
+    assert_eq!(
+        settings_names(&db, &["tags", "0"], JsonCondition::Exists),
+        vec![PrismaValue::from("a"), PrismaValue::from("c")]
+    );
+}
+
+#[test]
+fn decimal_equals_binds_the_value() {
+    let item = model(
+        "Item",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            scalar_field("name", "String", true),
+            scalar_field("price", "Decimal", true),
+        ],
+    );
+
+    let db = test_database("decimal_equals_binds_the_value", vec![item], vec![]);
+
+    db.execute(r#"INSERT INTO "{db}"."Item" ("id", "name", "price") VALUES ('1', 'a', '1.50'), ('2', 'b', '2.25')"#);
+
+    let price = db.model("Item").fields().find_from_scalar("price").unwrap();
+
+    assert_eq!(
+        item_names(&db, price.equals(Decimal::from_str("2.25").unwrap())),
+        vec![PrismaValue::from("b")]
+    );
+}
+
+/// Users with their profiles: `alice` has one about cats, `bob` one about dogs
+/// and `carol` none. The profile about birds has no user.
+fn profiles_database(name: &str, inline_in_profile: bool) -> TestDatabase {
+    let user = model(
+        "User",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            scalar_field("name", "String", true),
+            relation_field("profile", "UserProfile", "B", false, false),
+        ],
+    );
+
+    let profile = model(
+        "Profile",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            scalar_field("bio", "String", true),
+            relation_field("user", "UserProfile", "A", false, false),
+        ],
+    );
+
+    let relation = match inline_in_profile {
+        true => inline(
+            relation("UserProfile", "Profile", "User", "SET_NULL", "SET_NULL"),
+            "Profile",
+            "user",
+        ),
+        false => relation("UserProfile", "Profile", "User", "SET_NULL", "SET_NULL"),
+    };
+
+    let db = test_database(name, vec![user, profile], vec![relation]);
+
+    for (id, name) in &[("u1", "alice"), ("u2", "bob"), ("u3", "carol")] {
+        db.execute(&format!(
+            r#"INSERT INTO "{{db}}"."User" ("id", "name") VALUES ('{}', '{}')"#,
+            id, name
+        ));
+    }
+
+    for (id, bio, user) in &[
+        ("p1", "cats", Some("u1")),
+        ("p2", "dogs", Some("u2")),
+        ("p3", "birds", None),
+    ] {
+        db.execute(&format!(
+            r#"INSERT INTO "{{db}}"."Profile" ("id", "bio") VALUES ('{}', '{}')"#,
+            id, bio
+        ));
+
+        match (user, inline_in_profile) {
+            (Some(user), true) => db.execute(&format!(
+                r#"UPDATE "{{db}}"."Profile" SET "user" = '{}' WHERE "id" = '{}'"#,
+                user, id
+            )),
+            (Some(user), false) => db.execute(&format!(
+                r#"INSERT INTO "{{db}}"."_UserProfile" ("id", "A", "B") VALUES ('{}', '{}', '{}')"#,
+                id, id, user
+            )),
+            (None, _) => (),
+        }
+    }
+
+    db
+}
+
+fn user_names(db: &TestDatabase, filter: Filter) -> Vec<PrismaValue> {
+    let model = db.model("User");
+    let selected_fields = SelectedFields::from(model.clone());
+
+    let nodes = db
+        .database
+        .get_nodes(model, QueryArguments::from(filter), &selected_fields)
+        .unwrap();
+
+    field_values(&nodes, "name")
+}
+
+fn profile_bios(db: &TestDatabase, filter: Filter) -> Vec<PrismaValue> {
+    let model = db.model("Profile");
+    let selected_fields = SelectedFields::from(model.clone());
+
+    let nodes = db
+        .database
+        .get_nodes(model, QueryArguments::from(filter), &selected_fields)
+        .unwrap();
+
+    field_values(&nodes, "bio")
+}
+
+fn assert_to_one_filters(db: &TestDatabase) {
+    let profile = db.model("User").fields().find_from_relation_fields("profile").unwrap();
+    let user = db.model("Profile").fields().find_from_relation_fields("user").unwrap();
+    let bio = db.model("Profile").fields().find_from_scalar("bio").unwrap();
+
+    assert_eq!(
+        user_names(db, profile.to_one_related(bio.equals("cats"))),
+        vec![PrismaValue::from("alice")]
+    );
+
+    assert_eq!(
+        user_names(db, Filter::not(vec![profile.to_one_related(bio.equals("cats"))])),
+        vec![PrismaValue::from("bob"), PrismaValue::from("carol")]
+    );
+
+    assert_eq!(
+        user_names(db, profile.one_relation_is_null()),
+        vec![PrismaValue::from("carol")]
+    );
+
+    assert_eq!(
+        user_names(db, Filter::not(vec![profile.one_relation_is_null()])),
+        vec![PrismaValue::from("alice"), PrismaValue::from("bob")]
+    );
+
+    assert_eq!(
+        profile_bios(db, user.one_relation_is_null()),
+        vec![PrismaValue::from("birds")]
+    );
+}
+
+#[test]
+fn to_one_filters_work_with_an_inline_relation() {
+    let db = profiles_database("to_one_filters_work_with_an_inline_relation", true);
+    assert_to_one_filters(&db);
+}
+
+#[test]
+fn to_one_filters_work_with_a_relation_table() {
+    let db = profiles_database("to_one_filters_work_with_a_relation_table", false);
+    assert_to_one_filters(&db);
+}
+
+/// Items with the tags `a: [red, blue]`, `b: [red]` and `c: []`.
+fn tags_database(name: &str) -> TestDatabase {
+    let item = model(
+        "Item",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            scalar_field("name", "String", true),
+            list(scalar_field("tags", "String", false)),
+        ],
+    );
+
+    let db = test_database(name, vec![item], vec![]);
+
+    for (id, name, tags) in &[
+        ("1", "a", vec!["red", "blue"]),
+        ("2", "b", vec!["red"]),
+        ("3", "c", vec![]),
+    ] {
+        db.execute(&format!(
+            r#"INSERT INTO "{{db}}"."Item" ("id", "name") VALUES ('{}', '{}')"#,
+            id, name
+        ));
+
+        for (position, tag) in tags.iter().enumerate() {
+            db.execute(&format!(
+                r#"INSERT INTO "{{db}}"."Item_tags" ("nodeId", "position", "value") VALUES ('{}', {}, '{}')"#,
+                id, position, tag
+            ));
+        }
+    }
+
+    db
+}
+
+#[test]
+fn list_has_finds_the_element() {
+    let db = tags_database("list_has_finds_the_element");
+    let tags = db.model("Item").fields().find_from_scalar("tags").unwrap();
+
+    assert_eq!(
+        item_names(&db, tags.contains_element("blue")),
+        vec![PrismaValue::from("a")]
+    );
+}
+
+#[test]
+fn list_has_every_needs_all_elements() {
+    let db = tags_database("list_has_every_needs_all_elements");
+    let tags = db.model("Item").fields().find_from_scalar("tags").unwrap();
+
+    assert_eq!(
+        item_names(&db, tags.contains_every_element(vec!["red", "blue"])),
+        vec![PrismaValue::from("a")]
+    );
+}
+
+#[test]
+fn list_has_some_needs_one_element() {
+    let db = tags_database("list_has_some_needs_one_element");
+    let tags = db.model("Item").fields().find_from_scalar("tags").unwrap();
+
+    assert_eq!(
+        item_names(&db, tags.contains_some_element(vec!["red", "green"])),
+        vec![PrismaValue::from("a"), PrismaValue::from("b")]
+    );
+
+    assert!(item_names(&db, tags.contains_some_element(Vec::<&str>::new())).is_empty());
+}
+
+#[test]
+fn list_is_empty_finds_lists_without_elements() {
+    let db = tags_database("list_is_empty_finds_lists_without_elements");
+    let tags = db.model("Item").fields().find_from_scalar("tags").unwrap();
+
+    assert_eq!(item_names(&db, tags.contains_none()), vec![PrismaValue::from("c")]);
+
+    assert_eq!(
+        item_names(&db, Filter::not(vec![tags.contains_none()])),
+        vec![PrismaValue::from("a"), PrismaValue::from("b")]
+    );
+}
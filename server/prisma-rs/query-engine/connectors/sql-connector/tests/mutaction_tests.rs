@@ -0,0 +1,981 @@
+mod test_harness;
+
+use chrono::Utc;
+use connector::{
+    error::ConnectorError,
+    filter::{Filter, NodeSelector},
+    mutaction::*,
+    ConnectorResult, DataResolver, DatabaseMutactionExecutor, QueryArguments, RelationCompare, ScalarCompare,
+};
+use prisma_models::*;
+use std::sync::Arc;
+use test_harness::*;
+
+fn execute(db: &TestDatabase, mutaction: TopLevelDatabaseMutaction) -> ConnectorResult<DatabaseMutactionResult> {
+    db.database.execute(db.db_name().to_string(), mutaction)
+}
+
+fn create(db: &TestDatabase, model: &str, args: Vec<(&str, PrismaValue)>) -> ConnectorResult<DatabaseMutactionResult> {
+    let mut non_list_args = PrismaArgs::new();
+
+    for (name, value) in args {
+        non_list_args.insert(name, value);
+    }
+
+    execute(
+        db,
+        TopLevelDatabaseMutaction::CreateNode(CreateNode {
+            model: db.model(model),
+            non_list_args,
+            list_args: Vec::new(),
+            nested_mutactions: Default::default(),
+        }),
+    )
+}
+
+fn delete(db: &TestDatabase, model: &str, id: &str) -> ConnectorResult<DatabaseMutactionResult> {
+    let model = db.model(model);
+
+    execute(
+        db,
+        TopLevelDatabaseMutaction::DeleteNode(DeleteNode {
+            where_: NodeSelector::new(model.fields().id(), GraphqlId::from(id)),
+            expected_version: None,
+        }),
+    )
+}
+
+fn update(
+    db: &TestDatabase,
+    model: &str,
+    id: &GraphqlId,
+    args: Vec<(&str, PrismaValue)>,
+) -> ConnectorResult<DatabaseMutactionResult> {
+    let model = db.model(model);
+    let mut non_list_args = PrismaArgs::new();
+
+    for (name, value) in args {
+        non_list_args.insert(name, value);
+    }
+
+    execute(
+        db,
+        TopLevelDatabaseMutaction::UpdateNode(UpdateNode {
+            where_: NodeSelector::new(model.fields().id(), id.clone()),
+            non_list_args,
+            list_args: Vec::new(),
+            list_operations: Vec::new(),
+            nested_mutactions: Default::default(),
+            expected_version: None,
+        }),
+    )
+}
+
+fn assert_read_only(result: ConnectorResult<DatabaseMutactionResult>, field_name: &str) {
+    match result {
+        Err(ConnectorError::FieldIsReadOnly { field }) => assert_eq!(field, field_name),
+        result => panic!("Expected {} to be read-only, got {:?}", field_name, result),
+    }
+}
+
+fn counters_database(name: &str) -> TestDatabase {
+    let counter = model(
+        "Counter",
+        vec![
+            auto_generated(scalar_field("id", "Int", true)),
+            scalar_field("name", "String", true),
+        ],
+    );
+
+    test_database(name, vec![counter], vec![])
+}
+
+#[test]
+fn database_generated_ids_are_read_back() {
+    let db = counters_database("database_generated_ids_are_read_back");
+
+    let first = create(&db, "Counter", vec![("name", PrismaValue::from("a"))]).unwrap();
+    let second = create(&db, "Counter", vec![("name", PrismaValue::from("b"))]).unwrap();
+
+    assert_eq!(first.id(), &GraphqlId::Int(1));
+    assert_eq!(second.id(), &GraphqlId::Int(2));
+}
+
+#[test]
+fn database_generated_ids_can_be_given() {
+    let db = counters_database("database_generated_ids_can_be_given");
+
+    let given = create(
+        &db,
+        "Counter",
+        vec![("id", PrismaValue::Int(7)), ("name", PrismaValue::from("a"))],
+    )
+    .unwrap();
+    let generated = create(&db, "Counter", vec![("name", PrismaValue::from("b"))]).unwrap();
+
+    assert_eq!(given.id(), &GraphqlId::Int(7));
+    assert_eq!(generated.id(), &GraphqlId::Int(8));
+    assert!(find_by_id(&db, "Counter", &GraphqlId::Int(7)).is_some());
+}
+
+fn posts_database(name: &str) -> TestDatabase {
+    let post = model(
+        "Post",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            scalar_field("title", "String", true),
+            scalar_field("createdAt", "DateTime", true),
+            scalar_field("updatedAt", "DateTime", true),
+        ],
+    );
+
+    test_database(name, vec![post], vec![])
+}
+
+fn timestamp(node: &SingleNode, field: &str) -> i64 {
+    match node.get_field_value(field).unwrap() {
+        PrismaValue::DateTime(dt) => dt.timestamp_millis(),
+        value => panic!("Expected {} to be a DateTime, got {:?}", field, value),
+    }
+}
+
+#[test]
+fn creates_set_created_at_and_updated_at() {
+    let db = posts_database("creates_set_created_at_and_updated_at");
+
+    let before = Utc::now().timestamp_millis();
+    let id = create(&db, "Post", vec![("title", PrismaValue::from("a"))])
+        .unwrap()
+        .id()
+        .clone();
+    let after = Utc::now().timestamp_millis();
+
+    let node = find_by_id(&db, "Post", &id).unwrap();
+    let created_at = timestamp(&node, "createdAt");
+
+    assert!(before <= created_at && created_at <= after);
+    assert_eq!(timestamp(&node, "updatedAt"), created_at);
+}
+
+#[test]
+fn updates_only_set_updated_at() {
+    let db = posts_database("updates_only_set_updated_at");
+
+    db.execute(r#"INSERT INTO "{db}"."Post" ("id", "title", "createdAt", "updatedAt") VALUES ('1', 'a', 0, 0)"#);
+    let id = GraphqlId::from("1");
+
+    let before = Utc::now().timestamp_millis();
+    update(&db, "Post", &id, vec![("title", PrismaValue::from("b"))]).unwrap();
+
+    let node = find_by_id(&db, "Post", &id).unwrap();
+
+    assert_eq!(timestamp(&node, "createdAt"), 0);
+    assert!(timestamp(&node, "updatedAt") >= before);
+}
+
+#[test]
+fn created_at_cannot_be_given() {
+    let db = posts_database("created_at_cannot_be_given");
+
+    let result = create(
+        &db,
+        "Post",
+        vec![
+            ("title", PrismaValue::from("a")),
+            ("createdAt", PrismaValue::DateTime(Utc::now())),
+        ],
+    );
+
+    assert_read_only(result, "createdAt");
+}
+
+#[test]
+fn updated_at_cannot_be_given() {
+    let db = posts_database("updated_at_cannot_be_given");
+
+    db.execute(r#"INSERT INTO "{db}"."Post" ("id", "title", "createdAt", "updatedAt") VALUES ('1', 'a', 0, 0)"#);
+
+    let result = update(
+        &db,
+        "Post",
+        &GraphqlId::from("1"),
+        vec![("updatedAt", PrismaValue::DateTime(Utc::now()))],
+    );
+
+    assert_read_only(result, "updatedAt");
+}
+
+/// Users with posts, deleting a user does `on_delete` to the posts and
+/// deleting a post does `post_on_delete` to the user.
+fn blog_database(name: &str, on_delete: &str, post_on_delete: &str) -> TestDatabase {
+    let user = model(
+        "User",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            relation_field("posts", "UserPosts", "A", true, false),
+        ],
+    );
+
+    let post = model(
+        "Post",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            relation_field("author", "UserPosts", "B", false, false),
+        ],
+    );
+
+    let db = test_database(
+        name,
+        vec![user, post],
+        vec![relation("UserPosts", "User", "Post", on_delete, post_on_delete)],
+    );
+
+    db.execute(r#"INSERT INTO "{db}"."User" ("id") VALUES ('u1'), ('u2')"#);
+    db.execute(r#"INSERT INTO "{db}"."Post" ("id") VALUES ('p1'), ('p2'), ('p3')"#);
+    db.execute(r#"INSERT INTO "{db}"."_UserPosts" ("A", "B") VALUES ('u1', 'p1'), ('u1', 'p2'), ('u2', 'p3')"#);
+
+    db
+}
+
+#[test]
+fn cascading_deletes_remove_the_related_records() {
+    let db = blog_database("cascading_deletes_remove_the_related_records", "CASCADE", "SET_NULL");
+
+    delete(&db, "User", "u1").unwrap();
+
+    assert_eq!(db.count("User"), 1);
+    assert_eq!(db.count("Post"), 1);
+    assert_eq!(db.count("_UserPosts"), 1);
+    assert!(find_by_id(&db, "Post", &GraphqlId::from("p3")).is_some());
+}
+
+#[test]
+fn restricted_deletes_fail_with_related_records() {
+    let db = blog_database("restricted_deletes_fail_with_related_records", "RESTRICT", "SET_NULL");
+
+    match delete(&db, "User", "u1") {
+        Err(ConnectorError::RelationViolation { relation_name, .. }) => assert_eq!(relation_name, "UserPosts"),
+        result => panic!("Expected a relation violation, got {:?}", result),
+    }
+
+    assert_eq!(db.count("User"), 2);
+    assert_eq!(db.count("Post"), 3);
+    assert_eq!(db.count("_UserPosts"), 3);
+}
+
+#[test]
+fn restricted_deletes_succeed_without_related_records() {
+    let db = blog_database(
+        "restricted_deletes_succeed_without_related_records",
+        "RESTRICT",
+        "SET_NULL",
+    );
+
+    db.execute(r#"DELETE FROM "{db}"."_UserPosts" WHERE "A" = 'u2'"#);
+    delete(&db, "User", "u2").unwrap();
+
+    assert_eq!(db.count("User"), 1);
+    assert_eq!(db.count("Post"), 3);
+}
+
+#[test]
+fn cascading_deletes_follow_cycles_once() {
+    let db = blog_database("cascading_deletes_follow_cycles_once", "CASCADE", "CASCADE");
+
+    // p1 cascades back to u1, which is being deleted already
+    delete(&db, "Post", "p1").unwrap();
+
+    assert_eq!(db.count("User"), 1);
+    assert_eq!(db.count("Post"), 1);
+    assert_eq!(db.count("_UserPosts"), 1);
+}
+
+#[test]
+fn cascading_deletes_stay_within_the_parameter_limit() {
+    let db = blog_database(
+        "cascading_deletes_stay_within_the_parameter_limit",
+        "CASCADE",
+        "SET_NULL",
+    );
+
+    // More posts than SQLite takes parameters in one statement
+    db.execute(
+        r#"INSERT INTO "{db}"."Post" ("id")
+        WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 1500)
+        SELECT 'many' || i FROM n"#,
+    );
+    db.execute(
+        r#"INSERT INTO "{db}"."_UserPosts" ("A", "B") SELECT 'u1', "id" FROM "{db}"."Post" WHERE "id" LIKE 'many%'"#,
+    );
+
+    delete(&db, "User", "u1").unwrap();
+
+    assert_eq!(db.count("Post"), 1);
+    assert_eq!(db.count("_UserPosts"), 1);
+}
+
+/// Posts with comments, deleting a post cascades to its comments, which
+/// have soft deletes.
+fn commented_posts_database(name: &str) -> TestDatabase {
+    let post = model(
+        "Post",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            relation_field("comments", "PostComments", "A", true, false),
+        ],
+    );
+
+    let comment = soft_delete(model(
+        "Comment",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            scalar_field("text", "String", true),
+            scalar_field("deletedAt", "DateTime", false),
+            relation_field("post", "PostComments", "B", false, false),
+        ],
+    ));
+
+    let db = test_database(
+        name,
+        vec![post, comment],
+        vec![relation("PostComments", "Post", "Comment", "CASCADE", "SET_NULL")],
+    );
+
+    db.execute(r#"INSERT INTO "{db}"."Post" ("id") VALUES ('p1'), ('p2')"#);
+    db.execute(
+        r#"INSERT INTO "{db}"."Comment" ("id", "text") VALUES ('c1', 'first'), ('c2', 'second'), ('c3', 'third')"#,
+    );
+    db.execute(r#"INSERT INTO "{db}"."_PostComments" ("A", "B") VALUES ('p1', 'c1'), ('p1', 'c2'), ('p2', 'c3')"#);
+
+    db
+}
+
+#[test]
+fn cascading_deletes_soft_delete_related_records() {
+    let db = commented_posts_database("cascading_deletes_soft_delete_related_records");
+
+    delete(&db, "Post", "p1").unwrap();
+
+    assert_eq!(db.count("Post"), 1);
+    assert_eq!(db.count("Comment"), 3);
+    assert!(find_by_id(&db, "Comment", &GraphqlId::from("c1")).is_none());
+    assert!(find_by_id(&db, "Comment", &GraphqlId::from("c2")).is_none());
+    assert!(find_by_id(&db, "Comment", &GraphqlId::from("c3")).is_some());
+}
+
+#[test]
+fn relation_filters_leave_out_soft_deleted_records() {
+    let db = commented_posts_database("relation_filters_leave_out_soft_deleted_records");
+    let post = db.model("Post");
+    let comments = post.fields().find_from_relation_fields("comments").unwrap();
+    let text = db.model("Comment").fields().find_from_scalar("text").unwrap();
+
+    delete(&db, "Comment", "c3").unwrap();
+
+    let nodes = db
+        .database
+        .get_nodes(
+            Arc::clone(&post),
+            QueryArguments::from(comments.at_least_one_related(text.equals("third"))),
+            &SelectedFields::from(Arc::clone(&post)),
+        )
+        .unwrap();
+
+    assert!(nodes.nodes.is_empty());
+}
+
+fn create_many(
+    db: &TestDatabase,
+    model: &str,
+    records: Vec<Vec<(&str, PrismaValue)>>,
+    skip_duplicates: bool,
+) -> ConnectorResult<DatabaseMutactionResult> {
+    let records = records
+        .into_iter()
+        .map(|args| {
+            let mut non_list_args = PrismaArgs::new();
+
+            for (name, value) in args {
+                non_list_args.insert(name, value);
+            }
+
+            CreateNodesRecord {
+                non_list_args,
+                list_args: Vec::new(),
+            }
+        })
+        .collect();
+
+    execute(
+        db,
+        TopLevelDatabaseMutaction::CreateNodes(CreateNodes {
+            model: db.model(model),
+            records,
+            skip_duplicates,
+        }),
+    )
+}
+
+fn tags_database(name: &str) -> TestDatabase {
+    let tag = model(
+        "Tag",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            unique(scalar_field("name", "String", true)),
+        ],
+    );
+
+    test_database(name, vec![tag], vec![])
+}
+
+fn tag(name: &str) -> Vec<(&'static str, PrismaValue)> {
+    vec![("name", PrismaValue::from(name))]
+}
+
+#[test]
+fn create_many_stays_within_the_parameter_limit() {
+    let db = tags_database("create_many_stays_within_the_parameter_limit");
+
+    // More values than SQLite takes parameters in one statement
+    let records = (0..1500).map(|i| tag(&format!("tag{}", i))).collect();
+    let result = create_many(&db, "Tag", records, false).unwrap();
+
+    assert_eq!(result.count(), 1500);
+    assert_eq!(db.count("Tag"), 1500);
+}
+
+#[test]
+fn create_many_fails_on_duplicates() {
+    let db = tags_database("create_many_fails_on_duplicates");
+
+    db.execute(r#"INSERT INTO "{db}"."Tag" ("id", "name") VALUES ('1', 'a')"#);
+
+    match create_many(&db, "Tag", vec![tag("b"), tag("a")], false) {
+        Err(ConnectorError::UniqueConstraintViolation { .. }) => (),
+        result => panic!("Expected a unique constraint violation, got {:?}", result),
+    }
+
+    assert_eq!(db.count("Tag"), 1);
+}
+
+#[test]
+fn create_many_skips_duplicates() {
+    let db = tags_database("create_many_skips_duplicates");
+
+    db.execute(r#"INSERT INTO "{db}"."Tag" ("id", "name") VALUES ('1', 'a')"#);
+
+    let result = create_many(&db, "Tag", vec![tag("a"), tag("b"), tag("c"), tag("b")], true).unwrap();
+
+    assert_eq!(result.count(), 2);
+    assert_eq!(db.count("Tag"), 3);
+}
+
+fn stats_database(name: &str) -> TestDatabase {
+    let stats = model(
+        "Stats",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            scalar_field("views", "Int", true),
+            scalar_field("score", "Float", true),
+        ],
+    );
+
+    let db = test_database(name, vec![stats], vec![]);
+
+    db.execute(r#"INSERT INTO "{db}"."Stats" ("id", "views", "score") VALUES ('1', 10, 1.5), ('2', 20, 2.5)"#);
+
+    db
+}
+
+fn update_with_operation(db: &TestDatabase, field: &str, operation: NumericOperation) {
+    let model = db.model("Stats");
+    let mut non_list_args = PrismaArgs::new();
+    non_list_args.insert_operation(field, operation);
+
+    execute(
+        db,
+        TopLevelDatabaseMutaction::UpdateNode(UpdateNode {
+            where_: NodeSelector::new(model.fields().id(), GraphqlId::from("1")),
+            non_list_args,
+            list_args: Vec::new(),
+            list_operations: Vec::new(),
+            nested_mutactions: Default::default(),
+            expected_version: None,
+        }),
+    )
+    .unwrap();
+}
+
+fn stat(db: &TestDatabase, id: &str, field: &str) -> PrismaValue {
+    let node = find_by_id(db, "Stats", &GraphqlId::from(id)).unwrap();
+    node.get_field_value(field).unwrap().clone()
+}
+
+#[test]
+fn updates_increment_numbers() {
+    let db = stats_database("updates_increment_numbers");
+
+    update_with_operation(&db, "views", NumericOperation::Increment(PrismaValue::Int(5)));
+    update_with_operation(&db, "score", NumericOperation::Increment(PrismaValue::Float(0.25)));
+
+    assert_eq!(stat(&db, "1", "views"), PrismaValue::Int(15));
+    assert_eq!(stat(&db, "1", "score"), PrismaValue::Float(1.75));
+    assert_eq!(stat(&db, "2", "views"), PrismaValue::Int(20));
+}
+
+#[test]
+fn updates_decrement_numbers() {
+    let db = stats_database("updates_decrement_numbers");
+
+    update_with_operation(&db, "views", NumericOperation::Decrement(PrismaValue::Int(15)));
+    update_with_operation(&db, "score", NumericOperation::Decrement(PrismaValue::Float(0.5)));
+
+    assert_eq!(stat(&db, "1", "views"), PrismaValue::Int(-5));
+    assert_eq!(stat(&db, "1", "score"), PrismaValue::Float(1.0));
+}
+
+#[test]
+fn updates_multiply_numbers() {
+    let db = stats_database("updates_multiply_numbers");
+
+    update_with_operation(&db, "views", NumericOperation::Multiply(PrismaValue::Int(3)));
+    update_with_operation(&db, "score", NumericOperation::Multiply(PrismaValue::Float(2.0)));
+
+    assert_eq!(stat(&db, "1", "views"), PrismaValue::Int(30));
+    assert_eq!(stat(&db, "1", "score"), PrismaValue::Float(3.0));
+}
+
+#[test]
+fn batch_updates_apply_operations_to_each_record() {
+    let db = stats_database("batch_updates_apply_operations_to_each_record");
+    let mut non_list_args = PrismaArgs::new();
+    non_list_args.insert_operation("views", NumericOperation::Increment(PrismaValue::Int(1)));
+
+    let result = execute(
+        &db,
+        TopLevelDatabaseMutaction::UpdateNodes(UpdateNodes {
+            model: db.model("Stats"),
+            filter: Filter::and(vec![]),
+            non_list_args,
+            list_args: Vec::new(),
+            list_operations: Vec::new(),
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(result.count(), 2);
+    assert_eq!(stat(&db, "1", "views"), PrismaValue::Int(11));
+    assert_eq!(stat(&db, "2", "views"), PrismaValue::Int(21));
+}
+
+fn tagged_posts_database(name: &str) -> TestDatabase {
+    let post = model(
+        "Post",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            list(scalar_field("tags", "String", false)),
+        ],
+    );
+
+    let db = test_database(name, vec![post], vec![]);
+
+    db.execute(r#"INSERT INTO "{db}"."Post" ("id") VALUES ('1'), ('2')"#);
+    db.execute(
+        r#"INSERT INTO "{db}"."Post_tags" ("nodeId", "position", "value")
+        VALUES ('1', 1000, 'a'), ('1', 2000, 'b'), ('2', 1000, 'c')"#,
+    );
+
+    db
+}
+
+fn update_tags(db: &TestDatabase, id: &str, operation: ScalarListOperation) {
+    let model = db.model("Post");
+
+    execute(
+        db,
+        TopLevelDatabaseMutaction::UpdateNode(UpdateNode {
+            where_: NodeSelector::new(model.fields().id(), GraphqlId::from(id)),
+            non_list_args: PrismaArgs::new(),
+            list_args: Vec::new(),
+            list_operations: vec![(String::from("tags"), operation)],
+            nested_mutactions: Default::default(),
+            expected_version: None,
+        }),
+    )
+    .unwrap();
+}
+
+fn tags(db: &TestDatabase, id: &str) -> Vec<PrismaValue> {
+    let field = db.model("Post").fields().find_from_scalar("tags").unwrap();
+
+    db.database
+        .get_scalar_list_values_by_node_ids(field, vec![GraphqlId::from(id)])
+        .unwrap()
+        .into_iter()
+        .flat_map(|list| list.values)
+        .collect()
+}
+
+fn strings(values: &[&str]) -> Vec<PrismaValue> {
+    values.iter().map(|value| PrismaValue::from(*value)).collect()
+}
+
+#[test]
+fn list_pushes_append_values() {
+    let db = tagged_posts_database("list_pushes_append_values");
+
+    update_tags(&db, "1", ScalarListOperation::Push(strings(&["x", "y"])));
+
+    assert_eq!(tags(&db, "1"), strings(&["a", "b", "x", "y"]));
+    assert_eq!(tags(&db, "2"), strings(&["c"]));
+}
+
+#[test]
+fn list_unshifts_prepend_values() {
+    let db = tagged_posts_database("list_unshifts_prepend_values");
+
+    update_tags(&db, "1", ScalarListOperation::Unshift(strings(&["x", "y"])));
+
+    assert_eq!(tags(&db, "1"), strings(&["x", "y", "a", "b"]));
+}
+
+#[test]
+fn list_removes_delete_every_occurrence() {
+    let db = tagged_posts_database("list_removes_delete_every_occurrence");
+
+    update_tags(&db, "1", ScalarListOperation::Push(strings(&["a"])));
+    update_tags(&db, "1", ScalarListOperation::Remove(strings(&["a"])));
+
+    assert_eq!(tags(&db, "1"), strings(&["b"]));
+    assert_eq!(tags(&db, "2"), strings(&["c"]));
+}
+
+#[test]
+fn list_unsets_remove_all_values() {
+    let db = tagged_posts_database("list_unsets_remove_all_values");
+
+    update_tags(&db, "1", ScalarListOperation::Unset);
+
+    assert!(tags(&db, "1").is_empty());
+    assert_eq!(db.count("Post_tags"), 1);
+}
+
+#[test]
+fn batch_updates_apply_list_operations_to_each_record() {
+    let db = tagged_posts_database("batch_updates_apply_list_operations_to_each_record");
+
+    execute(
+        &db,
+        TopLevelDatabaseMutaction::UpdateNodes(UpdateNodes {
+            model: db.model("Post"),
+            filter: Filter::and(vec![]),
+            non_list_args: PrismaArgs::new(),
+            list_args: Vec::new(),
+            list_operations: vec![(String::from("tags"), ScalarListOperation::Unshift(strings(&["x"])))],
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(tags(&db, "1"), strings(&["x", "a", "b"]));
+    assert_eq!(tags(&db, "2"), strings(&["x", "c"]));
+}
+
+fn accounts_database(name: &str) -> TestDatabase {
+    let account = model(
+        "Account",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            unique(scalar_field("email", "String", true)),
+            unique(scalar_field("handle", "String", true)),
+            scalar_field("visits", "Int", true),
+        ],
+    );
+
+    let db = test_database(name, vec![account], vec![]);
+
+    db.execute(
+        r#"INSERT INTO "{db}"."Account" ("id", "email", "handle", "visits") VALUES ('1', 'a@prisma.io', 'a', 1)"#,
+    );
+
+    db
+}
+
+fn upsert_account(db: &TestDatabase, email: &str, handle: &str) -> ConnectorResult<DatabaseMutactionResult> {
+    let mut update_args = PrismaArgs::new();
+    update_args.insert_operation("visits", NumericOperation::Increment(PrismaValue::Int(1)));
+
+    upsert_account_with(db, email, handle, update_args)
+}
+
+/// Upserts the account with the email, updating an existing one with the
+/// arguments.
+fn upsert_account_with(
+    db: &TestDatabase,
+    email: &str,
+    handle: &str,
+    update_args: PrismaArgs,
+) -> ConnectorResult<DatabaseMutactionResult> {
+    let model = db.model("Account");
+    let email_field = model.fields().find_from_scalar("email").unwrap();
+    let where_ = NodeSelector::new(email_field, PrismaValue::from(email));
+
+    let mut create_args = PrismaArgs::new();
+    create_args.insert("email", PrismaValue::from(email));
+    create_args.insert("handle", PrismaValue::from(handle));
+    create_args.insert("visits", PrismaValue::Int(1));
+
+    execute(
+        db,
+        TopLevelDatabaseMutaction::UpsertNode(UpsertNode {
+            where_: where_.clone(),
+            create: CreateNode {
+                model: model.clone(),
+                non_list_args: create_args,
+                list_args: Vec::new(),
+                nested_mutactions: Default::default(),
+            },
+            update: UpdateNode {
+                where_,
+                non_list_args: update_args,
+                list_args: Vec::new(),
+                list_operations: Vec::new(),
+                nested_mutactions: Default::default(),
+                expected_version: None,
+            },
+        }),
+    )
+}
+
+fn visits(db: &TestDatabase, id: &GraphqlId) -> PrismaValue {
+    let node = find_by_id(db, "Account", id).unwrap();
+    node.get_field_value("visits").unwrap().clone()
+}
+
+#[test]
+fn upserts_create_missing_records() {
+    let db = accounts_database("upserts_create_missing_records");
+
+    let result = upsert_account(&db, "b@prisma.io", "b").unwrap();
+
+    assert_eq!(result.typ, DatabaseMutactionResultType::Create);
+    assert_eq!(db.count("Account"), 2);
+    assert_eq!(visits(&db, result.id()), PrismaValue::Int(1));
+}
+
+#[test]
+fn upserts_update_existing_records() {
+    let db = accounts_database("upserts_update_existing_records");
+
+    let result = upsert_account(&db, "a@prisma.io", "a").unwrap();
+
+    assert_eq!(result.typ, DatabaseMutactionResultType::Update);
+    assert_eq!(result.id(), &GraphqlId::from("1"));
+    assert_eq!(db.count("Account"), 1);
+    assert_eq!(visits(&db, result.id()), PrismaValue::Int(2));
+}
+
+#[test]
+fn upserts_fail_on_conflicts_with_other_unique_fields() {
+    let db = accounts_database("upserts_fail_on_conflicts_with_other_unique_fields");
+
+    match upsert_account(&db, "b@prisma.io", "a") {
+        Err(ConnectorError::UniqueConstraintViolation { .. }) => (),
+        result => panic!("Expected a unique constraint violation, got {:?}", result),
+    }
+
+    assert_eq!(db.count("Account"), 1);
+    assert_eq!(visits(&db, &GraphqlId::from("1")), PrismaValue::Int(1));
+}
+
+#[test]
+fn upserts_changing_the_selected_field_return_the_updated_record() {
+    let db = accounts_database("upserts_changing_the_selected_field_return_the_updated_record");
+
+    let mut update_args = PrismaArgs::new();
+    update_args.insert("email", PrismaValue::from("c@prisma.io"));
+
+    let result = upsert_account_with(&db, "a@prisma.io", "a", update_args).unwrap();
+
+    assert_eq!(result.typ, DatabaseMutactionResultType::Update);
+    assert_eq!(result.id(), &GraphqlId::from("1"));
+    assert_eq!(db.count("Account"), 1);
+}
+
+#[test]
+fn upserts_without_update_arguments_keep_existing_records() {
+    let db = accounts_database("upserts_without_update_arguments_keep_existing_records");
+
+    let result = upsert_account_with(&db, "a@prisma.io", "a", PrismaArgs::new()).unwrap();
+
+    assert_eq!(result.typ, DatabaseMutactionResultType::Update);
+    assert_eq!(result.id(), &GraphqlId::from("1"));
+    assert_eq!(visits(&db, result.id()), PrismaValue::Int(1));
+}
+
+/// Posts in categories with unique names, both sides are lists.
+fn categorized_posts_database(name: &str) -> TestDatabase {
+    let category = model(
+        "Category",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            unique(scalar_field("name", "String", true)),
+            relation_field("posts", "CategoryPosts", "A", true, false),
+        ],
+    );
+
+    let post = model(
+        "Post",
+        vec![
+            scalar_field("id", "GraphQLID", true),
+            relation_field("categories", "CategoryPosts", "B", true, false),
+        ],
+    );
+
+    let db = test_database(
+        name,
+        vec![category, post],
+        vec![relation("CategoryPosts", "Category", "Post", "SET_NULL", "SET_NULL")],
+    );
+
+    db.execute(r#"INSERT INTO "{db}"."Category" ("id", "name") VALUES ('c1', 'rust')"#);
+
+    db
+}
+
+/// Connects the category named `name`, or creates one named `create_name`.
+fn connect_or_create_category(
+    db: &TestDatabase,
+    name: &str,
+    create_name: &str,
+    top_is_create: bool,
+) -> NestedMutactions {
+    let categories = db
+        .model("Post")
+        .fields()
+        .find_from_relation_fields("categories")
+        .unwrap();
+    let name_field = db.model("Category").fields().find_from_scalar("name").unwrap();
+
+    let mut non_list_args = PrismaArgs::new();
+    non_list_args.insert("name", PrismaValue::from(create_name));
+
+    NestedMutactions {
+        connect_or_creates: vec![NestedConnectOrCreate {
+            relation_field: Arc::clone(&categories),
+            where_: NodeSelector::new(name_field, PrismaValue::from(name)),
+            create: NestedCreateNode {
+                relation_field: categories,
+                non_list_args,
+                list_args: Vec::new(),
+                top_is_create,
+                nested_mutactions: Default::default(),
+            },
+            top_is_create,
+        }],
+        ..Default::default()
+    }
+}
+
+fn create_post(
+    db: &TestDatabase,
+    id: &str,
+    nested_mutactions: NestedMutactions,
+) -> ConnectorResult<DatabaseMutactionResult> {
+    let mut non_list_args = PrismaArgs::new();
+    non_list_args.insert("id", PrismaValue::from(id));
+
+    execute(
+        db,
+        TopLevelDatabaseMutaction::CreateNode(CreateNode {
+            model: db.model("Post"),
+            non_list_args,
+            list_args: Vec::new(),
+            nested_mutactions,
+        }),
+    )
+}
+
+/// The names of the categories of the post.
+fn category_names(db: &TestDatabase, post_id: &str) -> Vec<PrismaValue> {
+    let categories = db
+        .model("Post")
+        .fields()
+        .find_from_relation_fields("categories")
+        .unwrap();
+
+    let nodes = db
+        .database
+        .get_related_nodes(
+            categories,
+            &[GraphqlId::from(post_id)],
+            QueryArguments::default(),
+            &SelectedFields::from(db.model("Category")),
+        )
+        .unwrap();
+
+    field_values(&nodes, "name")
+}
+
+#[test]
+fn nested_connect_or_creates_connect_existing_records() {
+    let db = categorized_posts_database("nested_connect_or_creates_connect_existing_records");
+
+    let nested = connect_or_create_category(&db, "rust", "rust", true);
+    create_post(&db, "p1", nested).unwrap();
+
+    assert_eq!(db.count("Category"), 1);
+    assert_eq!(category_names(&db, "p1"), strings(&["rust"]));
+}
+
+#[test]
+fn nested_connect_or_creates_create_missing_records() {
+    let db = categorized_posts_database("nested_connect_or_creates_create_missing_records");
+
+    let nested = connect_or_create_category(&db, "go", "go", true);
+    create_post(&db, "p1", nested).unwrap();
+
+    assert_eq!(db.count("Category"), 2);
+    assert_eq!(category_names(&db, "p1"), strings(&["go"]));
+}
+
+#[test]
+fn nested_connect_or_creates_work_in_updates() {
+    let db = categorized_posts_database("nested_connect_or_creates_work_in_updates");
+
+    db.execute(r#"INSERT INTO "{db}"."Post" ("id") VALUES ('p1')"#);
+
+    let model = db.model("Post");
+    let nested_mutactions = connect_or_create_category(&db, "go", "go", false);
+
+    execute(
+        &db,
+        TopLevelDatabaseMutaction::UpdateNode(UpdateNode {
+            where_: NodeSelector::new(model.fields().id(), GraphqlId::from("p1")),
+            non_list_args: PrismaArgs::new(),
+            list_args: Vec::new(),
+            list_operations: Vec::new(),
+            nested_mutactions,
+            expected_version: None,
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(category_names(&db, "p1"), strings(&["go"]));
+}
+
+#[test]
+fn nested_connect_or_creates_fail_on_conflicts_with_other_records() {
+    let db = categorized_posts_database("nested_connect_or_creates_fail_on_conflicts_with_other_records");
+
+    // No category is named go, creating one named rust conflicts with c1.
+    let nested = connect_or_create_category(&db, "go", "rust", true);
+
+    match create_post(&db, "p1", nested) {
+        Err(ConnectorError::UniqueConstraintViolation { .. }) => (),
+        result => panic!("Expected a unique constraint violation, got {:?}", result),
+    }
+
+    assert_eq!(db.count("Category"), 1);
+    assert_eq!(db.count("Post"), 0);
+    assert_eq!(db.count("_CategoryPosts"), 0);
+}
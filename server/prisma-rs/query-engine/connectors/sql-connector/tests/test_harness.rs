@@ -0,0 +1,260 @@
+#![allow(dead_code)]
+
+use connector::{filter::NodeSelector, DataResolver};
+use prisma_models::*;
+use prisma_query::ast::Query;
+use serde_json::{json, Value};
+use sql_connector::{SqlDatabase, Sqlite, Transaction, Transactional};
+use std::{env, fs, path::PathBuf};
+
+pub struct TestDatabase {
+    pub database: SqlDatabase<Sqlite>,
+    pub project: ProjectRef,
+}
+
+impl TestDatabase {
+    pub fn db_name(&self) -> &str {
+        &self.project.internal_data_model().db_name
+    }
+
+    pub fn model(&self, name: &str) -> ModelRef {
+        self.project.internal_data_model().find_model(name).unwrap()
+    }
+
+    /// The number of rows in the table.
+    pub fn count(&self, table: &str) -> usize {
+        self.database.count_by_table(self.db_name(), table).unwrap()
+    }
+
+    /// Runs one statement, `{db}` standing for the database name.
+    pub fn execute(&self, sql: &str) {
+        let sql = sql.replace("{db}", self.db_name());
+
+        self.database
+            .executor
+            .with_transaction(self.db_name(), |conn| conn.write(Query::from(sql.as_str())))
+            .unwrap();
+    }
+}
+
+/// A new SQLite database file named after the test, with the legacy models
+/// and relations given. The tables are created from the same definitions:
+/// a table per model, one per scalar list field and one per relation, inline
+/// relations adding a column to their model table instead.
+pub fn test_database(name: &str, models: Vec<Value>, relations: Vec<Value>) -> TestDatabase {
+    let folder = databases_folder();
+    let _ = fs::remove_file(folder.join(format!("{}.db", name)));
+
+    let tables: Vec<String> = models
+        .iter()
+        .flat_map(model_tables)
+        .chain(relations.iter().map(relation_table))
+        .collect();
+
+    let template: ProjectTemplate = serde_json::from_value(json!({
+        "id": name,
+        "schema": {
+            "models": models,
+            "relations": relations,
+            "enums": [],
+        },
+    }))
+    .unwrap();
+
+    let sqlite = Sqlite::new(folder.to_str().unwrap().to_string(), 1, true).unwrap();
+
+    let database = TestDatabase {
+        database: SqlDatabase::new(sqlite),
+        project: template.into(),
+    };
+
+    for table in tables {
+        database.execute(&table);
+    }
+
+    database
+}
+
+pub fn model(name: &str, fields: Vec<Value>) -> Value {
+    json!({
+        "name": name,
+        "stableIdentifier": name,
+        "isEmbedded": false,
+        "fields": fields,
+    })
+}
+
+pub fn scalar_field(name: &str, type_identifier: &str, is_required: bool) -> Value {
+    json!({
+        "name": name,
+        "typeIdentifier": type_identifier,
+        "isRequired": is_required,
+        "isList": false,
+        "isUnique": name == "id",
+        "isHidden": false,
+        "isAutoGenerated": false,
+    })
+}
+
+/// A field of the relation, stored in a relation table `_{relation_name}`
+/// with the columns `A` and `B`.
+pub fn relation_field(name: &str, relation_name: &str, side: &str, is_list: bool, is_required: bool) -> Value {
+    json!({
+        "name": name,
+        "typeIdentifier": "Relation",
+        "isRequired": is_required,
+        "isList": is_list,
+        "isUnique": false,
+        "isHidden": false,
+        "isAutoGenerated": false,
+        "relationName": relation_name,
+        "relationSide": side,
+    })
+}
+
+/// `on_delete` is `SET_NULL`, `CASCADE` or `RESTRICT`.
+pub fn relation(name: &str, model_a: &str, model_b: &str, model_a_on_delete: &str, model_b_on_delete: &str) -> Value {
+    json!({
+        "name": name,
+        "modelAId": model_a,
+        "modelBId": model_b,
+        "modelAOnDelete": model_a_on_delete,
+        "modelBOnDelete": model_b_on_delete,
+    })
+}
+
+/// Stores the relation in the `column` of the `model` table instead of a
+/// relation table.
+pub fn inline(mut relation: Value, model: &str, column: &str) -> Value {
+    relation["manifestation"] = json!({
+        "relationManifestationType": "inline",
+        "inTableOfModelId": model,
+        "referencingColumn": column,
+    });
+    relation
+}
+
+/// Makes a field unique.
+pub fn unique(mut field: Value) -> Value {
+    field["isUnique"] = json!(true);
+    field
+}
+
+/// Makes a scalar field a list.
+pub fn list(mut field: Value) -> Value {
+    field["isList"] = json!(true);
+    field
+}
+
+/// Makes a field generated by the database, an `Int` id becomes an
+/// `INTEGER PRIMARY KEY`.
+pub fn auto_generated(mut field: Value) -> Value {
+    field["isAutoGenerated"] = json!(true);
+    field
+}
+
+/// Makes a model keep its deleted records in `deletedAt`.
+pub fn soft_delete(mut model: Value) -> Value {
+    model["isSoftDelete"] = json!(true);
+    model
+}
+
+/// The record of the model with the given id.
+pub fn find_by_id(db: &TestDatabase, model: &str, id: &GraphqlId) -> Option<SingleNode> {
+    let model = db.model(model);
+    let selector = NodeSelector::new(model.fields().id(), id.clone());
+
+    db.database
+        .get_node_by_where(&selector, &SelectedFields::from(model))
+        .unwrap()
+}
+
+/// The values of a field, in the order of the records.
+pub fn field_values(nodes: &ManyNodes, field: &str) -> Vec<PrismaValue> {
+    nodes
+        .nodes
+        .iter()
+        .map(|node| node.get_field_value(&nodes.field_names, field).unwrap().clone())
+        .collect()
+}
+
+/// The table of the model and the tables of its scalar lists.
+fn model_tables(model: &Value) -> Vec<String> {
+    let name = model["name"].as_str().unwrap();
+    let scalars: Vec<&Value> = model["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|field| field["typeIdentifier"] != "Relation")
+        .collect();
+
+    let columns: Vec<String> = scalars
+        .iter()
+        .filter(|field| field["isList"] != true)
+        .map(|field| column(field))
+        .collect();
+
+    let lists = scalars.iter().filter(|field| field["isList"] == true).map(|field| {
+        format!(
+            r#"CREATE TABLE "{{db}}"."{}_{}" ("nodeId" TEXT NOT NULL, "position" INTEGER NOT NULL, "value" {} NOT NULL)"#,
+            name,
+            field["name"].as_str().unwrap(),
+            column_type(field),
+        )
+    });
+
+    Some(format!(r#"CREATE TABLE "{{db}}"."{}" ({})"#, name, columns.join(", ")))
+        .into_iter()
+        .chain(lists)
+        .collect()
+}
+
+/// The relation table, or the column of an inline relation.
+fn relation_table(relation: &Value) -> String {
+    match relation["manifestation"].as_object() {
+        Some(inline) => format!(
+            r#"ALTER TABLE "{{db}}"."{}" ADD COLUMN "{}" TEXT"#,
+            inline["inTableOfModelId"].as_str().unwrap(),
+            inline["referencingColumn"].as_str().unwrap(),
+        ),
+        None => format!(
+            r#"CREATE TABLE "{{db}}"."_{}" ("id" TEXT, "A" TEXT NOT NULL, "B" TEXT NOT NULL)"#,
+            relation["name"].as_str().unwrap()
+        ),
+    }
+}
+
+fn column(field: &Value) -> String {
+    let name = field["name"].as_str().unwrap();
+    let mut column = format!(r#""{}" {}"#, name, column_type(field));
+
+    if name == "id" {
+        column.push_str(" PRIMARY KEY");
+    } else if field["isRequired"] == true {
+        column.push_str(" NOT NULL");
+    }
+
+    if name != "id" && field["isUnique"] == true {
+        column.push_str(" UNIQUE");
+    }
+
+    column
+}
+
+/// Dates are stored as milliseconds, decimals as text to keep them exact.
+fn column_type(field: &Value) -> &'static str {
+    match field["typeIdentifier"].as_str().unwrap() {
+        "Int" | "BigInt" | "DateTime" => "INTEGER",
+        "Float" => "REAL",
+        "Boolean" => "BOOLEAN",
+        "Bytes" => "BLOB",
+        _ => "TEXT",
+    }
+}
+
+fn databases_folder() -> PathBuf {
+    let folder = env::temp_dir().join("sql-connector-tests");
+    fs::create_dir_all(&folder).unwrap();
+
+    folder
+}
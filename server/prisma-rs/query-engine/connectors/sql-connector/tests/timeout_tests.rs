@@ -0,0 +1,99 @@
+mod test_harness;
+
+use connector::{
+    deadline, error::ConnectorError, mutaction::*, ConnectorResult, DataResolver, DatabaseMutactionExecutor,
+    QueryArguments,
+};
+use prisma_models::*;
+use prisma_query::ast::Query;
+use sql_connector::{Transaction, Transactional};
+use std::time::Duration;
+use test_harness::*;
+
+fn timeout_database(name: &str) -> TestDatabase {
+    test_database(
+        name,
+        vec![model(
+            "User",
+            vec![
+                scalar_field("id", "GraphQLID", true),
+                scalar_field("name", "String", true),
+            ],
+        )],
+        Vec::new(),
+    )
+}
+
+fn create_user(db: &TestDatabase, name: &str) -> ConnectorResult<DatabaseMutactionResult> {
+    let mut non_list_args = PrismaArgs::new();
+    non_list_args.insert("name", PrismaValue::String(name.to_string()));
+
+    db.database.execute(
+        db.db_name().to_string(),
+        TopLevelDatabaseMutaction::CreateNode(CreateNode {
+            model: db.model("User"),
+            non_list_args,
+            list_args: Vec::new(),
+            nested_mutactions: Default::default(),
+        }),
+    )
+}
+
+fn user_names(db: &TestDatabase) -> Vec<PrismaValue> {
+    let model = db.model("User");
+    let selected_fields = SelectedFields::from(model.clone());
+    let nodes = db
+        .database
+        .get_nodes(model, QueryArguments::default(), &selected_fields)
+        .unwrap();
+
+    field_values(&nodes, "name")
+}
+
+#[test]
+fn a_request_without_a_timeout_is_not_interrupted() {
+    let db = timeout_database("timeout_none");
+
+    deadline::with_timeout(None, || create_user(&db, "Bob")).unwrap();
+
+    assert_eq!(vec![PrismaValue::from("Bob")], user_names(&db));
+}
+
+#[test]
+fn an_expired_deadline_fails_before_writing() {
+    let db = timeout_database("timeout_expired");
+
+    let result = deadline::with_timeout(Some(Duration::from_secs(0)), || create_user(&db, "Bob"));
+
+    match result {
+        Err(ConnectorError::QueryTimeout) => (),
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+
+    assert!(user_names(&db).is_empty());
+}
+
+#[test]
+fn a_slow_statement_is_interrupted_and_rolled_back() {
+    let db = timeout_database("timeout_slow");
+
+    let slow_insert = format!(
+        "WITH RECURSIVE numbers(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM numbers WHERE n < 100000000) \
+         INSERT INTO \"{}\".\"User\" (\"id\", \"name\") SELECT 'user-' || n, 'name' FROM numbers",
+        db.db_name()
+    );
+
+    let result = deadline::with_timeout(Some(Duration::from_millis(100)), || {
+        db.database
+            .executor
+            .with_transaction(db.db_name(), |conn| conn.write(Query::from(slow_insert.as_str())))
+            .map_err(ConnectorError::from)
+    });
+
+    match result {
+        Err(ConnectorError::QueryTimeout) => (),
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+
+    assert_eq!(0, db.count("User"));
+}
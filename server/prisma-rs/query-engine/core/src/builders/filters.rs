@@ -1,9 +1,19 @@
 use crate::{CoreError, CoreResult};
-use connector::{filter::Filter, RelationCompare, ScalarCompare};
+use connector::{
+    filter::{Filter, JsonCondition, QueryMode, ScalarCondition, ScalarFilter},
+    JsonCompare, RelationCompare, ScalarCompare, ScalarListCompare,
+};
 use graphql_parser::query::Value;
-use prisma_models::{Field, ModelRef, PrismaListValue, PrismaValue};
+use prisma_models::{
+    DomainResult, Field, ModelRef, PrismaListValue, PrismaValue, RelationFieldRef, ScalarField, TypeIdentifier,
+};
 use std::{collections::BTreeMap, convert::TryFrom, sync::Arc};
 
+const MODE_ARG: &str = "mode";
+const JSON_PATH_ARG: &str = "path";
+const IS_ARG: &str = "is";
+const IS_NOT_ARG: &str = "isNot";
+
 #[derive(Debug, PartialEq)]
 enum FilterOp {
     In,
@@ -19,6 +29,11 @@ enum FilterOp {
     NotStartsWith,
     EndsWith,
     NotEndsWith,
+    Search,
+    Has,
+    HasEvery,
+    HasSome,
+    IsEmpty,
     Some,
     None,
     Every,
@@ -44,6 +59,11 @@ impl From<&FilterOp> for &'static str {
             FilterOp::NotStartsWith => "_not_starts_with",
             FilterOp::EndsWith => "_ends_with",
             FilterOp::NotEndsWith => "_not_ends_with",
+            FilterOp::Search => "_search",
+            FilterOp::Has => "_has",
+            FilterOp::HasEvery => "_has_every",
+            FilterOp::HasSome => "_has_some",
+            FilterOp::IsEmpty => "_is_empty",
             FilterOp::Some => "_some",
             FilterOp::None => "_none",
             FilterOp::Every => "_every",
@@ -70,6 +90,11 @@ pub fn extract_filter(map: &BTreeMap<String, Value>, model: ModelRef) -> CoreRes
         FilterOp::Contains,
         FilterOp::StartsWith,
         FilterOp::EndsWith,
+        FilterOp::Search,
+        FilterOp::HasEvery, // Needs to be before `_every`
+        FilterOp::HasSome,  // Needs to be before `_some`
+        FilterOp::Has,
+        FilterOp::IsEmpty,
         FilterOp::Some,
         FilterOp::None,
         FilterOp::Every,
@@ -79,8 +104,11 @@ pub fn extract_filter(map: &BTreeMap<String, Value>, model: ModelRef) -> CoreRes
         FilterOp::Field, // Needs to be last
     ];
 
+    let mode = extract_mode(map, &model)?;
+
     let filters = map
         .iter()
+        .filter(|(k, _)| !(k.as_str() == MODE_ARG && mode.is_some()))
         .map(|(k, v): (&String, &Value)| {
             let op = ops.iter().find(|op| {
                 let op_name: &'static str = (*op).into();
@@ -124,9 +152,30 @@ pub fn extract_filter(map: &BTreeMap<String, Value>, model: ModelRef) -> CoreRes
                     let field = model.fields().find_from_all(&field_name).unwrap(); // fixme: unwrap
 
                     match field {
+                        Field::Scalar(ref s)
+                            if op == &FilterOp::Search && s.type_identifier != TypeIdentifier::String =>
+                        {
+                            Err(CoreError::QueryValidationError(format!(
+                                "Full-text search is only supported on String fields, `{}` is not one",
+                                s.name
+                            )))
+                        }
+                        Field::Scalar(ref s) if mode.is_some() && s.type_identifier != TypeIdentifier::String => {
+                            Err(CoreError::QueryValidationError(format!(
+                                "The filter mode only applies to String fields, `{}` is not one",
+                                s.name
+                            )))
+                        }
+                        Field::Scalar(ref s) if is_list_op(op) => extract_list_filter(s, op, v),
+                        Field::Scalar(ref s) if op == &FilterOp::Field && s.type_identifier == TypeIdentifier::Json => {
+                            match v {
+                                Value::Object(o) => extract_json_filter(s, o),
+                                v => Ok(s.equals(PrismaValue::from_typed_value(v, s.type_identifier)?)),
+                            }
+                        }
                         Field::Scalar(s) => {
-                            let value = PrismaValue::from_value(v);
-                            Ok(match op {
+                            let value = PrismaValue::from_typed_value(v, s.type_identifier)?;
+                            let filter = match op {
                                 FilterOp::In => s.is_in(PrismaListValue::try_from(value)?),
                                 FilterOp::NotIn => s.not_in(PrismaListValue::try_from(value)?),
                                 FilterOp::Not => s.not_equals(value),
@@ -140,8 +189,14 @@ pub fn extract_filter(map: &BTreeMap<String, Value>, model: ModelRef) -> CoreRes
                                 FilterOp::NotStartsWith => s.not_starts_with(value),
                                 FilterOp::EndsWith => s.ends_with(value),
                                 FilterOp::NotEndsWith => s.not_ends_with(value),
+                                FilterOp::Search => s.search(value),
                                 FilterOp::Field => s.equals(value),
                                 _ => unreachable!(),
+                            };
+
+                            Ok(match (filter, mode) {
+                                (Filter::Scalar(sf), Some(QueryMode::Insensitive)) => Filter::Scalar(sf.insensitive()),
+                                (filter, _) => filter,
                             })
                         }
                         Field::Relation(r) => {
@@ -160,9 +215,11 @@ pub fn extract_filter(map: &BTreeMap<String, Value>, model: ModelRef) -> CoreRes
                                 (FilterOp::Every, Some(value)) => {
                                     r.every_related(extract_filter(value, r.related_model())?)
                                 }
-                                (FilterOp::Field, Some(value)) => {
-                                    r.to_one_related(extract_filter(value, r.related_model())?)
-                                }
+                                (FilterOp::Field, _) if r.is_list => Err(CoreError::QueryValidationError(format!(
+                                    "`{}` is a list relation, filter it with `_some`, `_none` or `_every`",
+                                    r.name
+                                )))?,
+                                (FilterOp::Field, Some(value)) => extract_to_one_filter(&r, value)?,
                                 (FilterOp::Field, None) => r.one_relation_is_null(),
                                 (op, val) => Err(CoreError::QueryValidationError(format!(
                                     "Invalid filter: Operation {:?} with {:?}",
@@ -178,3 +235,322 @@ pub fn extract_filter(map: &BTreeMap<String, Value>, model: ModelRef) -> CoreRes
 
     Ok(Filter::and(filters))
 }
+
+/// `mode` applies to the string filters on the same level. A model field
+/// called `mode` takes precedence, it is filtered as usual then.
+fn extract_mode(map: &BTreeMap<String, Value>, model: &ModelRef) -> CoreResult<Option<QueryMode>> {
+    if model.fields().find_from_all(MODE_ARG).is_ok() {
+        return Ok(None);
+    }
+
+    match map.get(MODE_ARG) {
+        None => Ok(None),
+        Some(Value::Enum(mode)) if mode == "default" => Ok(Some(QueryMode::Default)),
+        Some(Value::Enum(mode)) if mode == "insensitive" => Ok(Some(QueryMode::Insensitive)),
+        Some(value) => Err(CoreError::QueryValidationError(format!(
+            "Invalid filter mode {:?}, expected `default` or `insensitive`",
+            value
+        ))),
+    }
+}
+
+fn is_list_op(op: &FilterOp) -> bool {
+    match op {
+        FilterOp::Has | FilterOp::HasEvery | FilterOp::HasSome | FilterOp::IsEmpty => true,
+        _ => false,
+    }
+}
+
+/// Operators on the values of scalar list fields
+fn extract_list_filter(field: &Arc<ScalarField>, op: &FilterOp, value: &Value) -> CoreResult<Filter> {
+    if !field.is_list {
+        return Err(CoreError::QueryValidationError(format!(
+            "Operation {:?} is only supported on scalar list fields, `{}` is not one",
+            op, field.name
+        )));
+    }
+
+    let element = |value: &Value| PrismaValue::from_typed_value(value, field.type_identifier);
+    let values = |value: &Value| match value {
+        Value::List(l) => Ok(l.iter().map(element).collect::<DomainResult<Vec<PrismaValue>>>()?),
+        v => Err(CoreError::QueryValidationError(format!(
+            "Invalid value {:?} for {:?}, expected a list",
+            v, op
+        ))),
+    };
+
+    match (op, value) {
+        (FilterOp::Has, value) => Ok(field.contains_element(element(value)?)),
+        (FilterOp::HasEvery, value) => Ok(field.contains_every_element(values(value)?)),
+        (FilterOp::HasSome, value) => Ok(field.contains_some_element(values(value)?)),
+        (FilterOp::IsEmpty, Value::Boolean(true)) => Ok(field.contains_none()),
+        (FilterOp::IsEmpty, Value::Boolean(false)) => Ok(Filter::not(vec![field.contains_none()])),
+        (op, value) => Err(CoreError::QueryValidationError(format!(
+            "Invalid filter: Operation {:?} with {:?}",
+            op, value
+        ))),
+    }
+}
+
+/// `is` and `isNot` wrap a filter on the related record, with `null` they
+/// check whether there is one. Without them the filter applies as with `is`.
+/// Fields of the related model called `is` or `isNot` take precedence.
+fn extract_to_one_filter(field: &RelationFieldRef, map: &BTreeMap<String, Value>) -> CoreResult<Filter> {
+    let related_model = field.related_model();
+    let is_wrapper = |k: &String| (k == IS_ARG || k == IS_NOT_ARG) && related_model.fields().find_from_all(k).is_err();
+
+    if map.is_empty() || !map.keys().all(is_wrapper) {
+        return Ok(field.to_one_related(extract_filter(map, related_model)?));
+    }
+
+    let filters = map
+        .iter()
+        .map(|(k, v)| {
+            let filter = match v {
+                Value::Object(o) => field.to_one_related(extract_filter(o, Arc::clone(&related_model))?),
+                Value::Null => field.one_relation_is_null(),
+                v => {
+                    return Err(CoreError::QueryValidationError(format!(
+                        "Invalid value {:?} for `{}`, expected a filter or null",
+                        v, k
+                    )));
+                }
+            };
+
+            Ok(if k == IS_NOT_ARG {
+                Filter::not(vec![filter])
+            } else {
+                filter
+            })
+        })
+        .collect::<CoreResult<Vec<Filter>>>()?;
+
+    Ok(Filter::and(filters))
+}
+
+/// `path` selects a value inside a Json field, the other keys are conditions
+/// on that value.
+fn extract_json_filter(field: &Arc<ScalarField>, map: &BTreeMap<String, Value>) -> CoreResult<Filter> {
+    let path = match map.get(JSON_PATH_ARG) {
+        Some(Value::List(l)) => l
+            .iter()
+            .map(|v| match v {
+                Value::String(s) => Ok(s.clone()),
+                v => Err(CoreError::QueryValidationError(format!(
+                    "Invalid JSON path element {:?}, expected a string",
+                    v
+                ))),
+            })
+            .collect::<CoreResult<Vec<String>>>()?,
+        _ => {
+            return Err(CoreError::QueryValidationError(format!(
+                "Filters on the Json field `{}` require a `path` list",
+                field.name
+            )));
+        }
+    };
+
+    let filters = map
+        .iter()
+        .filter(|(k, _)| k.as_str() != JSON_PATH_ARG)
+        .map(|(k, v)| {
+            let condition = match (k.as_str(), v) {
+                ("equals", v) => JsonCondition::Equals(json_value(v)?),
+                ("string_contains", Value::String(s)) => JsonCondition::StringContains(s.clone()),
+                ("array_contains", v) => JsonCondition::ArrayContains(json_value(v)?),
+                ("lt", v) => JsonCondition::LessThan(json_value(v)?),
+                ("gt", v) => JsonCondition::GreaterThan(json_value(v)?),
+                ("exists", Value::Boolean(true)) => JsonCondition::Exists,
+                ("exists", Value::Boolean(false)) => {
+                    return Ok(Filter::not(vec![field.json_path(path.clone(), JsonCondition::Exists)]));
+                }
+                (k, v) => {
+                    return Err(CoreError::QueryValidationError(format!(
+                        "Invalid JSON filter `{}` with {:?}",
+                        k, v
+                    )));
+                }
+            };
+
+            Ok(field.json_path(path.clone(), condition))
+        })
+        .collect::<CoreResult<Vec<Filter>>>()?;
+
+    Ok(Filter::and(filters))
+}
+
+fn json_value(value: &Value) -> CoreResult<serde_json::Value> {
+    use serde_json::Value as Json;
+
+    let json = match value {
+        Value::Null => Json::Null,
+        Value::Boolean(b) => Json::Bool(*b),
+        Value::Int(i) => match i.as_i64() {
+            Some(i) => Json::from(i),
+            None => return Err(CoreError::QueryValidationError("Invalid number provided".into())),
+        },
+        Value::Float(f) => Json::from(*f),
+        Value::String(s) | Value::Enum(s) => Json::String(s.clone()),
+        Value::List(l) => Json::Array(l.iter().map(json_value).collect::<CoreResult<Vec<Json>>>()?),
+        Value::Object(o) => Json::Object(
+            o.iter()
+                .map(|(k, v)| Ok((k.clone(), json_value(v)?)))
+                .collect::<CoreResult<serde_json::Map<String, Json>>>()?,
+        ),
+        Value::Variable(name) => {
+            return Err(CoreError::QueryValidationError(format!(
+                "Unresolved variable `{}`",
+                name
+            )));
+        }
+    };
+
+    Ok(json)
+}
+
+/// The first full-text search on the top level of a filter, the one records
+/// can be ordered by with `_relevance`.
+pub fn find_search(filter: &Filter) -> Option<(Arc<ScalarField>, String)> {
+    match filter {
+        Filter::And(filters) => filters.iter().find_map(|f| find_search(f)),
+        Filter::Scalar(ScalarFilter {
+            field,
+            condition: ScalarCondition::Search(query),
+            ..
+        }) => Some((Arc::clone(field), format!("{}", query))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use chrono::prelude::*;
+    use connector::filter::ScalarListCondition;
+
+    fn filter_map(values: Vec<(&str, Value)>) -> BTreeMap<String, Value> {
+        values.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+    }
+
+    #[test]
+    fn insensitive_mode_applies_to_string_filters() {
+        let internal_data_model = internal_data_model();
+        let map = filter_map(vec![
+            ("name_contains", Value::String("bob".into())),
+            ("mode", Value::Enum("insensitive".into())),
+        ]);
+
+        match extract_filter(&map, user_model(&internal_data_model)).unwrap() {
+            Filter::And(filters) => match filters.first().map(|f| f.as_ref()) {
+                Some(Filter::Scalar(filter)) => assert_eq!(QueryMode::Insensitive, filter.mode),
+                filter => panic!("expected a scalar filter, got {:?}", filter),
+            },
+            filter => panic!("expected a conjunction, got {:?}", filter),
+        }
+    }
+
+    #[test]
+    fn mode_is_rejected_on_other_fields() {
+        let internal_data_model = internal_data_model();
+        let map = filter_map(vec![
+            ("age_gt", Value::Int(3.into())),
+            ("mode", Value::Enum("insensitive".into())),
+        ]);
+
+        match extract_filter(&map, user_model(&internal_data_model)) {
+            Err(CoreError::QueryValidationError(message)) => assert!(message.contains("`age`")),
+            result => panic!("expected a validation error, got {:?}", result),
+        }
+    }
+
+    fn post_filter(values: Vec<(&str, Value)>) -> CoreResult<Filter> {
+        let internal_data_model = internal_data_model();
+        let post = internal_data_model.find_model("Post").unwrap();
+
+        extract_filter(&filter_map(values), post)
+    }
+
+    fn list_condition(filter: Filter) -> ScalarListCondition {
+        match filter {
+            Filter::And(mut filters) => match *filters.pop().unwrap() {
+                Filter::ScalarList(filter) => filter.condition,
+                filter => panic!("expected a scalar list filter, got {:?}", filter),
+            },
+            filter => panic!("expected a conjunction, got {:?}", filter),
+        }
+    }
+
+    #[test]
+    fn list_operators_map_onto_list_conditions() {
+        let red = || Value::String("red".into());
+        let tags = || Value::List(vec![red(), Value::String("blue".into())]);
+
+        match list_condition(post_filter(vec![("tags_has", red())]).unwrap()) {
+            ScalarListCondition::Contains(value) => assert_eq!(PrismaValue::from("red"), value),
+            condition => panic!("unexpected condition {:?}", condition),
+        }
+
+        match list_condition(post_filter(vec![("tags_has_every", tags())]).unwrap()) {
+            ScalarListCondition::ContainsEvery(values) => assert_eq!(2, values.len()),
+            condition => panic!("unexpected condition {:?}", condition),
+        }
+
+        match list_condition(post_filter(vec![("tags_has_some", tags())]).unwrap()) {
+            ScalarListCondition::ContainsSome(values) => assert_eq!(2, values.len()),
+            condition => panic!("unexpected condition {:?}", condition),
+        }
+
+        match list_condition(post_filter(vec![("tags_is_empty", Value::Boolean(true))]).unwrap()) {
+            ScalarListCondition::ContainsNone => (),
+            condition => panic!("unexpected condition {:?}", condition),
+        }
+    }
+
+    #[test]
+    fn list_operators_are_rejected_on_other_fields() {
+        match post_filter(vec![("title_has", Value::String("red".into()))]) {
+            Err(CoreError::QueryValidationError(message)) => assert!(message.contains("`title`")),
+            result => panic!("expected a validation error, got {:?}", result),
+        }
+    }
+
+    fn user_condition(values: Vec<(&str, Value)>) -> ScalarCondition {
+        let internal_data_model = internal_data_model();
+
+        match extract_filter(&filter_map(values), user_model(&internal_data_model)).unwrap() {
+            Filter::And(mut filters) => match *filters.pop().unwrap() {
+                Filter::Scalar(filter) => filter.condition,
+                filter => panic!("expected a scalar filter, got {:?}", filter),
+            },
+            filter => panic!("expected a conjunction, got {:?}", filter),
+        }
+    }
+
+    #[test]
+    fn date_times_with_an_offset_are_compared_in_utc() {
+        let condition = user_condition(vec![("createdAt", Value::String("2019-05-01T12:30:00+02:00".into()))]);
+        let expected = PrismaValue::DateTime(Utc.ymd(2019, 5, 1).and_hms(10, 30, 0));
+
+        match condition {
+            ScalarCondition::Equals(value) => assert_eq!(expected, value),
+            condition => panic!("unexpected condition {:?}", condition),
+        }
+    }
+
+    #[test]
+    fn strings_looking_like_json_stay_strings() {
+        match user_condition(vec![("name", Value::String("{}".into()))]) {
+            ScalarCondition::Equals(value) => assert_eq!(PrismaValue::from("{}"), value),
+            condition => panic!("unexpected condition {:?}", condition),
+        }
+    }
+
+    #[test]
+    fn invalid_date_times_are_rejected() {
+        let internal_data_model = internal_data_model();
+        let map = filter_map(vec![("createdAt_gt", Value::String("yesterday".into()))]);
+
+        assert!(extract_filter(&map, user_model(&internal_data_model)).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use super::BuilderExt;
-use crate::{query_ast::ManyRelatedRecordsQuery, CoreResult};
+use crate::{query_ast::ManyRelatedRecordsQuery, CoreError, CoreResult};
 
 use graphql_parser::query::Field;
 use prisma_models::{ModelRef, RelationFieldRef};
@@ -42,6 +42,13 @@ impl<'f> BuilderExt for ManyRelationBuilder<'f> {
         let parent_field = Arc::clone(parent);
         let selected_fields = Self::collect_selected_fields(Arc::clone(&model), field, Arc::clone(&parent))?;
         let args = Self::extract_query_args(field, Arc::clone(&model))?;
+
+        if args.relevance.is_some() {
+            return Err(CoreError::QueryValidationError(
+                "Ordering by `_relevance` is only supported on top-level queries".into(),
+            ));
+        }
+
         let name = field.alias.as_ref().unwrap_or(&field.name).clone();
         let fields = Self::collect_selection_order(&field);
 
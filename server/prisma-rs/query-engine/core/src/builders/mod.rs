@@ -8,6 +8,7 @@ mod one_rel;
 mod root;
 mod single;
 mod mutations;
+mod subscription;
 
 pub(crate) mod utils;
 
@@ -17,11 +18,12 @@ pub use one_rel::*;
 pub use root::*;
 pub use single::*;
 pub use mutations::*;
+pub use subscription::*;
 
 pub use self::inflector::Inflector;
 
 use crate::{CoreError, CoreResult, ReadQuery};
-use connector::QueryArguments;
+use connector::{QueryArguments, Relevance};
 use graphql_parser::query::{Field, Selection, Value};
 use prisma_models::{
     Field as ModelField, GraphqlId, ModelRef, OrderBy, RelationFieldRef, InternalDataModelRef, SelectedField,
@@ -32,6 +34,9 @@ use rust_inflector::Inflector as RustInflector;
 use std::{collections::BTreeMap, sync::Arc};
 use uuid::Uuid;
 
+/// `orderBy` values starting with this rank by full-text search relevance
+const RELEVANCE: &str = "_relevance";
+
 /// A common query-builder type
 #[derive(Debug)]
 pub enum Builder<'field> {
@@ -112,7 +117,7 @@ pub trait BuilderExt {
     fn build(self) -> CoreResult<Self::Output>;
 
     fn extract_query_args(field: &Field, model: ModelRef) -> CoreResult<QueryArguments> {
-        field
+        let args = field
             .arguments
             .iter()
             .fold(Ok(QueryArguments::default()), |result, (k, v)| {
@@ -143,14 +148,60 @@ pub trait BuilderExt {
                             Some(num) => Ok(QueryArguments { after: Some((num as usize).into()), ..res }),
                             None => Err(CoreError::QueryValidationError("Invalid number provided".into())),
                         },
+                        ("orderby", Value::Enum(order_arg)) if order_arg.starts_with(RELEVANCE) => Ok(res),
                         ("orderby", Value::Enum(order_arg)) => Self::extract_order_by(res, order_arg, Arc::clone(&model)),
                         ("where", Value::Object(o)) => Self::extract_filter(res, o, Arc::clone(&model)),
+                        ("includedeleted", Value::Boolean(include)) => Ok(QueryArguments { include_deleted: *include, ..res }),
                         (name, _) => Err(CoreError::QueryValidationError(format!("Unknown key: `{}`", name))),
                     }
                 } else {
                     result
                 }
-            })
+            })?;
+
+        Self::extract_relevance(args, field)
+    }
+
+    /// `orderBy: _relevance` ranks by the `_search` filter of the same query,
+    /// so it is resolved after all other arguments.
+    fn extract_relevance(aggregator: QueryArguments, field: &Field) -> CoreResult<QueryArguments> {
+        let order_arg = field.arguments.iter().find_map(|(k, v)| match (k.to_lowercase().as_str(), v) {
+            ("orderby", Value::Enum(order_arg)) if order_arg.starts_with(RELEVANCE) => Some(order_arg.as_str()),
+            _ => None,
+        });
+
+        let sort_order = match order_arg {
+            None => return Ok(aggregator),
+            Some("_relevance") | Some("_relevance_DESC") => SortOrder::Descending,
+            Some("_relevance_ASC") => SortOrder::Ascending,
+            Some(order_arg) => {
+                return Err(CoreError::QueryValidationError(format!("Unknown ordering `{}`", order_arg)));
+            }
+        };
+
+        if aggregator.after.is_some() || aggregator.before.is_some() {
+            return Err(CoreError::QueryValidationError(
+                "Ordering by `_relevance` can't be combined with `after` or `before`".into(),
+            ));
+        }
+
+        let (search_field, query) = match aggregator.filter.as_ref().and_then(filters::find_search) {
+            Some(search) => search,
+            None => {
+                return Err(CoreError::QueryValidationError(
+                    "Ordering by `_relevance` requires a `_search` filter".into(),
+                ));
+            }
+        };
+
+        Ok(QueryArguments {
+            relevance: Some(Relevance {
+                field: search_field,
+                query,
+                sort_order,
+            }),
+            ..aggregator
+        })
     }
 
     fn extract_order_by(aggregator: QueryArguments, order_arg: &str, model: ModelRef) -> CoreResult<QueryArguments> {
@@ -1,12 +1,12 @@
 //! Simple wrapper for WriteQueries
 
-use crate::{builders::utils, BuilderExt, ManyBuilder, ReadQuery, SingleBuilder};
+use crate::{builders::utils, BuilderExt, CountQueryResult, ManyBuilder, ReadQuery, ReadQueryResult, SingleBuilder};
 use connector::mutaction::{
     DatabaseMutactionResult as MutationResult, NestedDatabaseMutaction as NestedMutation,
-    TopLevelDatabaseMutaction as RootMutation, Identifier,
+    TopLevelDatabaseMutaction as RootMutation, Identifier, DatabaseMutactionResultType as MutationResultType,
 };
 use graphql_parser::query::Field;
-use prisma_models::ModelRef;
+use prisma_models::{GraphqlId, ModelRef};
 use std::sync::Arc;
 
 /// A top-level write query (mutation)
@@ -39,9 +39,10 @@ impl WriteQuery {
     pub fn model(&self) -> ModelRef {
         match self.inner {
             RootMutation::CreateNode(ref node) => Arc::clone(&node.model),
-            RootMutation::UpdateNode(ref node) => node.where_.field.model.upgrade().unwrap(),
-            RootMutation::DeleteNode(ref node) => node.where_.field.model.upgrade().unwrap(),
-            RootMutation::UpsertNode(ref node) => node.where_.field.model.upgrade().unwrap(),
+            RootMutation::CreateNodes(ref nodes) => Arc::clone(&nodes.model),
+            RootMutation::UpdateNode(ref node) => node.where_.model(),
+            RootMutation::DeleteNode(ref node) => node.where_.model(),
+            RootMutation::UpsertNode(ref node) => node.where_.model(),
             RootMutation::UpdateNodes(ref nodes) => Arc::clone(&nodes.model),
             RootMutation::DeleteNodes(ref nodes) => Arc::clone(&nodes.model),
             _ => unimplemented!(),
@@ -65,9 +66,31 @@ impl WriteQuery {
         }
     }
 
+    /// The `ReadQuery` that will follow this `WriteQuery`, selecting a placeholder id
+    pub fn preview_read(&self) -> Option<ReadQuery> {
+        let id = GraphqlId::Int(0);
+        let result = MutationResult {
+            identifier: Identifier::Id(id),
+            typ: MutationResultType::Create,
+        };
+
+        match self.inner {
+            RootMutation::CreateNode(_) | RootMutation::UpdateNode(_) | RootMutation::UpsertNode(_) => {
+                self.generate_read(result)
+            }
+            _ => None,
+        }
+    }
+
     /// Generate a `ReadQuery` from the encapsulated `WriteQuery`
     #[warn(warnings)]
     pub fn generate_read(&self, res: MutationResult) -> Option<ReadQuery> {
+        // Batch writes return their result without reading any records
+        match self.inner {
+            RootMutation::CreateNodes(_) | RootMutation::UpdateNodes(_) => return None,
+            _ => (),
+        }
+
         let field = match res.identifier {
             Identifier::Id(gql_id) => utils::derive_field(&self.field, self.model(), gql_id),
             _ => unimplemented!(),
@@ -82,7 +105,7 @@ impl WriteQuery {
                 .ok()
                 .map(|q| ReadQuery::RecordQuery(q)),
 
-            RootMutation::UpdateNode(_) => SingleBuilder::new()
+            RootMutation::UpdateNode(_) | RootMutation::UpsertNode(_) => SingleBuilder::new()
                 .setup(self.model(), &field)
                 .build()
                 .ok()
@@ -90,6 +113,20 @@ impl WriteQuery {
             _ => unimplemented!(),
         }
     }
+
+    /// The result of a batch `WriteQuery`, returned in place of a `ReadQuery`
+    pub fn batch_result(&self, res: &MutationResult) -> Option<ReadQueryResult> {
+        match (&self.inner, &res.identifier) {
+            (RootMutation::CreateNodes(_), Identifier::Count(count))
+            | (RootMutation::UpdateNodes(_), Identifier::Count(count)) => {
+                Some(ReadQueryResult::Count(CountQueryResult {
+                    name: self.field.alias.as_ref().unwrap_or(&self.field.name).clone(),
+                    count: *count,
+                }))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl NestedWriteQuery {
@@ -1,9 +1,21 @@
 //! Providing an interface to build WriteQueries
 
-use crate::{builders::utils, CoreError, CoreResult, WriteQuery};
-use connector::mutaction::{CreateNode, DeleteNode, DeleteNodes, TopLevelDatabaseMutaction, UpdateNode, UpsertNode};
+use crate::{
+    builders::{filters, utils},
+    CoreError, CoreResult, WriteQuery,
+};
+use connector::{
+    filter::Filter,
+    mutaction::{
+        CreateNode, CreateNodes, CreateNodesRecord, DeleteNode, DeleteNodes, NestedConnectOrCreate, NestedCreateNode,
+        NestedMutactions, TopLevelDatabaseMutaction, UpdateNode, UpdateNodes, UpsertNode,
+    },
+};
 use graphql_parser::query::{Field, Value};
-use prisma_models::{InternalDataModelRef, ModelRef, PrismaArgs, PrismaValue};
+use prisma_models::{
+    InternalDataModelRef, ModelRef, NumericOperation, PrismaArgs, PrismaValue, RelationFieldRef, ScalarListOperation,
+    TypeIdentifier,
+};
 
 use crate::Inflector;
 use rust_inflector::Inflector as RustInflector;
@@ -22,6 +34,7 @@ pub struct MutationBuilder<'field> {
 }
 
 type PrismaListArgs = Vec<(String, Option<Vec<PrismaValue>>)>;
+type PrismaListOperations = Vec<(String, ScalarListOperation)>;
 
 impl<'field> MutationBuilder<'field> {
     pub fn new(internal_data_model: InternalDataModelRef, field: &'field Field) -> Self {
@@ -32,47 +45,85 @@ impl<'field> MutationBuilder<'field> {
     }
 
     pub fn build(self) -> CoreResult<WriteQuery> {
-        let (non_list_args, list_args) = get_mutation_args(&self.field.arguments);
         let (op, model) = parse_model_action(
             self.field.alias.as_ref().unwrap_or_else(|| &self.field.name),
             Arc::clone(&self.internal_data_model),
         )?;
 
+        // Batch creates take a list of records instead of a single one
+        if let Operation::CreateMany = op {
+            return Ok(WriteQuery {
+                inner: TopLevelDatabaseMutaction::CreateNodes(get_create_many_args(&self.field.arguments, model)?),
+                field: self.field.clone(),
+                nested: vec![],
+            });
+        }
+
+        // Batch updates select their records with a filter instead of a node selector
+        if let Operation::UpdateMany = op {
+            return Ok(WriteQuery {
+                inner: TopLevelDatabaseMutaction::UpdateNodes(get_update_many_args(&self.field.arguments, model)?),
+                field: self.field.clone(),
+                nested: vec![],
+            });
+        }
+
+        let (non_list_args, list_args, list_operations) = get_mutation_args(&self.field.arguments, &model)?;
+
+        if let Operation::Create = op {
+            reject_operations(&non_list_args, &list_operations)?;
+        }
+
         let inner = match op {
             Operation::Create => TopLevelDatabaseMutaction::CreateNode(CreateNode {
+                nested_mutactions: get_nested_mutactions(&self.field.arguments, "data", &model, true)?,
                 model,
                 non_list_args,
                 list_args,
-                nested_mutactions: Default::default(),
             }),
             Operation::Update => TopLevelDatabaseMutaction::UpdateNode(UpdateNode {
                 where_: utils::extract_node_selector(self.field, Arc::clone(&model))?,
                 non_list_args,
                 list_args,
-                nested_mutactions: Default::default(),
+                list_operations,
+                nested_mutactions: get_nested_mutactions(&self.field.arguments, "data", &model, false)?,
+                expected_version: get_expected_version(&self.field.arguments, &model)?,
             }),
             Operation::Delete => TopLevelDatabaseMutaction::DeleteNode(DeleteNode {
                 where_: utils::extract_node_selector(self.field, Arc::clone(&model))?,
+                expected_version: get_expected_version(&self.field.arguments, &model)?,
             }),
             Operation::DeleteMany => TopLevelDatabaseMutaction::DeleteNodes(DeleteNodes {
                 model,
                 filter: unsafe { std::mem::uninitialized() }, // BOOM
             }),
-            Operation::Upsert => TopLevelDatabaseMutaction::UpsertNode(UpsertNode {
-                where_: utils::extract_node_selector(self.field, Arc::clone(&model))?,
-                create: CreateNode {
-                    model: Arc::clone(&model),
-                    non_list_args: non_list_args.clone(),
-                    list_args: list_args.clone(),
-                    nested_mutactions: Default::default(),
-                },
-                update: UpdateNode {
+            Operation::Upsert => {
+                // The create and the update take their values from their own arguments
+                let (create_args, create_list_args, create_list_operations) =
+                    get_mutation_args(&named_arguments(&self.field.arguments, "create"), &model)?;
+                reject_operations(&create_args, &create_list_operations)?;
+
+                let (update_args, update_list_args, update_list_operations) =
+                    get_mutation_args(&named_arguments(&self.field.arguments, "update"), &model)?;
+
+                TopLevelDatabaseMutaction::UpsertNode(UpsertNode {
                     where_: utils::extract_node_selector(self.field, Arc::clone(&model))?,
-                    non_list_args,
-                    list_args,
-                    nested_mutactions: Default::default(),
-                },
-            }),
+                    create: CreateNode {
+                        model: Arc::clone(&model),
+                        non_list_args: create_args,
+                        list_args: create_list_args,
+                        nested_mutactions: get_nested_mutactions(&self.field.arguments, "create", &model, true)?,
+                    },
+                    update: UpdateNode {
+                        where_: utils::extract_node_selector(self.field, Arc::clone(&model))?,
+                        non_list_args: update_args,
+                        list_args: update_list_args,
+                        list_operations: update_list_operations,
+                        nested_mutactions: get_nested_mutactions(&self.field.arguments, "update", &model, false)?,
+                        expected_version: None,
+                    },
+                })
+            }
             _ => unimplemented!(),
         };
 
@@ -86,45 +137,336 @@ impl<'field> MutationBuilder<'field> {
 }
 
 /// Extract String-Value pairs into usable mutation arguments
-fn get_mutation_args(args: &Vec<(String, Value)>) -> (PrismaArgs, PrismaListArgs) {
+fn get_mutation_args(
+    args: &Vec<(String, Value)>,
+    model: &ModelRef,
+) -> CoreResult<(PrismaArgs, PrismaListArgs, PrismaListOperations)> {
+    // Values for scalar fields are read with the field's type, the others as they come
+    let value_for = |k: &str, v: &Value| match model.fields().find_from_scalar(k) {
+        Ok(field) => PrismaValue::from_typed_value(v, field.type_identifier),
+        Err(_) => Ok(PrismaValue::from_value(v)),
+    };
+
+    let mut operations = BTreeMap::new();
+    let mut list_operations = vec![];
+
     let (args, lists) = args
         .iter()
-        .fold((BTreeMap::new(), vec![]), |(mut map, mut vec), (_, v)| {
+        .try_fold((BTreeMap::new(), vec![]), |(mut map, mut vec), (name, v)| {
             match v {
-                Value::Object(o) => o.iter().for_each(|(k, v)| {
-                    // If the child is an object, we are probably dealing with ScalarList values
-                    match v {
-                        Value::Object(o) if o.contains_key("set") => {
-                            vec.push((
-                                k.clone(),
-                                match o.get("set") {
-                                    Some(Value::List(l)) => Some(
-                                        l.iter()
-                                            .map(|v| PrismaValue::from_value(v))
-                                            .collect::<Vec<PrismaValue>>(),
-                                    ),
-                                    None => None,
-                                    _ => unimplemented!(), // or unreachable? dunn duuuuun!
-                                },
-                            ));
+                Value::Object(o) => {
+                    for (k, v) in o.iter() {
+                        // Relation fields hold nested mutations, see `get_nested_mutactions`
+                        if model.fields().find_from_relation_fields(k).is_ok() {
+                            continue;
                         }
-                        v => {
-                            map.insert(k.clone(), PrismaValue::from_value(v));
+
+                        // If the child is an object, we are probably dealing with ScalarList values
+                        match v {
+                            Value::Object(o) if o.contains_key("set") => {
+                                vec.push((
+                                    k.clone(),
+                                    match o.get("set") {
+                                        Some(Value::List(l)) => Some(
+                                            l.iter()
+                                                .map(|v| value_for(k, v))
+                                                .collect::<Result<Vec<PrismaValue>, _>>()?,
+                                        ),
+                                        None => None,
+                                        _ => unimplemented!(), // or unreachable? dunn duuuuun!
+                                    },
+                                ));
+                            }
+                            Value::Object(o) if is_list_operation(o) => {
+                                list_operations.push((k.clone(), get_list_operation(k, o, model)?));
+                            }
+                            Value::Object(o) if is_numeric_operation(o) => {
+                                operations.insert(k.clone(), get_numeric_operation(k, o, model)?);
+                            }
+                            v => {
+                                map.insert(k.clone(), value_for(k, v)?);
+                            }
                         }
                     }
-                }),
+                }
+                // Read by `get_expected_version`
+                _ if name == "expectedVersion" => (),
                 _ => panic!("Unknown argument structure!"),
             }
 
-            (map, vec)
-        });
-    (args.into(), lists)
+            Ok::<_, CoreError>((map, vec))
+        })?;
+
+    let mut args: PrismaArgs = args.into();
+    args.operations = operations;
+
+    Ok((args, lists, list_operations))
+}
+
+/// The arguments with the given name
+fn named_arguments(args: &Vec<(String, Value)>, name: &str) -> Vec<(String, Value)> {
+    args.iter().filter(|(arg_name, _)| arg_name == name).cloned().collect()
+}
+
+/// Extract the `expectedVersion` argument, only allowed on models with a version field
+fn get_expected_version(args: &Vec<(String, Value)>, model: &ModelRef) -> CoreResult<Option<PrismaValue>> {
+    let value = match args.iter().find(|(name, _)| name == "expectedVersion") {
+        Some((_, value)) => value,
+        None => return Ok(None),
+    };
+
+    match model.fields().version() {
+        Some(field) => Ok(Some(PrismaValue::from_typed_value(value, field.type_identifier)?)),
+        None => Err(CoreError::QueryValidationError(format!(
+            "Model `{}` has no version field",
+            model.name
+        ))),
+    }
+}
+
+/// Numeric operations are objects like `{ increment: 1 }`
+fn is_numeric_operation(o: &BTreeMap<String, Value>) -> bool {
+    let operations = ["increment", "decrement", "multiply"];
+    o.len() == 1 && o.keys().all(|k| operations.contains(&k.as_str()))
+}
+
+/// Extract a numeric operation, only allowed on `Int`, `Float` and `Decimal` fields
+fn get_numeric_operation(name: &str, o: &BTreeMap<String, Value>, model: &ModelRef) -> CoreResult<NumericOperation> {
+    let field = match model.fields().find_from_scalar(name) {
+        Ok(field) => field,
+        Err(_) => {
+            return Err(CoreError::QueryValidationError(format!(
+                "Field `{}` is not a scalar field",
+                name
+            )))
+        }
+    };
+
+    match field.type_identifier {
+        TypeIdentifier::Int | TypeIdentifier::Float | TypeIdentifier::Decimal => (),
+        _ => {
+            return Err(CoreError::QueryValidationError(format!(
+                "Numeric operations are not supported on field `{}`",
+                name
+            )))
+        }
+    }
+
+    // UNWRAP: `is_numeric_operation` made sure there is exactly one entry.
+    let (op, value) = o.iter().next().unwrap();
+    let value = PrismaValue::from_typed_value(value, field.type_identifier)?;
+
+    match op.as_str() {
+        "increment" => Ok(NumericOperation::Increment(value)),
+        "decrement" => Ok(NumericOperation::Decrement(value)),
+        _ => Ok(NumericOperation::Multiply(value)),
+    }
+}
+
+/// List operations are objects like `{ push: [1, 2] }` or `{ unset: true }`
+fn is_list_operation(o: &BTreeMap<String, Value>) -> bool {
+    let operations = ["push", "unshift", "remove", "unset"];
+    o.len() == 1 && o.keys().all(|k| operations.contains(&k.as_str()))
+}
+
+/// Extract a list operation, taking a single value or a list of values
+fn get_list_operation(name: &str, o: &BTreeMap<String, Value>, model: &ModelRef) -> CoreResult<ScalarListOperation> {
+    let field = match model.fields().find_from_scalar(name) {
+        Ok(ref field) if field.is_list => Arc::clone(field),
+        _ => {
+            return Err(CoreError::QueryValidationError(format!(
+                "Field `{}` is not a scalar list",
+                name
+            )))
+        }
+    };
+
+    // UNWRAP: `is_list_operation` made sure there is exactly one entry.
+    let (op, value) = o.iter().next().unwrap();
+
+    if op == "unset" {
+        return Ok(ScalarListOperation::Unset);
+    }
+
+    let values = match value {
+        Value::List(l) => l
+            .iter()
+            .map(|v| PrismaValue::from_typed_value(v, field.type_identifier))
+            .collect::<Result<Vec<PrismaValue>, _>>()?,
+        v => vec![PrismaValue::from_typed_value(v, field.type_identifier)?],
+    };
+
+    match op.as_str() {
+        "push" => Ok(ScalarListOperation::Push(values)),
+        "unshift" => Ok(ScalarListOperation::Unshift(values)),
+        _ => Ok(ScalarListOperation::Remove(values)),
+    }
+}
+
+/// New records have no values for numeric or list operations to work on
+fn reject_operations(args: &PrismaArgs, list_operations: &PrismaListOperations) -> CoreResult<()> {
+    let mut names = args
+        .operations
+        .keys()
+        .chain(list_operations.iter().map(|(name, _)| name));
+
+    match names.next() {
+        Some(name) => Err(CoreError::QueryValidationError(format!(
+            "Operations are only supported in updates, found one on `{}`",
+            name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Extract the nested mutations given for relation fields in the argument `arg_name`
+///
+/// Only `connectOrCreate` is supported so far.
+fn get_nested_mutactions(
+    args: &Vec<(String, Value)>,
+    arg_name: &str,
+    model: &ModelRef,
+    top_is_create: bool,
+) -> CoreResult<NestedMutactions> {
+    let mut nested = NestedMutactions::default();
+
+    let data = match args.iter().find(|(name, _)| name == arg_name) {
+        Some((_, Value::Object(o))) => o,
+        _ => return Ok(nested),
+    };
+
+    for (k, v) in data.iter() {
+        let relation_field = match model.fields().find_from_relation_fields(k) {
+            Ok(rf) => rf,
+            Err(_) => continue,
+        };
+
+        if let Value::Object(ops) = v {
+            // To-many relations take a list, to-one relations a single object
+            let values = match ops.get("connectOrCreate") {
+                Some(Value::List(l)) => l.iter().collect(),
+                Some(v) => vec![v],
+                None => vec![],
+            };
+
+            for value in values {
+                let connect_or_create = get_connect_or_create(value, Arc::clone(&relation_field), top_is_create)?;
+                nested.connect_or_creates.push(connect_or_create);
+            }
+        }
+    }
+
+    Ok(nested)
+}
+
+/// Extract a `connectOrCreate: { where, create }` nested mutation
+fn get_connect_or_create(
+    value: &Value,
+    relation_field: RelationFieldRef,
+    top_is_create: bool,
+) -> CoreResult<NestedConnectOrCreate> {
+    let related_model = relation_field.related_model();
+
+    let (where_, create) = match value {
+        Value::Object(o) => match (o.get("where"), o.get("create")) {
+            (Some(where_), Some(create)) => (where_, create),
+            _ => {
+                return Err(CoreError::QueryValidationError(format!(
+                    "connectOrCreate on `{}` needs both `where` and `create`",
+                    relation_field.name
+                )))
+            }
+        },
+        _ => return Err(CoreError::QueryValidationError("Unknown argument structure!".into())),
+    };
+
+    let args = vec![("data".to_string(), create.clone())];
+    let (non_list_args, list_args, list_operations) = get_mutation_args(&args, &related_model)?;
+    reject_operations(&non_list_args, &list_operations)?;
+
+    Ok(NestedConnectOrCreate {
+        where_: utils::node_selector_from_value(where_, Arc::clone(&related_model))?,
+        create: NestedCreateNode {
+            relation_field: Arc::clone(&relation_field),
+            non_list_args,
+            list_args,
+            top_is_create,
+            nested_mutactions: get_nested_mutactions(&args, "data", &related_model, true)?,
+        },
+        relation_field,
+        top_is_create,
+    })
+}
+
+/// Extract the records and options of a `createMany` mutation
+fn get_create_many_args(args: &Vec<(String, Value)>, model: ModelRef) -> CoreResult<CreateNodes> {
+    let mut records = vec![];
+    let mut skip_duplicates = false;
+
+    for (name, value) in args {
+        match (name.as_str(), value) {
+            ("data", Value::List(list)) => {
+                for data in list {
+                    let (non_list_args, list_args, list_operations) =
+                        get_mutation_args(&vec![(name.clone(), data.clone())], &model)?;
+                    reject_operations(&non_list_args, &list_operations)?;
+
+                    records.push(CreateNodesRecord {
+                        non_list_args,
+                        list_args,
+                    });
+                }
+            }
+            ("skipDuplicates", Value::Boolean(skip)) => skip_duplicates = *skip,
+            (name, _) => {
+                return Err(CoreError::QueryValidationError(format!(
+                    "Unknown argument `{}` for createMany",
+                    name
+                )))
+            }
+        }
+    }
+
+    Ok(CreateNodes {
+        model,
+        records,
+        skip_duplicates,
+    })
+}
+
+/// Extract the values and the filter of an `updateMany` mutation
+fn get_update_many_args(args: &Vec<(String, Value)>, model: ModelRef) -> CoreResult<UpdateNodes> {
+    let mut filter = Filter::and(vec![]);
+
+    for (name, value) in args {
+        match (name.as_str(), value) {
+            ("data", _) => (),
+            ("where", Value::Object(map)) => filter = filters::extract_filter(map, Arc::clone(&model))?,
+            (name, _) => {
+                return Err(CoreError::QueryValidationError(format!(
+                    "Unknown argument `{}` for updateMany",
+                    name
+                )))
+            }
+        }
+    }
+
+    let (non_list_args, list_args, list_operations) = get_mutation_args(&named_arguments(args, "data"), &model)?;
+
+    Ok(UpdateNodes {
+        model,
+        filter,
+        non_list_args,
+        list_args,
+        list_operations,
+    })
 }
 
 /// A simple enum to discriminate top-level actions
 #[allow(dead_code)] // FIXME: Remove!
 enum Operation {
     Create,
+    CreateMany,
     Update,
     Delete,
     Upsert,
@@ -137,6 +479,7 @@ impl From<&str> for Operation {
     fn from(s: &str) -> Self {
         match s {
             "create" => Operation::Create,
+            "createMany" => Operation::CreateMany,
             "update" => Operation::Update,
             "updateMany" => Operation::UpdateMany,
             "delete" => Operation::Delete,
@@ -149,7 +492,15 @@ impl From<&str> for Operation {
 
 /// Parse the mutation name into an action and the model it should operate on
 fn parse_model_action(name: &String, internal_data_model: InternalDataModelRef) -> CoreResult<(Operation, ModelRef)> {
-    let actions = vec!["create", "updateMany", "update", "deleteMany", "delete", "upsert"];
+    let actions = vec![
+        "createMany",
+        "create",
+        "updateMany",
+        "update",
+        "deleteMany",
+        "delete",
+        "upsert",
+    ];
 
     let action = match actions.iter().find(|action| name.starts_with(*action)) {
         Some(a) => a,
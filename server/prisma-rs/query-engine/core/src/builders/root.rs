@@ -1,5 +1,5 @@
 use super::Builder;
-use crate::{CoreResult, Query as PrismaQuery, MutationBuilder};
+use crate::{CoreError, CoreResult, Query as PrismaQuery, MutationBuilder, Subscription as PrismaSubscription, SubscriptionBuilder};
 use graphql_parser::query::*;
 use prisma_models::InternalDataModelRef;
 use std::sync::Arc;
@@ -39,12 +39,37 @@ impl RootBuilder {
                     directives: _,
                     selection_set,
                 })) => self.build_mutation(&selection_set.items),
+
+                Definition::Operation(OperationDefinition::Subscription(_)) => Err(CoreError::UnsupportedFeatureError(
+                    "Subscriptions are only supported over websockets.".into(),
+                )),
                 _ => unimplemented!(),
             })
             .collect::<CoreResult<Vec<Vec<PrismaQuery>>>>() // Collect all the "query trees"
             .map(|v| v.into_iter().flatten().collect())
     }
 
+    /// Builds the subscriptions of a document, any other operation is rejected
+    pub fn build_subscriptions(self) -> CoreResult<Vec<PrismaSubscription>> {
+        self.query
+            .definitions
+            .iter()
+            .map(|d| match d {
+                Definition::Operation(OperationDefinition::Subscription(Subscription {
+                    position: _,
+                    name: _,
+                    variable_definitions: _,
+                    directives: _,
+                    selection_set,
+                })) => self.build_subscription(&selection_set.items),
+                _ => Err(CoreError::QueryValidationError(
+                    "Only subscription operations are allowed here.".into(),
+                )),
+            })
+            .collect::<CoreResult<Vec<Vec<PrismaSubscription>>>>()
+            .map(|v| v.into_iter().flatten().collect())
+    }
+
     fn build_query(&self, root_fields: &Vec<Selection>) -> CoreResult<Vec<PrismaQuery>> {
         root_fields
             .iter()
@@ -70,6 +95,21 @@ impl RootBuilder {
             })
             .collect()
     }
+
+    /// Every root field of a subscription subscribes to the events of one model
+    fn build_subscription(&self, root_fields: &Vec<Selection>) -> CoreResult<Vec<PrismaSubscription>> {
+        root_fields
+            .iter()
+            .map(|item| {
+                match item {
+                    Selection::Field(root_field) => SubscriptionBuilder::new(Arc::clone(&self.internal_data_model), root_field).build(),
+                    _ => Err(CoreError::QueryValidationError(
+                        "Fragments are not supported on the root of a subscription.".into(),
+                    )),
+                }
+            })
+            .collect()
+    }
 }
 
 trait UuidCheck {
@@ -0,0 +1,227 @@
+//! Builds subscriptions and renders their payloads for mutation events
+
+use super::{filters, utils, BuilderExt, SingleBuilder};
+use crate::{
+    ir,
+    subscriptions::{MutationEvent, MutationType, SubscriptionFilter},
+    CoreError, CoreResult, ReadQuery, ReadQueryResult, RecordQuery,
+};
+use graphql_parser::query::{Field, Selection, Value};
+use prisma_models::{GraphqlId, InternalDataModelRef, ModelRef, PrismaValue, SingleNode};
+use rust_inflector::Inflector as RustInflector;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// The fields a subscription payload can select
+const PAYLOAD_FIELDS: &[&str] = &["mutation", "updatedFields", "node", "previousValues"];
+
+/// A subscription to the mutation events of a single model
+#[derive(Debug, Clone)]
+pub struct Subscription {
+    pub name: String,
+    pub model: ModelRef,
+    pub filter: SubscriptionFilter,
+
+    /// Required to create the payload for an event
+    pub field: Field,
+}
+
+pub struct SubscriptionBuilder {
+    internal_data_model: InternalDataModelRef,
+    field: Field,
+}
+
+impl SubscriptionBuilder {
+    pub fn new(internal_data_model: InternalDataModelRef, field: &Field) -> Self {
+        Self {
+            internal_data_model,
+            field: field.clone(),
+        }
+    }
+
+    pub fn build(self) -> CoreResult<Subscription> {
+        let model = self
+            .internal_data_model
+            .models()
+            .iter()
+            .find(|m| m.name.to_camel_case() == self.field.name)
+            .map(|m| Arc::clone(m))
+            .ok_or_else(|| {
+                CoreError::QueryValidationError(format!("Model not found for subscription field {}", self.field.name))
+            })?;
+
+        for item in &self.field.selection_set.items {
+            match item {
+                Selection::Field(f) if PAYLOAD_FIELDS.contains(&f.name.as_str()) => (),
+                Selection::Field(f) => Err(CoreError::QueryValidationError(format!(
+                    "Selected field {} not found on subscription payload of {}",
+                    f.name, model.name
+                )))?,
+                _ => Err(CoreError::UnsupportedFeatureError(
+                    "Fragments and inline fragment spreads.".into(),
+                ))?,
+            }
+        }
+
+        let filter = match self.field.arguments.iter().find(|(k, _)| k == "where") {
+            Some((_, Value::Object(o))) => Self::extract_filter(o, Arc::clone(&model))?,
+            Some((_, v)) => Err(CoreError::QueryValidationError(format!(
+                "Invalid where argument {:?}",
+                v
+            )))?,
+            None => SubscriptionFilter::default(),
+        };
+
+        Ok(Subscription {
+            name: self.field.alias.as_ref().unwrap_or(&self.field.name).clone(),
+            model,
+            filter,
+            field: self.field,
+        })
+    }
+
+    fn extract_filter(map: &BTreeMap<String, Value>, model: ModelRef) -> CoreResult<SubscriptionFilter> {
+        map.iter().fold(Ok(SubscriptionFilter::default()), |result, (k, v)| {
+            let res = result?;
+
+            match (k.as_str(), v) {
+                ("mutation_in", Value::List(l)) => {
+                    let types = l
+                        .iter()
+                        .map(|v| match v {
+                            Value::Enum(e) => MutationType::from_str(e).ok_or_else(|| {
+                                CoreError::QueryValidationError(format!("Unknown mutation type `{}`", e))
+                            }),
+                            v => Err(CoreError::QueryValidationError(format!(
+                                "Invalid mutation type {:?}",
+                                v
+                            ))),
+                        })
+                        .collect::<CoreResult<Vec<_>>>()?;
+
+                    Ok(SubscriptionFilter {
+                        mutation_in: Some(types),
+                        ..res
+                    })
+                }
+                ("updatedFields_contains", Value::String(s)) => Ok(SubscriptionFilter {
+                    updated_fields_contains: Some(s.clone()),
+                    ..res
+                }),
+                ("node", Value::Object(o)) => {
+                    let filter = filters::extract_filter(o, Arc::clone(&model))?;
+
+                    if !SubscriptionFilter::supports(&filter) {
+                        return Err(CoreError::UnsupportedFeatureError(
+                            "Relation and scalar list filters in subscriptions.".into(),
+                        ));
+                    }
+
+                    Ok(SubscriptionFilter {
+                        node: Some(filter),
+                        ..res
+                    })
+                }
+                (name, _) => Err(CoreError::QueryValidationError(format!(
+                    "Unknown subscription filter: `{}`",
+                    name
+                ))),
+            }
+        })
+    }
+}
+
+impl Subscription {
+    /// Generate the `ReadQuery` for the `node` selection of the payload, if any.
+    /// Deleted nodes can't be read anymore, their payload only has `previousValues`.
+    pub fn generate_read(&self, event: &MutationEvent) -> Option<ReadQuery> {
+        if event.mutation_type == MutationType::Deleted {
+            return None;
+        }
+
+        // The selector of the event also finds nodes identified by compound ids
+        let query = self.record_query()?;
+
+        Some(ReadQuery::RecordQuery(RecordQuery {
+            selector: event.selector.clone(),
+            ..query
+        }))
+    }
+
+    /// The `ReadQuery` for the `node` selection, to check it before subscribing
+    pub fn preview_read(&self) -> Option<ReadQuery> {
+        self.record_query().map(ReadQuery::RecordQuery)
+    }
+
+    /// The query for the `node` selection, with a placeholder id as selector
+    fn record_query(&self) -> Option<RecordQuery> {
+        let node_field = self.payload_field("node")?;
+        let field = utils::derive_field(node_field, Arc::clone(&self.model), GraphqlId::Int(0));
+
+        SingleBuilder::new().setup(Arc::clone(&self.model), &field).build().ok()
+    }
+
+    /// Render the payload of an event, `node` is the result of the query from `generate_read`
+    pub fn build_response(&self, event: &MutationEvent, node: Option<ReadQueryResult>) -> ir::Response {
+        let mut node = node;
+        let map: ir::Map = self
+            .payload_fields()
+            .map(|f| {
+                let name = f.alias.as_ref().unwrap_or(&f.name).clone();
+                let item = match f.name.as_str() {
+                    "mutation" => ir::Item::Value(PrismaValue::Enum(event.mutation_type.as_str().into())),
+                    "updatedFields" if event.mutation_type == MutationType::Updated => ir::Item::List(
+                        event
+                            .updated_fields
+                            .iter()
+                            .map(|name| ir::Item::Value(PrismaValue::String(name.clone())))
+                            .collect(),
+                    ),
+                    "node" => match node.take().and_then(|n| ir::Builder::new().add(n).build().pop()) {
+                        Some(ir::Response::Data(_, item)) => item,
+                        _ => ir::Item::Value(PrismaValue::Null),
+                    },
+                    "previousValues" => match event.previous_values {
+                        Some(ref previous) => Self::previous_values(f, previous),
+                        None => ir::Item::Value(PrismaValue::Null),
+                    },
+                    _ => ir::Item::Value(PrismaValue::Null),
+                };
+
+                (name, item)
+            })
+            .collect();
+
+        ir::Response::Data(self.name.clone(), ir::Item::Map(None, map))
+    }
+
+    fn previous_values(field: &Field, previous: &SingleNode) -> ir::Item {
+        let map: ir::Map = field
+            .selection_set
+            .items
+            .iter()
+            .filter_map(|i| match i {
+                Selection::Field(f) => Some(f),
+                _ => None,
+            })
+            .map(|f| {
+                let name = f.alias.as_ref().unwrap_or(&f.name).clone();
+                let value = previous.get_field_value(&f.name).ok().cloned();
+
+                (name, ir::Item::Value(value.unwrap_or(PrismaValue::Null)))
+            })
+            .collect();
+
+        ir::Item::Map(None, map)
+    }
+
+    fn payload_fields(&self) -> impl Iterator<Item = &Field> {
+        self.field.selection_set.items.iter().filter_map(|i| match i {
+            Selection::Field(f) => Some(f),
+            _ => None,
+        })
+    }
+
+    fn payload_field(&self, name: &str) -> Option<&Field> {
+        self.payload_fields().find(|f| f.name == name)
+    }
+}
@@ -1,9 +1,9 @@
 //! A set of utilities to build (read & write) queries
 
 use graphql_parser::query::{Field, Value};
-use prisma_models::{ModelRef, PrismaValue, GraphqlId};
+use prisma_models::{DomainError, ModelRef, PrismaValue, GraphqlId};
 use connector::filter::NodeSelector;
-use crate::CoreResult;
+use crate::{CoreError, CoreResult};
 
 use std::sync::Arc;
 use std::collections::BTreeMap;
@@ -13,19 +13,90 @@ pub(crate) fn extract_node_selector(field: &Field, model: ModelRef) -> CoreResul
 
     // FIXME: this expects at least one query arg...
     let (_, value) = field.arguments.first().expect("no arguments found");
-    match value {
-        Value::Object(obj) => {
-            let (field_name, value) = obj.iter().next().expect("object was empty");
-            let field = model.fields().find_from_scalar(field_name).unwrap();
-            let value = PrismaValue::from_value(value);
-
-            Ok(NodeSelector {
-                field: Arc::clone(&field),
-                value: value,
-            })
+    node_selector_from_value(value, model)
+}
+
+/// Get node selector from a `where` value and model
+///
+/// The key is either a unique scalar field or the name of a compound unique
+/// (its field names joined by `_`), in which case the value is an object
+/// holding a value for every field of the compound.
+pub(crate) fn node_selector_from_value(value: &Value, model: ModelRef) -> CoreResult<NodeSelector> {
+    let obj = match value {
+        Value::Object(obj) if obj.len() == 1 => obj,
+        Value::Object(obj) if obj.is_empty() => {
+            return Err(CoreError::QueryValidationError(format!(
+                "A where selector on model {} needs a unique field",
+                model.name
+            )))
+        }
+        Value::Object(_) => {
+            return Err(CoreError::QueryValidationError(format!(
+                "A where selector on model {} takes exactly one unique field or compound unique",
+                model.name
+            )))
         }
-        _ => unimplemented!(),
+        value => {
+            return Err(CoreError::QueryValidationError(format!(
+                "Expected an object as where selector on model {}, got {:?}",
+                model.name, value
+            )))
+        }
+    };
+
+    let (field_name, value) = obj.iter().next().unwrap();
+
+    if let Ok(field) = model.fields().find_from_scalar(field_name) {
+        let value = PrismaValue::from_typed_value(value, field.type_identifier)?;
+        return Ok(NodeSelector::new(Arc::clone(&field), value));
     }
+
+    let fields = match model.find_compound_unique(field_name) {
+        Ok(fields) => fields,
+        Err(DomainError::CompoundUniqueNotFound { .. }) => {
+            return Err(CoreError::QueryValidationError(format!(
+                "{} is not a unique field or compound unique of model {}",
+                field_name, model.name
+            )))
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let values = match value {
+        Value::Object(values) => values,
+        value => {
+            return Err(CoreError::QueryValidationError(format!(
+                "Expected an object with the fields of compound unique {}, got {:?}",
+                field_name, value
+            )))
+        }
+    };
+
+    if let Some(name) = values
+        .keys()
+        .find(|name| fields.iter().all(|field| &field.name != *name))
+    {
+        return Err(CoreError::QueryValidationError(format!(
+            "{} is not a field of compound unique {}",
+            name, field_name
+        )));
+    }
+
+    let selected = fields
+        .into_iter()
+        .map(|field| match values.get(&field.name) {
+            Some(value) => {
+                let value = PrismaValue::from_typed_value(value, field.type_identifier)?;
+                Ok((field, value))
+            }
+            None => Err(CoreError::QueryValidationError(format!(
+                "Missing value for field {} of compound unique {}",
+                field.name, field_name
+            ))),
+        })
+        .collect::<CoreResult<Vec<_>>>()?;
+
+    Ok(NodeSelector::compound(selected))
 }
 
 /// A function that derives a field given a field
@@ -13,8 +13,14 @@ pub enum CoreError {
     #[fail(display = "Query validation error: {}", _0)]
     QueryValidationError(String),
 
+    #[fail(display = "Query limit exceeded: {}", _0)]
+    QueryLimitExceeded(String),
+
     #[fail(display = "Unsupported feature: {}", _0)]
     UnsupportedFeatureError(String),
+
+    #[fail(display = "Invalid change feed message: {}", _0)]
+    ChangeFeedError(String),
 }
 
 impl From<ConnectorError> for CoreError {
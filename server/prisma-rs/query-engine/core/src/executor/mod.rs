@@ -12,8 +12,8 @@ pub use read::ReadQueryExecutor;
 pub use write::WriteQueryExecutor;
 
 use crate::{
-    BuilderExt, CoreError, CoreResult, Query, ReadQuery, ReadQueryResult, RecordQuery, SingleBuilder, WriteQuery,
-    WriteQueryResult,
+    ir, BuilderExt, CoreError, CoreResult, MutationEvent, Query, QueryLimits, ReadQuery, ReadQueryResult, RecordQuery,
+    SingleBuilder, Subscription, WriteQuery, WriteQueryResult,
 };
 use connector::{filter::NodeSelector, QueryArguments};
 use connector::{
@@ -33,6 +33,7 @@ use prisma_models::{
 pub struct Executor {
     pub read_exec: ReadQueryExecutor,
     pub write_exec: WriteQueryExecutor,
+    pub limits: QueryLimits,
 }
 
 type FoldResult = ConnectorResult<Vec<DatabaseMutactionResult>>;
@@ -41,7 +42,16 @@ impl Executor {
     /// Can be given a list of both ReadQueries and WriteQueries
     ///
     /// Will execute WriteQueries first, then all ReadQueries, while preserving order.
-    pub fn exec_all(&self, queries: Vec<Query>) -> CoreResult<Vec<ReadQueryResult>> {
+    pub fn exec_all(&self, mut queries: Vec<Query>) -> CoreResult<Vec<ReadQueryResult>> {
+        // Reject expensive queries before anything is executed
+        for query in queries.iter_mut() {
+            self.limits.check(query)?;
+
+            if let Query::Write(ref write) = query {
+                self.check_prefetch(write)?;
+            }
+        }
+
         // Give all queries to the pipeline module
         let mut pipeline = QueryPipeline::from(queries);
 
@@ -51,18 +61,26 @@ impl Executor {
         pipeline.store_prefetch(idx.into_iter().zip(results).collect());
 
         // Execute write queries and generate required read queries
-        let (mut idx, mut queries) = (vec![], vec![]);
+        let (mut idx, mut queries, mut batch_results) = (vec![], vec![], vec![]);
         for (index, write) in pipeline.get_writes() {
             let res = self.write_exec.execute(write.inner.clone())?;
 
+            // Batch writes don't need a read to produce their result
+            if let (Some(index), Some(result)) = (index, write.batch_result(&res)) {
+                batch_results.push((index, result));
+                continue;
+            }
+
             // Execute reads if they are required to be executed
-            if let (Some(index), Some(read)) = (index, write.generate_read(res)) {
+            // The read has been checked with the preview of the write
+            if let (Some(index), Some(mut read)) = (index, write.generate_read(res)) {
+                self.limits.apply_defaults(&mut read);
                 idx.push(index);
                 queries.push(read);
             }
         }
         let results = self.read_exec.execute(&queries)?;
-        pipeline.store_reads(idx.into_iter().zip(results.into_iter()).collect());
+        pipeline.store_reads(idx.into_iter().zip(results.into_iter()).chain(batch_results).collect());
 
         // Now execute all remaining reads
         let (idx, queries): (Vec<_>, Vec<_>) = pipeline.get_reads().into_iter().unzip();
@@ -72,4 +90,57 @@ impl Executor {
         // Consume pipeline into return value
         Ok(pipeline.consume())
     }
+
+    /// Rejects subscriptions whose payload exceeds the limits, before anyone subscribes
+    pub fn check_subscriptions(&self, subscriptions: &[Subscription]) -> CoreResult<()> {
+        subscriptions
+            .iter()
+            .filter_map(|subscription| subscription.preview_read())
+            .map(|mut read| self.limits.apply(&mut read))
+            .collect()
+    }
+
+    /// Renders the payload of a subscription for an event.
+    ///
+    /// Returns `None` if the event is filtered out by the subscription.
+    pub fn exec_subscription(
+        &self,
+        subscription: &Subscription,
+        event: &MutationEvent,
+    ) -> CoreResult<Option<ir::Response>> {
+        if !subscription.filter.matches(event) {
+            return Ok(None);
+        }
+
+        // The read has been checked by `check_subscriptions` when subscribing
+        let read = subscription.generate_read(event).map(|mut read| {
+            self.limits.apply_defaults(&mut read);
+            read
+        });
+
+        // The event already carries the scalars of the node, only relations and lists are read
+        let node = match (read, event.node.clone()) {
+            (Some(ReadQuery::RecordQuery(query)), Some(node)) => Some(self.read_exec.execute_for_node(&query, node)?),
+            _ => None,
+        };
+
+        Ok(Some(subscription.build_response(event, node)))
+    }
+
+    /// The prefetch of a `deleteMany` reads every node it deletes, they are
+    /// counted to check the limits before anything is deleted.
+    fn check_prefetch(&self, write: &WriteQuery) -> CoreResult<()> {
+        match (&write.inner, self.limits.max_nodes) {
+            (TopLevelDatabaseMutaction::DeleteNodes(ref delete), Some(_)) => {
+                let args = QueryArguments::from(delete.filter.clone());
+                let nodes = self
+                    .read_exec
+                    .data_resolver
+                    .count_by_model(Arc::clone(&delete.model), args)?;
+
+                self.limits.check_nodes(nodes as u64)
+            }
+            _ => Ok(()),
+        }
+    }
 }
@@ -27,25 +27,8 @@ impl ReadQueryExecutor {
                         .data_resolver
                         .get_node_by_where(&query.selector, &selected_fields)?;
 
-                    match scalars {
-                        Some(ref record) => {
-                            let model = Arc::clone(&query.selector.field.model());
-                            let ids = vec![record.get_id_value(model)?.clone()];
-                            let list_fields = selected_fields.scalar_lists();
-                            let lists = self.resolve_scalar_list_fields(ids.clone(), list_fields)?;
-                            let nested = self.execute_internal(&query.nested, ids)?;
-
-                            let result = SingleReadQueryResult {
-                                name: query.name.clone(),
-                                fields: query.fields.clone(),
-                                scalars,
-                                nested,
-                                selected_fields,
-                                lists,
-                            };
-                            results.push(ReadQueryResult::Single(result));
-                        }
-                        None => (),
+                    if let Some(record) = scalars {
+                        results.push(self.resolve_record(query, record, selected_fields)?);
                     }
                 }
                 ReadQuery::ManyRecordsQuery(query) => {
@@ -163,6 +146,35 @@ impl ReadQueryExecutor {
         Ok(results)
     }
 
+    /// Resolves a record query for a record that has already been read, e.g. the node of a
+    /// mutation event. Only its scalar lists and nested queries are read from the database.
+    pub fn execute_for_node(&self, query: &RecordQuery, record: SingleNode) -> CoreResult<ReadQueryResult> {
+        let selected_fields = Self::inject_required_fields(query.selected_fields.clone());
+        self.resolve_record(query, record, selected_fields)
+    }
+
+    fn resolve_record(
+        &self,
+        query: &RecordQuery,
+        record: SingleNode,
+        selected_fields: SelectedFields,
+    ) -> CoreResult<ReadQueryResult> {
+        let model = query.selector.model();
+        let ids = vec![record.get_id_value(model)?.clone()];
+        let list_fields = selected_fields.scalar_lists();
+        let lists = self.resolve_scalar_list_fields(ids.clone(), list_fields)?;
+        let nested = self.execute_internal(&query.nested, ids)?;
+
+        Ok(ReadQueryResult::Single(SingleReadQueryResult {
+            name: query.name.clone(),
+            fields: query.fields.clone(),
+            scalars: Some(record),
+            nested,
+            selected_fields,
+            lists,
+        }))
+    }
+
     fn resolve_scalar_list_fields(
         &self,
         record_ids: Vec<GraphqlId>,
@@ -1,15 +1,375 @@
-use connector::mutaction::{DatabaseMutactionResult, TopLevelDatabaseMutaction};
-use connector::{ConnectorResult, DatabaseMutactionExecutor};
+use crate::subscriptions::{ChangeFeedMessage, MutationEvent, MutationType, SubscriptionDispatcher};
+use connector::filter::{Filter, NodeSelector};
+use connector::mutaction::{DatabaseMutactionResult, Identifier, TopLevelDatabaseMutaction};
+use connector::{ChangeFeed, ConnectorResult, DataResolver, DatabaseMutactionExecutor};
+use prisma_models::{
+    DomainError, GraphqlId, ModelRef, PrismaArgs, PrismaListValue, PrismaValue, ScalarListOperation, SelectedFields,
+    SingleNode,
+};
 use std::sync::Arc;
 
 /// A small wrapper around running WriteQueries
 pub struct WriteQueryExecutor {
     pub db_name: String,
     pub write_executor: Arc<DatabaseMutactionExecutor + Send + Sync + 'static>,
+
+    /// Used to read the state of nodes before and after a write, if anyone subscribed to them
+    pub data_resolver: Arc<DataResolver + Send + Sync + 'static>,
+    pub dispatcher: Arc<SubscriptionDispatcher>,
+
+    /// If set, events go through the database to reach the subscribers of all engines
+    pub change_feed: Option<Arc<ChangeFeed + Send + Sync + 'static>>,
 }
 
 impl WriteQueryExecutor {
     pub fn execute(&self, mutaction: TopLevelDatabaseMutaction) -> ConnectorResult<DatabaseMutactionResult> {
-        self.write_executor.execute(self.db_name.clone(), mutaction)
+        let model = match Self::model(&mutaction) {
+            Some(ref model) if self.has_listeners(model)? => Arc::clone(model),
+            _ => return self.write_executor.execute(self.db_name.clone(), mutaction),
+        };
+
+        match mutaction {
+            TopLevelDatabaseMutaction::CreateNode(ref create) => {
+                let result = self.write_executor.execute(self.db_name.clone(), mutaction.clone())?;
+
+                self.publish(
+                    model,
+                    MutationType::Created,
+                    &result,
+                    &create.non_list_args,
+                    None,
+                    vec![],
+                )?;
+                Ok(result)
+            }
+            TopLevelDatabaseMutaction::UpdateNode(ref update) => {
+                let previous = self.find_node(&update.where_)?;
+                let updated_fields =
+                    Self::updated_fields(&update.non_list_args, &update.list_args, &update.list_operations);
+                let result = self.write_executor.execute(self.db_name.clone(), mutaction.clone())?;
+
+                self.publish(
+                    model,
+                    MutationType::Updated,
+                    &result,
+                    &update.non_list_args,
+                    previous,
+                    updated_fields,
+                )?;
+                Ok(result)
+            }
+            TopLevelDatabaseMutaction::UpsertNode(ref upsert) => {
+                let previous = self.find_node(&upsert.where_)?;
+                let result = self.write_executor.execute(self.db_name.clone(), mutaction.clone())?;
+
+                match previous {
+                    Some(_) => {
+                        let update = &upsert.update;
+                        let updated_fields =
+                            Self::updated_fields(&update.non_list_args, &update.list_args, &update.list_operations);
+
+                        self.publish(
+                            model,
+                            MutationType::Updated,
+                            &result,
+                            &update.non_list_args,
+                            previous,
+                            updated_fields,
+                        )?;
+                    }
+                    None => self.publish(
+                        model,
+                        MutationType::Created,
+                        &result,
+                        &upsert.create.non_list_args,
+                        None,
+                        vec![],
+                    )?,
+                };
+
+                Ok(result)
+            }
+            TopLevelDatabaseMutaction::DeleteNode(_) => {
+                let result = self.write_executor.execute(self.db_name.clone(), mutaction)?;
+
+                if let Identifier::Node(ref node) = result.identifier {
+                    let previous = Some(node.clone());
+                    self.publish(
+                        model,
+                        MutationType::Deleted,
+                        &result,
+                        &PrismaArgs::new(),
+                        previous,
+                        vec![],
+                    )?;
+                }
+
+                Ok(result)
+            }
+            TopLevelDatabaseMutaction::UpdateNodes(ref update) => {
+                let previous = self.find_nodes(Arc::clone(&model), update.filter.clone())?;
+                let updated_fields =
+                    Self::updated_fields(&update.non_list_args, &update.list_args, &update.list_operations);
+                let result = self.write_executor.execute(self.db_name.clone(), mutaction.clone())?;
+
+                for node in previous {
+                    self.publish(
+                        Arc::clone(&model),
+                        MutationType::Updated,
+                        &result,
+                        &update.non_list_args,
+                        Some(node),
+                        updated_fields.clone(),
+                    )?;
+                }
+
+                Ok(result)
+            }
+            TopLevelDatabaseMutaction::DeleteNodes(ref delete) => {
+                let previous = self.find_nodes(Arc::clone(&model), delete.filter.clone())?;
+                let result = self.write_executor.execute(self.db_name.clone(), mutaction.clone())?;
+                let args = PrismaArgs::new();
+
+                for node in previous {
+                    self.publish(
+                        Arc::clone(&model),
+                        MutationType::Deleted,
+                        &result,
+                        &args,
+                        Some(node),
+                        vec![],
+                    )?;
+                }
+
+                Ok(result)
+            }
+            TopLevelDatabaseMutaction::CreateNodes(_) | TopLevelDatabaseMutaction::ResetData(_) => {
+                self.write_executor.execute(self.db_name.clone(), mutaction)
+            }
+        }
+    }
+
+    /// Events are only emitted for top level mutactions, changes made by
+    /// nested mutactions are not published. Batch creates don't report the
+    /// ids of their records, so they are not published either.
+    fn model(mutaction: &TopLevelDatabaseMutaction) -> Option<ModelRef> {
+        match mutaction {
+            TopLevelDatabaseMutaction::CreateNode(ref node) => Some(Arc::clone(&node.model)),
+            TopLevelDatabaseMutaction::CreateNodes(_) => None,
+            TopLevelDatabaseMutaction::UpdateNode(ref node) => Some(node.where_.model()),
+            TopLevelDatabaseMutaction::UpsertNode(ref node) => Some(node.where_.model()),
+            TopLevelDatabaseMutaction::DeleteNode(ref node) => Some(node.where_.model()),
+            TopLevelDatabaseMutaction::UpdateNodes(ref nodes) => Some(Arc::clone(&nodes.model)),
+            TopLevelDatabaseMutaction::DeleteNodes(ref nodes) => Some(Arc::clone(&nodes.model)),
+            TopLevelDatabaseMutaction::ResetData(_) => None,
+        }
+    }
+
+    /// With a change feed the subscribers of other engines count as well
+    fn has_listeners(&self, model: &ModelRef) -> ConnectorResult<bool> {
+        if self.dispatcher.has_subscribers(&model.name) {
+            return Ok(true);
+        }
+
+        match self.change_feed {
+            Some(ref change_feed) => change_feed.has_listeners(&model.name),
+            None => Ok(false),
+        }
+    }
+
+    fn updated_fields(
+        non_list_args: &PrismaArgs,
+        list_args: &[(String, PrismaListValue)],
+        list_operations: &[(String, ScalarListOperation)],
+    ) -> Vec<String> {
+        non_list_args
+            .keys()
+            .chain(non_list_args.operations.keys())
+            .cloned()
+            .chain(list_args.iter().map(|(name, _)| name.clone()))
+            .chain(list_operations.iter().map(|(name, _)| name.clone()))
+            .collect()
+    }
+
+    /// Selects the node by its identifying fields as they are after the
+    /// write: written values first, then the values the node had before.
+    /// A generated id is only known from the result of a create.
+    fn selector(
+        model: &ModelRef,
+        id: &GraphqlId,
+        args: &PrismaArgs,
+        previous: Option<&SingleNode>,
+    ) -> ConnectorResult<NodeSelector> {
+        let id_field = model.fields().id();
+
+        let fields = model
+            .identifying_fields()?
+            .into_iter()
+            .map(|field| {
+                let value = args
+                    .get_field_value(&field.name)
+                    .cloned()
+                    .or_else(|| previous.and_then(|node| node.get_field_value(&field.name).ok().cloned()))
+                    .or_else(|| {
+                        if field.name == id_field.name {
+                            Some(PrismaValue::from(id))
+                        } else {
+                            None
+                        }
+                    })
+                    .ok_or_else(|| DomainError::FieldNotFound {
+                        name: field.name.clone(),
+                        model: model.name.clone(),
+                    })?;
+
+                Ok((field, value))
+            })
+            .collect::<ConnectorResult<Vec<_>>>()?;
+
+        Ok(NodeSelector::compound(fields))
+    }
+
+    fn find_node(&self, selector: &NodeSelector) -> ConnectorResult<Option<SingleNode>> {
+        let selected_fields = SelectedFields::from(selector.model());
+        self.data_resolver.get_node_by_where(selector, &selected_fields)
+    }
+
+    fn find_nodes(&self, model: ModelRef, filter: Filter) -> ConnectorResult<Vec<SingleNode>> {
+        let selected_fields = SelectedFields::from(Arc::clone(&model));
+        let nodes = self.data_resolver.get_nodes(model, filter.into(), &selected_fields)?;
+        let field_names = nodes.field_names;
+
+        Ok(nodes
+            .nodes
+            .into_iter()
+            .map(|node| SingleNode::new(node, field_names.clone()))
+            .collect())
+    }
+
+    /// `previous_values` is the node before the write, `None` for creates.
+    fn publish(
+        &self,
+        model: ModelRef,
+        mutation_type: MutationType,
+        result: &DatabaseMutactionResult,
+        args: &PrismaArgs,
+        previous_values: Option<SingleNode>,
+        updated_fields: Vec<String>,
+    ) -> ConnectorResult<()> {
+        let id = match previous_values {
+            Some(ref node) => node.get_id_value(Arc::clone(&model))?,
+            None => result.id().clone(),
+        };
+
+        let selector = Self::selector(&model, &id, args, previous_values.as_ref())?;
+
+        if let Some(ref change_feed) = self.change_feed {
+            let message = ChangeFeedMessage::new(
+                &model,
+                mutation_type,
+                &id,
+                &selector,
+                previous_values.as_ref(),
+                &updated_fields,
+            );
+
+            // The write is committed at this point, failing to publish must not fail the mutation
+            match message.to_payload() {
+                Ok(payload) => {
+                    if let Err(err) = change_feed.notify(&payload) {
+                        error!("Unable to publish mutation event: {}", err);
+                    }
+                }
+                Err(err) => error!("Unable to serialize mutation event: {}", err),
+            }
+
+            return Ok(());
+        }
+
+        let node = match mutation_type {
+            MutationType::Deleted => None,
+            _ => self.find_node(&selector)?,
+        };
+
+        self.dispatcher.publish(MutationEvent {
+            model,
+            mutation_type,
+            id,
+            selector,
+            result: result.clone(),
+            node,
+            previous_values,
+            updated_fields,
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use prisma_models::Node;
+
+    fn selector_values(selector: &NodeSelector) -> Vec<(String, PrismaValue)> {
+        selector
+            .fields
+            .iter()
+            .map(|(field, value)| (field.name.clone(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn created_nodes_are_selected_by_their_id() {
+        let internal_data_model = internal_data_model();
+        let model = user_model(&internal_data_model);
+        let mut args = PrismaArgs::new();
+        args.insert("name", PrismaValue::from("a"));
+
+        let selector = WriteQueryExecutor::selector(&model, &GraphqlId::from("1"), &args, None).unwrap();
+
+        assert_eq!(
+            selector_values(&selector),
+            vec![(String::from("id"), PrismaValue::from(GraphqlId::from("1")))]
+        );
+    }
+
+    #[test]
+    fn compound_ids_select_by_the_values_after_the_write() {
+        let internal_data_model = internal_data_model();
+        let model = internal_data_model.find_model("Membership").unwrap();
+        let previous = SingleNode::new(
+            Node::new(vec![
+                PrismaValue::from(GraphqlId::from("1")),
+                PrismaValue::from("alice"),
+                PrismaValue::from("admins"),
+            ]),
+            vec![String::from("id"), String::from("user"), String::from("team")],
+        );
+
+        let mut args = PrismaArgs::new();
+        args.insert("team", PrismaValue::from("owners"));
+
+        let selector = WriteQueryExecutor::selector(&model, &GraphqlId::from("1"), &args, Some(&previous)).unwrap();
+
+        assert!(selector.is_compound());
+        assert_eq!(
+            selector_values(&selector),
+            vec![
+                (String::from("user"), PrismaValue::from("alice")),
+                (String::from("team"), PrismaValue::from("owners")),
+            ]
+        );
+    }
+
+    #[test]
+    fn compound_ids_need_all_values() {
+        let internal_data_model = internal_data_model();
+        let model = internal_data_model.find_model("Membership").unwrap();
+        let mut args = PrismaArgs::new();
+        args.insert("user", PrismaValue::from("alice"));
+
+        assert!(WriteQueryExecutor::selector(&model, &GraphqlId::from("1"), &args, None).is_err());
     }
 }
@@ -1,7 +1,7 @@
 //! Process a set of records into an IR List
 
 use super::{maps::build_map, trim_records, Item, List, Map};
-use crate::{ManyReadQueryResults, ReadQueryResult};
+use crate::{CoreError, CoreResult, ManyReadQueryResults, ReadQueryResult};
 use prisma_models::{GraphqlId, PrismaValue};
 use std::{
     collections::{hash_map::IterMut, HashMap},
@@ -53,7 +53,7 @@ impl ParentsWithRecords {
     }
 }
 
-pub fn build_list(mut result: ManyReadQueryResults) -> List {
+pub fn build_list(mut result: ManyReadQueryResults) -> CoreResult<List> {
     let field_names = result.scalars.field_names;
 
     // First, move lists and nested out of result for separate processing.
@@ -65,65 +65,81 @@ pub fn build_list(mut result: ManyReadQueryResults) -> List {
     let mut nested_fields_to_groups: HashMap<String, ParentsWithRecords> = HashMap::new();
 
     // Group nested results by parent ids and move them into the grouped map.
-    nested.into_iter().for_each(|nested_result| match nested_result {
-        ReadQueryResult::Single(single) => {
-            if single.scalars.is_some() {
-                let parent_id = single
-                    .parent_id()
-                    .cloned()
-                    .expect("Parent ID needs to be present on nested results.");
-
-                if !nested_fields_to_groups.contains_key(&single.name) {
-                    nested_fields_to_groups.insert(single.name.clone(), ParentsWithRecords::Single(HashMap::new()));
-                }
+    for nested_result in nested.into_iter() {
+        match nested_result {
+            ReadQueryResult::Single(single) => {
+                if single.scalars.is_some() {
+                    let parent_id = single
+                        .parent_id()
+                        .cloned()
+                        .expect("Parent ID needs to be present on nested results.");
+
+                    if !nested_fields_to_groups.contains_key(&single.name) {
+                        nested_fields_to_groups.insert(single.name.clone(), ParentsWithRecords::Single(HashMap::new()));
+                    }
 
-                let parents_with_records = nested_fields_to_groups
-                    .get_mut(&single.name)
-                    .expect("Parents with records mapping must contain entries for all nested queries.");;
+                    let parents_with_records = nested_fields_to_groups
+                        .get_mut(&single.name)
+                        .expect("Parents with records mapping must contain entries for all nested queries.");
 
-                match build_map(single) {
-                    Some(m) => parents_with_records.insert(parent_id.clone(), vec![Item::Map(Some(parent_id), m)]),
-                    None => parents_with_records.insert(parent_id.clone(), vec![Item::Value(PrismaValue::Null)]),
-                };
-            }
-        }
-        ReadQueryResult::Many(many) => {
-            if !nested_fields_to_groups.contains_key(&many.name) {
-                nested_fields_to_groups.insert(many.name.clone(), ParentsWithRecords::Many(HashMap::new()));
+                    match build_map(single)? {
+                        Some(m) => parents_with_records.insert(parent_id.clone(), vec![Item::Map(Some(parent_id), m)]),
+                        None => parents_with_records.insert(parent_id.clone(), vec![Item::Value(PrismaValue::Null)]),
+                    };
+                }
             }
+            ReadQueryResult::Many(many) => {
+                if !nested_fields_to_groups.contains_key(&many.name) {
+                    nested_fields_to_groups.insert(many.name.clone(), ParentsWithRecords::Many(HashMap::new()));
+                }
 
-            let parents_with_records = nested_fields_to_groups
-                .get_mut(&many.name)
-                .expect("Parents with records mapping must contain entries for all nested queries.");
-
-            let query_args = many.query_arguments.clone();
-            let nested_build = build_list(many);
-
-            nested_build.into_iter().for_each(|item| match item {
-                Item::Map(parent_opt, i) => {
-                    let parent_id = parent_opt
-                        .clone()
-                        .expect("Expected parent ID to be present on nested query results.");
-
-                    if !parents_with_records.contains_key(&parent_id) {
-                        parents_with_records.insert(parent_id.clone(), vec![]);
+                let parents_with_records = nested_fields_to_groups
+                    .get_mut(&many.name)
+                    .expect("Parents with records mapping must contain entries for all nested queries.");
+
+                let many_name = many.name.clone();
+                let query_args = many.query_arguments.clone();
+                let nested_build = build_list(many)?;
+
+                for item in nested_build.into_iter() {
+                    match item {
+                        Item::Map(parent_opt, i) => {
+                            let parent_id = parent_opt
+                                .clone()
+                                .expect("Expected parent ID to be present on nested query results.");
+
+                            if !parents_with_records.contains_key(&parent_id) {
+                                parents_with_records.insert(parent_id.clone(), vec![]);
+                            }
+
+                            let records_for_parent = parents_with_records
+                                .get_mut(&parent_id)
+                                .expect("Expected records to parent mapping to contain entries for all nodes.");
+
+                            records_for_parent.push(Item::Map(parent_opt, i));
+                        }
+                        item => {
+                            return Err(CoreError::UnsupportedFeatureError(format!(
+                                "Expected nested records of `{}` to be maps, got {:?}",
+                                many_name, item
+                            )))
+                        }
                     }
-
-                    let records_for_parent = parents_with_records
-                        .get_mut(&parent_id)
-                        .expect("Expected records to parent mapping to contain entries for all nodes.");
-
-                    records_for_parent.push(Item::Map(parent_opt, i));
                 }
-                _ => unreachable!(),
-            });
 
-            // Post process results for this query
-            parents_with_records.iter_mut().for_each(|(_, v)| {
-                trim_records(v, &query_args);
-            });
+                // Post process results for this query
+                parents_with_records.iter_mut().for_each(|(_, v)| {
+                    trim_records(v, &query_args);
+                });
+            }
+            ReadQueryResult::Count(count) => {
+                return Err(CoreError::UnsupportedFeatureError(format!(
+                    "Counts can't be selected on a record (`{}`)",
+                    count.name
+                )))
+            }
         }
-    });
+    }
 
     // { scalar list field name -> { record id -> values } }
     let mut lists_to_groups: HashMap<String, HashMap<GraphqlId, Vec<PrismaValue>>> = HashMap::new();
@@ -150,7 +166,7 @@ pub fn build_list(mut result: ManyReadQueryResults) -> List {
     let final_field_order = result.fields.clone();
 
     // There is always at least one scalar selected (id), making scalars the perfect entry point.
-    result
+    let list = result
         .scalars
         .nodes
         .into_iter()
@@ -212,5 +228,7 @@ pub fn build_list(mut result: ManyReadQueryResults) -> List {
                 }),
             )
         })
-        .collect()
+        .collect();
+
+    Ok(list)
 }
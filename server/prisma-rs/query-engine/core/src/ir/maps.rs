@@ -1,10 +1,10 @@
 //! Process a record into an IR Map
 
 use super::{lists::build_list, trim_records, Item, Map};
-use crate::{ReadQueryResult, SingleReadQueryResult};
+use crate::{CoreError, CoreResult, ReadQueryResult, SingleReadQueryResult};
 use prisma_models::PrismaValue;
 
-pub fn build_map(result: SingleReadQueryResult) -> Option<Map> {
+pub fn build_map(result: SingleReadQueryResult) -> CoreResult<Option<Map>> {
     // Build selected fields first
     let mut outer = match &result.scalars {
         Some(single) => single
@@ -15,36 +15,40 @@ pub fn build_map(result: SingleReadQueryResult) -> Option<Map> {
                 map.insert(name.clone(), Item::Value(val.clone()));
                 map
             }),
-        None => return None,
+        None => return Ok(None),
     };
 
     // Parent id for nested queries has to be the id of this record.
     let parent_id = result.find_id().cloned();
 
     // Then add nested selected fields
-    outer = result.nested.into_iter().fold(outer, |mut map, query| {
+    for query in result.nested.into_iter() {
         match query {
             ReadQueryResult::Single(nested) => {
                 let nested_name = nested.name.clone();
-                match build_map(nested) {
-                    Some(m) => map.insert(nested_name, Item::Map(parent_id.clone(), m)),
-                    None => map.insert(nested_name, Item::Value(PrismaValue::Null)),
+                match build_map(nested)? {
+                    Some(m) => outer.insert(nested_name, Item::Map(parent_id.clone(), m)),
+                    None => outer.insert(nested_name, Item::Value(PrismaValue::Null)),
                 }
             }
             ReadQueryResult::Many(nested) => {
                 let query_name = nested.name.clone();
                 let query_args = nested.query_arguments.clone();
-                let mut nested_result = build_list(nested);
+                let mut nested_result = build_list(nested)?;
 
                 // Trim excess data from the processed result set
                 trim_records(&mut nested_result, &query_args);
 
-                map.insert(query_name, Item::List(nested_result))
+                outer.insert(query_name, Item::List(nested_result))
+            }
+            ReadQueryResult::Count(nested) => {
+                return Err(CoreError::UnsupportedFeatureError(format!(
+                    "Counts can't be selected on a record (`{}`)",
+                    nested.name
+                )))
             }
         };
-
-        map
-    });
+    }
 
     // Insert list data into the map
     result.lists.into_iter().for_each(|(field_name, mut list_values)| {
@@ -57,11 +61,11 @@ pub fn build_map(result: SingleReadQueryResult) -> Option<Map> {
 
     // Re-order fields to be in-line with what the query specified
     // This also removes implicit fields
-    Some(result.fields.iter().fold(Map::new(), |mut map, field| {
+    Ok(Some(result.fields.iter().fold(Map::new(), |mut map, field| {
         map.insert(
             field.clone(),
             outer.remove(field).expect("[Map]: Missing required field"),
         );
         map
-    }))
+    })))
 }
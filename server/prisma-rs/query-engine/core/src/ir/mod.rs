@@ -67,18 +67,28 @@ impl Builder {
                 ReadQueryResult::Single(query) => {
                     let query_name = query.name.clone();
                     match maps::build_map(query) {
-                        Some(m) => Response::Data(query_name, Item::Map(None, m)),
-                        None => Response::Data(query_name, Item::Value(PrismaValue::Null)),
+                        Ok(Some(m)) => Response::Data(query_name, Item::Map(None, m)),
+                        Ok(None) => Response::Data(query_name, Item::Value(PrismaValue::Null)),
+                        Err(err) => Response::Error(err.to_string()),
                     }
                 }
                 ReadQueryResult::Many(query) => {
                     let query_name = query.name.clone();
                     let query_args = query.query_arguments.clone();
-                    let mut result = lists::build_list(query);
+                    match lists::build_list(query) {
+                        Ok(mut result) => {
+                            // Trim excess data from the processed result set
+                            trim_records(&mut result, &query_args);
+                            Response::Data(query_name, Item::List(result))
+                        }
+                        Err(err) => Response::Error(err.to_string()),
+                    }
+                }
+                ReadQueryResult::Count(query) => {
+                    let mut map = Map::new();
+                    map.insert("count".into(), Item::Value(PrismaValue::Int(query.count as i64)));
 
-                    // Trim excess data from the processed result set
-                    trim_records(&mut result, &query_args);
-                    Response::Data(query_name, Item::List(result))
+                    Response::Data(query.name, Item::Map(None, map))
                 }
             });
             vec
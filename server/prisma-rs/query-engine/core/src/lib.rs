@@ -6,18 +6,25 @@ extern crate log;
 mod builders;
 mod error;
 mod query_ast;
+mod query_limits;
 mod query_results;
 mod executor;
 mod schema;
+mod subscriptions;
+
+#[cfg(test)]
+mod test_utils;
 
 pub mod ir;
 
 pub use builders::*;
 pub use error::*;
 pub use query_ast::*;
+pub use query_limits::*;
 pub use query_results::*;
 pub use executor::*;
 pub use schema::*;
+pub use subscriptions::*;
 
 pub type CoreResult<T> = Result<T, CoreError>;
 
@@ -0,0 +1,222 @@
+//! Limits on the cost of read queries, checked before anything is executed
+
+use crate::{CoreError, CoreResult, Query, ReadQuery};
+use connector::{mutaction::TopLevelDatabaseMutaction, QueryArguments};
+
+/// All limits are optional, an unset limit is not enforced
+#[derive(Debug, Default, Clone)]
+pub struct QueryLimits {
+    /// Maximum nesting of selections, the root field counts as 1
+    pub max_depth: Option<usize>,
+
+    /// Maximum number of nodes a query could return, estimated from `first` and `last`
+    pub max_nodes: Option<u64>,
+
+    /// Page size for lists that are requested without `first` or `last`
+    pub default_page_size: Option<u32>,
+
+    /// Maximum value of `first` and `last`
+    pub max_page_size: Option<u32>,
+}
+
+impl QueryLimits {
+    /// Checks a query against the limits, applying the default page size to its reads
+    pub fn check(&self, query: &mut Query) -> CoreResult<()> {
+        match query {
+            Query::Read(ref mut read) => self.apply(read),
+
+            // Mutations are checked with the read that returns their result. The
+            // prefetch of a `deleteMany` reads all matched nodes, the executor
+            // counts them with `check_nodes`.
+            Query::Write(ref write) => match (&write.inner, write.generate_prefetch()) {
+                (TopLevelDatabaseMutaction::DeleteNodes(_), _) => Ok(()),
+                (_, Some(mut read)) => self.apply(&mut read),
+                (_, None) => match write.preview_read() {
+                    Some(mut read) => self.apply(&mut read),
+                    None => Ok(()),
+                },
+            },
+        }
+    }
+
+    /// Applies the default page size to a read and rejects it if it exceeds a limit
+    pub fn apply(&self, query: &mut ReadQuery) -> CoreResult<()> {
+        let mut nodes = 0;
+        self.visit(query, 1, 1, &mut nodes)?;
+        self.check_nodes(nodes)
+    }
+
+    /// Applies the default page size to a read without checking it. Only for
+    /// reads that have been checked before, e.g. with the preview of a write.
+    pub fn apply_defaults(&self, query: &mut ReadQuery) {
+        let (args, nested) = match query {
+            ReadQuery::RecordQuery(q) => (None, &mut q.nested),
+            ReadQuery::ManyRecordsQuery(q) => (Some(&mut q.args), &mut q.nested),
+            ReadQuery::RelatedRecordQuery(q) => (None, &mut q.nested),
+            ReadQuery::ManyRelatedRecordsQuery(q) => (Some(&mut q.args), &mut q.nested),
+        };
+
+        if let Some(args) = args {
+            self.default_page_size(args);
+        }
+
+        nested.iter_mut().for_each(|query| self.apply_defaults(query));
+    }
+
+    /// Rejects reading more than the maximum number of nodes
+    pub fn check_nodes(&self, nodes: u64) -> CoreResult<()> {
+        match self.max_nodes {
+            Some(max) if nodes > max => Err(CoreError::QueryLimitExceeded(format!(
+                "The query could return up to {} nodes, the maximum is {}. Use `first` or `last` to limit lists.",
+                nodes, max
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn visit(&self, query: &mut ReadQuery, depth: usize, parents: u64, nodes: &mut u64) -> CoreResult<()> {
+        if let Some(max) = self.max_depth {
+            if depth > max {
+                return Err(CoreError::QueryLimitExceeded(format!(
+                    "The query has a depth of at least {}, the maximum is {}.",
+                    depth, max
+                )));
+            }
+        }
+
+        let (name, args, nested) = match query {
+            ReadQuery::RecordQuery(q) => (&q.name, None, &mut q.nested),
+            ReadQuery::ManyRecordsQuery(q) => (&q.name, Some(&mut q.args), &mut q.nested),
+            ReadQuery::RelatedRecordQuery(q) => (&q.name, None, &mut q.nested),
+            ReadQuery::ManyRelatedRecordsQuery(q) => (&q.name, Some(&mut q.args), &mut q.nested),
+        };
+
+        let count = match args {
+            Some(args) => parents.saturating_mul(self.page_size(name, args)?),
+            None => parents,
+        };
+
+        *nodes = nodes.saturating_add(count);
+
+        nested
+            .iter_mut()
+            .map(|query| self.visit(query, depth + 1, count, nodes))
+            .collect()
+    }
+
+    /// The number of nodes a list returns at most, after applying the page size limits
+    fn page_size(&self, name: &str, args: &mut QueryArguments) -> CoreResult<u64> {
+        self.default_page_size(args);
+
+        if let Some(max) = self.max_page_size {
+            if let Some(size) = args.first.into_iter().chain(args.last).find(|size| *size > max) {
+                return Err(CoreError::QueryLimitExceeded(format!(
+                    "`{}` requests {} nodes, the maximum page size is {}.",
+                    name, size, max
+                )));
+            }
+        }
+
+        let (start, end) = args.window_limits();
+        Ok(u64::from(end.saturating_sub(start)))
+    }
+
+    fn default_page_size(&self, args: &mut QueryArguments) {
+        if args.first.is_none() && args.last.is_none() {
+            args.first = self.default_page_size.or(self.max_page_size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::*, RootBuilder};
+
+    fn check(limits: &QueryLimits, query: &str) -> CoreResult<Query> {
+        let internal_data_model = internal_data_model();
+        let mut queries = RootBuilder {
+            query: graphql_parser::parse_query(query).unwrap(),
+            internal_data_model,
+            operation_name: None,
+        }
+        .build()?;
+
+        let mut query = queries.pop().unwrap();
+        limits.check(&mut query)?;
+
+        Ok(query)
+    }
+
+    fn assert_exceeded(result: CoreResult<Query>) {
+        match result {
+            Err(CoreError::QueryLimitExceeded(_)) => (),
+            result => panic!("Expected a query limit to be exceeded, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn queries_deeper_than_the_maximum_are_rejected() {
+        let limits = QueryLimits {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+
+        assert!(check(&limits, "{ users { posts { id } } }").is_ok());
+        assert_exceeded(check(&limits, "{ users { posts { author { id } } } }"));
+    }
+
+    #[test]
+    fn pages_larger_than_the_maximum_are_rejected() {
+        let limits = QueryLimits {
+            max_page_size: Some(100),
+            ..Default::default()
+        };
+
+        assert!(check(&limits, "{ users(first: 100) { id } }").is_ok());
+        assert_exceeded(check(&limits, "{ users(last: 101) { id } }"));
+        assert_exceeded(check(&limits, "{ users { posts(first: 500) { id } } }"));
+    }
+
+    #[test]
+    fn nested_lists_multiply_the_node_count() {
+        let limits = QueryLimits {
+            max_nodes: Some(110),
+            ..Default::default()
+        };
+
+        // 10 users and 10 posts for each of them
+        assert!(check(&limits, "{ users(first: 10) { posts(first: 10) { id } } }").is_ok());
+        assert_exceeded(check(&limits, "{ users(first: 10) { posts(first: 11) { id } } }"));
+    }
+
+    #[test]
+    fn lists_without_a_page_size_get_the_default() {
+        let limits = QueryLimits {
+            default_page_size: Some(20),
+            max_nodes: Some(1000),
+            ..Default::default()
+        };
+
+        match check(&limits, "{ users { posts { id } } }").unwrap() {
+            Query::Read(ReadQuery::ManyRecordsQuery(users)) => {
+                assert_eq!(users.args.first, Some(20));
+
+                match users.nested[0] {
+                    ReadQuery::ManyRelatedRecordsQuery(ref posts) => assert_eq!(posts.args.first, Some(20)),
+                    ref query => panic!("Expected the posts to be a list, got {:?}", query),
+                }
+            }
+            query => panic!("Expected a list of users, got {:?}", query),
+        }
+
+        // 20 users with 20 posts each
+        assert_exceeded(check(
+            &QueryLimits {
+                max_nodes: Some(100),
+                ..limits
+            },
+            "{ users { posts { id } } }",
+        ));
+    }
+}
@@ -5,6 +5,7 @@ use prisma_models::{GraphqlId, ManyNodes, PrismaValue, SelectedFields, SelectedS
 pub enum ReadQueryResult {
     Single(SingleReadQueryResult),
     Many(ManyReadQueryResults),
+    Count(CountQueryResult),
 }
 
 impl ReadQueryResult {
@@ -12,10 +13,18 @@ impl ReadQueryResult {
         match self {
             ReadQueryResult::Single(s) => s.name.clone(),
             ReadQueryResult::Many(m) => m.name.clone(),
+            ReadQueryResult::Count(c) => c.name.clone(),
         }
     }
 }
 
+/// The number of records a batch mutation wrote, returned without reading them
+#[derive(Debug)]
+pub struct CountQueryResult {
+    pub name: String,
+    pub count: usize,
+}
+
 #[derive(Debug)]
 pub struct SingleReadQueryResult {
     pub name: String,
@@ -0,0 +1,241 @@
+use super::{MutationEvent, MutationType, SubscriptionDispatcher};
+use crate::{CoreError, CoreResult};
+use connector::{
+    filter::NodeSelector,
+    mutaction::{DatabaseMutactionResult, DatabaseMutactionResultType, Identifier},
+    ChangeFeed, DataResolver,
+};
+use prisma_models::{GraphqlId, InternalDataModelRef, ModelRef, Node, PrismaValue, SelectedFields, SingleNode};
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, thread, time::Duration};
+
+/// Postgres rejects `NOTIFY` payloads of 8000 bytes or more
+const MAX_PAYLOAD_SIZE: usize = 7999;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// A `MutationEvent` as it is sent over the change feed.
+///
+/// The node itself is not part of the message, receivers read it from the
+/// database as the write is already committed.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeFeedMessage {
+    pub model: String,
+    pub mutation_type: String,
+    pub id: GraphqlId,
+
+    /// Field names and values of the `NodeSelector` of the event
+    pub selector: Vec<(String, PrismaValue)>,
+    pub updated_fields: Vec<String>,
+    pub previous_values: Option<Vec<(String, PrismaValue)>>,
+}
+
+impl ChangeFeedMessage {
+    pub fn new(
+        model: &ModelRef,
+        mutation_type: MutationType,
+        id: &GraphqlId,
+        selector: &NodeSelector,
+        previous_values: Option<&SingleNode>,
+        updated_fields: &[String],
+    ) -> Self {
+        let previous_values = previous_values.map(|node| {
+            node.field_names
+                .iter()
+                .cloned()
+                .zip(node.node.values.iter().cloned())
+                .collect()
+        });
+
+        Self {
+            model: model.name.clone(),
+            mutation_type: mutation_type.as_str().into(),
+            id: id.clone(),
+            selector: selector
+                .fields
+                .iter()
+                .map(|(field, value)| (field.name.clone(), value.clone()))
+                .collect(),
+            updated_fields: updated_fields.to_vec(),
+            previous_values,
+        }
+    }
+
+    /// Serializes the message, dropping the previous values if the payload would get too large
+    pub fn to_payload(mut self) -> serde_json::Result<String> {
+        let payload = serde_json::to_string(&self)?;
+
+        if payload.len() <= MAX_PAYLOAD_SIZE {
+            Ok(payload)
+        } else {
+            warn!(
+                "Change feed payload for {} {} too large, dropping previous values.",
+                self.model, self.mutation_type
+            );
+
+            self.previous_values = None;
+            serde_json::to_string(&self)
+        }
+    }
+}
+
+/// Receives the events published by all engines on the database
+/// and hands them to the local dispatcher.
+pub struct ChangeFeedListener {
+    pub change_feed: Arc<ChangeFeed + Send + Sync + 'static>,
+    pub data_resolver: Arc<DataResolver + Send + Sync + 'static>,
+    pub dispatcher: Arc<SubscriptionDispatcher>,
+    pub internal_data_model: InternalDataModelRef,
+}
+
+impl ChangeFeedListener {
+    /// Blocks forever, reconnecting whenever the listening connection fails
+    pub fn run(&self) {
+        loop {
+            let models = || self.dispatcher.models();
+            let result = self.change_feed.listen(&models, &mut |payload| {
+                if let Err(err) = self.dispatch(&payload) {
+                    error!("Unable to dispatch change feed message: {}", err);
+                }
+            });
+
+            if let Err(err) = result {
+                error!("Change feed connection failed: {}", err);
+            }
+
+            thread::sleep(RECONNECT_DELAY);
+        }
+    }
+
+    fn dispatch(&self, payload: &str) -> CoreResult<()> {
+        let message: ChangeFeedMessage =
+            serde_json::from_str(payload).map_err(|e| CoreError::ChangeFeedError(format!("{}", e)))?;
+
+        let model = self.internal_data_model.find_model(&message.model)?;
+
+        if !self.dispatcher.has_subscribers(&model.name) {
+            return Ok(());
+        }
+
+        let mutation_type = MutationType::from_str(&message.mutation_type)
+            .ok_or_else(|| CoreError::ChangeFeedError(format!("Unknown mutation type `{}`", message.mutation_type)))?;
+
+        if message.selector.is_empty() {
+            return Err(CoreError::ChangeFeedError(String::from("Message without a selector")));
+        }
+
+        let selector = message
+            .selector
+            .into_iter()
+            .map(|(name, value)| Ok((model.fields().find_from_scalar(&name)?, value)))
+            .collect::<CoreResult<Vec<_>>>()
+            .map(NodeSelector::compound)?;
+
+        let node = match mutation_type {
+            MutationType::Deleted => None,
+            _ => {
+                let selected_fields = SelectedFields::from(Arc::clone(&model));
+                self.data_resolver.get_node_by_where(&selector, &selected_fields)?
+            }
+        };
+
+        let previous_values = message.previous_values.map(|pairs| {
+            let (field_names, values): (Vec<String>, Vec<PrismaValue>) = pairs.into_iter().unzip();
+            SingleNode::new(Node::new(values), field_names)
+        });
+
+        let typ = match mutation_type {
+            MutationType::Created => DatabaseMutactionResultType::Create,
+            MutationType::Updated => DatabaseMutactionResultType::Update,
+            MutationType::Deleted => DatabaseMutactionResultType::Delete,
+        };
+
+        self.dispatcher.publish(MutationEvent {
+            model,
+            mutation_type,
+            id: message.id.clone(),
+            selector,
+            result: DatabaseMutactionResult {
+                identifier: Identifier::Id(message.id),
+                typ,
+            },
+            node,
+            previous_values,
+            updated_fields: message.updated_fields,
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn membership_selector(internal_data_model: &InternalDataModelRef) -> NodeSelector {
+        let model = internal_data_model.find_model("Membership").unwrap();
+        let fields = model.identifying_fields().unwrap();
+
+        NodeSelector::compound(
+            fields
+                .into_iter()
+                .zip(vec![PrismaValue::from("alice"), PrismaValue::from("admins")])
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn messages_carry_compound_selectors() {
+        let internal_data_model = internal_data_model();
+        let model = internal_data_model.find_model("Membership").unwrap();
+        let selector = membership_selector(&internal_data_model);
+
+        let message = ChangeFeedMessage::new(
+            &model,
+            MutationType::Created,
+            &GraphqlId::from("1"),
+            &selector,
+            None,
+            &[],
+        );
+        let message: ChangeFeedMessage = serde_json::from_str(&message.to_payload().unwrap()).unwrap();
+
+        assert_eq!(message.model, "Membership");
+        assert_eq!(message.mutation_type, "CREATED");
+        assert_eq!(
+            message.selector,
+            vec![
+                (String::from("user"), PrismaValue::from("alice")),
+                (String::from("team"), PrismaValue::from("admins")),
+            ]
+        );
+    }
+
+    #[test]
+    fn large_previous_values_are_dropped_from_the_payload() {
+        let internal_data_model = internal_data_model();
+        let model = user_model(&internal_data_model);
+        let selector = NodeSelector::new(model.fields().id(), GraphqlId::from("1"));
+        let previous = user(vec![
+            ("id", PrismaValue::from("1")),
+            ("name", PrismaValue::from("a".repeat(MAX_PAYLOAD_SIZE))),
+        ]);
+
+        let message = ChangeFeedMessage::new(
+            &model,
+            MutationType::Deleted,
+            &GraphqlId::from("1"),
+            &selector,
+            Some(&previous),
+            &[],
+        );
+        let payload = message.to_payload().unwrap();
+        let message: ChangeFeedMessage = serde_json::from_str(&payload).unwrap();
+
+        assert!(payload.len() <= MAX_PAYLOAD_SIZE);
+        assert!(message.previous_values.is_none());
+        assert_eq!(message.selector.len(), 1);
+    }
+}
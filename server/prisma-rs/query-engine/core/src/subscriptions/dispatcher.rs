@@ -0,0 +1,153 @@
+use super::MutationEvent;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+};
+
+/// Identifies a registered subscriber, used to unsubscribe again
+pub type SubscriberId = usize;
+
+/// A callback that is invoked for every event on the subscribed model
+pub type SubscriberFn = Box<Fn(Arc<MutationEvent>) + Send + Sync + 'static>;
+
+struct Subscriber {
+    model_name: String,
+    callback: SubscriberFn,
+}
+
+/// Fans out mutation events to all in-process subscribers of a model.
+///
+/// Callbacks are invoked on the thread that publishes the event,
+/// so they should only hand the event over (e.g. to an actor) and return.
+#[derive(Default)]
+pub struct SubscriptionDispatcher {
+    next_id: AtomicUsize,
+    subscribers: RwLock<HashMap<SubscriberId, Subscriber>>,
+}
+
+impl SubscriptionDispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe<S: Into<String>>(&self, model_name: S, callback: SubscriberFn) -> SubscriberId {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let subscriber = Subscriber {
+            model_name: model_name.into(),
+            callback,
+        };
+
+        self.subscribers.write().unwrap().insert(id, subscriber);
+        id
+    }
+
+    pub fn unsubscribe(&self, id: SubscriberId) {
+        self.subscribers.write().unwrap().remove(&id);
+    }
+
+    /// Used by the write path to skip collecting event data nobody is listening for
+    pub fn has_subscribers(&self, model_name: &str) -> bool {
+        self.subscribers
+            .read()
+            .unwrap()
+            .values()
+            .any(|s| s.model_name == model_name)
+    }
+
+    /// The models with at least one subscriber
+    pub fn models(&self) -> Vec<String> {
+        let subscribers = self.subscribers.read().unwrap();
+        let mut models: Vec<String> = subscribers.values().map(|s| s.model_name.clone()).collect();
+
+        models.sort();
+        models.dedup();
+        models
+    }
+
+    pub fn publish(&self, event: MutationEvent) {
+        let event = Arc::new(event);
+        let subscribers = self.subscribers.read().unwrap();
+
+        subscribers
+            .values()
+            .filter(|s| s.model_name == event.model.name)
+            .for_each(|s| (s.callback)(Arc::clone(&event)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{subscriptions::MutationType, test_utils::*};
+    use connector::{
+        filter::NodeSelector,
+        mutaction::{DatabaseMutactionResult, DatabaseMutactionResultType, Identifier},
+    };
+    use prisma_models::{GraphqlId, ModelRef};
+    use std::sync::Mutex;
+
+    fn created(model: ModelRef) -> MutationEvent {
+        let id = GraphqlId::from("1");
+
+        MutationEvent {
+            selector: NodeSelector::new(model.fields().id(), id.clone()),
+            model,
+            mutation_type: MutationType::Created,
+            id: id.clone(),
+            result: DatabaseMutactionResult {
+                identifier: Identifier::Id(id),
+                typ: DatabaseMutactionResultType::Create,
+            },
+            node: None,
+            previous_values: None,
+            updated_fields: vec![],
+        }
+    }
+
+    fn recorder(received: &Arc<Mutex<Vec<String>>>, name: &'static str) -> SubscriberFn {
+        let received = Arc::clone(received);
+        Box::new(move |event| received.lock().unwrap().push(format!("{} {}", name, event.model.name)))
+    }
+
+    #[test]
+    fn events_only_reach_the_subscribers_of_their_model() {
+        let internal_data_model = internal_data_model();
+        let dispatcher = SubscriptionDispatcher::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        dispatcher.subscribe("User", recorder(&received, "first"));
+        dispatcher.subscribe("Post", recorder(&received, "second"));
+        dispatcher.publish(created(user_model(&internal_data_model)));
+
+        assert_eq!(*received.lock().unwrap(), vec![String::from("first User")]);
+    }
+
+    #[test]
+    fn unsubscribed_callbacks_are_not_invoked() {
+        let internal_data_model = internal_data_model();
+        let dispatcher = SubscriptionDispatcher::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let id = dispatcher.subscribe("User", recorder(&received, "first"));
+        dispatcher.unsubscribe(id);
+        dispatcher.publish(created(user_model(&internal_data_model)));
+
+        assert!(received.lock().unwrap().is_empty());
+        assert!(!dispatcher.has_subscribers("User"));
+    }
+
+    #[test]
+    fn models_are_listed_once() {
+        let dispatcher = SubscriptionDispatcher::new();
+
+        dispatcher.subscribe("User", Box::new(|_| ()));
+        dispatcher.subscribe("Post", Box::new(|_| ()));
+        dispatcher.subscribe("User", Box::new(|_| ()));
+
+        assert!(dispatcher.has_subscribers("Post"));
+        assert_eq!(dispatcher.models(), vec![String::from("Post"), String::from("User")]);
+    }
+}
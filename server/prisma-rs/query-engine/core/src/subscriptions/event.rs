@@ -0,0 +1,57 @@
+use connector::{filter::NodeSelector, mutaction::DatabaseMutactionResult};
+use prisma_models::{GraphqlId, ModelRef, SingleNode};
+
+/// The kind of change a `MutationEvent` describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MutationType {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl MutationType {
+    /// The GraphQL enum value (`CREATED`, `UPDATED`, `DELETED`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MutationType::Created => "CREATED",
+            MutationType::Updated => "UPDATED",
+            MutationType::Deleted => "DELETED",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "CREATED" => Some(MutationType::Created),
+            "UPDATED" => Some(MutationType::Updated),
+            "DELETED" => Some(MutationType::Deleted),
+            _ => None,
+        }
+    }
+}
+
+/// A change to a single node, emitted after a write has succeeded
+#[derive(Debug, Clone)]
+pub struct MutationEvent {
+    /// The model of the node that was changed
+    pub model: ModelRef,
+
+    pub mutation_type: MutationType,
+
+    /// The id of the changed node
+    pub id: GraphqlId,
+
+    /// Reads the node after the change, by the fields identifying it
+    pub selector: NodeSelector,
+
+    /// The result returned by the connector for the mutaction
+    pub result: DatabaseMutactionResult,
+
+    /// Scalar values of the node after the change, `None` for deletes
+    pub node: Option<SingleNode>,
+
+    /// Scalar values of the node before the change, `None` for creates
+    pub previous_values: Option<SingleNode>,
+
+    /// Names of the fields that were written, only populated for updates
+    pub updated_fields: Vec<String>,
+}
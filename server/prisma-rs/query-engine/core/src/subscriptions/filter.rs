@@ -0,0 +1,393 @@
+use super::{MutationEvent, MutationType};
+use connector::filter::{Filter, JsonCondition, JsonPathCondition, QueryMode, ScalarCondition, ScalarFilter};
+use prisma_models::{Decimal, GraphqlId, PrismaValue, SingleNode};
+use std::{cmp::Ordering, sync::Arc};
+
+/// The `where` argument of a subscription, evaluated in-process against events
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionFilter {
+    /// `mutation_in`, `None` matches all mutation types
+    pub mutation_in: Option<Vec<MutationType>>,
+
+    /// `updatedFields_contains`
+    pub updated_fields_contains: Option<String>,
+
+    /// `node`, matched against the node after the change (before, for deletes)
+    pub node: Option<Filter>,
+}
+
+impl SubscriptionFilter {
+    pub fn matches(&self, event: &MutationEvent) -> bool {
+        if let Some(ref types) = self.mutation_in {
+            if !types.contains(&event.mutation_type) {
+                return false;
+            }
+        }
+
+        if let Some(ref field) = self.updated_fields_contains {
+            if event.mutation_type != MutationType::Updated || !event.updated_fields.contains(field) {
+                return false;
+            }
+        }
+
+        match self.node {
+            Some(ref filter) => {
+                let node = match event.mutation_type {
+                    MutationType::Deleted => event.previous_values.as_ref(),
+                    _ => event.node.as_ref(),
+                };
+
+                node.map(|node| node_matches(filter, node)).unwrap_or(false)
+            }
+            None => true,
+        }
+    }
+
+    /// Only filters on the scalars of a node can be evaluated without the database.
+    pub fn supports(filter: &Filter) -> bool {
+        match filter {
+            Filter::And(filters) | Filter::Or(filters) | Filter::Not(filters) => {
+                filters.iter().all(|f| Self::supports(f))
+            }
+            Filter::Scalar(_) | Filter::BoolFilter(_) => true,
+            _ => false,
+        }
+    }
+}
+
+fn node_matches(filter: &Filter, node: &SingleNode) -> bool {
+    match filter {
+        Filter::And(filters) => filters.iter().all(|f| node_matches(f, node)),
+        Filter::Or(filters) => filters.iter().any(|f| node_matches(f, node)),
+        Filter::Not(filters) => !filters.iter().any(|f| node_matches(f, node)),
+        Filter::Scalar(sf) => scalar_matches(sf, node),
+        Filter::BoolFilter(b) => *b,
+        _ => false,
+    }
+}
+
+fn scalar_matches(filter: &ScalarFilter, node: &SingleNode) -> bool {
+    let value = match node.get_field_value(&filter.field.name) {
+        Ok(value) => value,
+        Err(_) => return false,
+    };
+
+    if filter.mode == QueryMode::Insensitive {
+        let folded = ScalarFilter {
+            field: Arc::clone(&filter.field),
+            condition: fold_condition(&filter.condition),
+            mode: QueryMode::Default,
+        };
+
+        return scalar_matches_value(&folded, &fold_case(value));
+    }
+
+    scalar_matches_value(filter, value)
+}
+
+fn scalar_matches_value(filter: &ScalarFilter, value: &PrismaValue) -> bool {
+    match filter.condition {
+        ScalarCondition::Equals(ref other) => values_equal(value, other),
+        ScalarCondition::NotEquals(ref other) => !values_equal(value, other),
+        ScalarCondition::Contains(ref other) => string_op(value, other, |a, b| a.contains(b)),
+        ScalarCondition::NotContains(ref other) => !string_op(value, other, |a, b| a.contains(b)),
+        ScalarCondition::StartsWith(ref other) => string_op(value, other, |a, b| a.starts_with(b)),
+        ScalarCondition::NotStartsWith(ref other) => !string_op(value, other, |a, b| a.starts_with(b)),
+        ScalarCondition::EndsWith(ref other) => string_op(value, other, |a, b| a.ends_with(b)),
+        ScalarCondition::NotEndsWith(ref other) => !string_op(value, other, |a, b| a.ends_with(b)),
+        ScalarCondition::LessThan(ref other) => compare(value, other) == Some(Ordering::Less),
+        ScalarCondition::LessThanOrEquals(ref other) => match compare(value, other) {
+            Some(Ordering::Less) | Some(Ordering::Equal) => true,
+            _ => false,
+        },
+        ScalarCondition::GreaterThan(ref other) => compare(value, other) == Some(Ordering::Greater),
+        ScalarCondition::GreaterThanOrEquals(ref other) => match compare(value, other) {
+            Some(Ordering::Greater) | Some(Ordering::Equal) => true,
+            _ => false,
+        },
+        ScalarCondition::In(Some(ref values)) => values.iter().any(|v| values_equal(value, v)),
+        ScalarCondition::In(None) => value == &PrismaValue::Null,
+        ScalarCondition::NotIn(Some(ref values)) => !values.iter().any(|v| values_equal(value, v)),
+        ScalarCondition::NotIn(None) => value != &PrismaValue::Null,
+        ScalarCondition::Search(ref query) => string_op(value, query, search_matches),
+        ScalarCondition::JsonPath(ref json) => json_matches(value, json),
+    }
+}
+
+/// Lowercases string values, everything else is compared as is
+fn fold_case(value: &PrismaValue) -> PrismaValue {
+    match value {
+        PrismaValue::String(s) => PrismaValue::String(s.to_lowercase()),
+        value => value.clone(),
+    }
+}
+
+fn fold_condition(condition: &ScalarCondition) -> ScalarCondition {
+    let fold_all = |values: &Option<Vec<PrismaValue>>| -> Option<Vec<PrismaValue>> {
+        values.as_ref().map(|v| v.iter().map(fold_case).collect())
+    };
+
+    match condition {
+        ScalarCondition::Equals(v) => ScalarCondition::Equals(fold_case(v)),
+        ScalarCondition::NotEquals(v) => ScalarCondition::NotEquals(fold_case(v)),
+        ScalarCondition::Contains(v) => ScalarCondition::Contains(fold_case(v)),
+        ScalarCondition::NotContains(v) => ScalarCondition::NotContains(fold_case(v)),
+        ScalarCondition::StartsWith(v) => ScalarCondition::StartsWith(fold_case(v)),
+        ScalarCondition::NotStartsWith(v) => ScalarCondition::NotStartsWith(fold_case(v)),
+        ScalarCondition::EndsWith(v) => ScalarCondition::EndsWith(fold_case(v)),
+        ScalarCondition::NotEndsWith(v) => ScalarCondition::NotEndsWith(fold_case(v)),
+        ScalarCondition::In(values) => ScalarCondition::In(fold_all(values)),
+        ScalarCondition::NotIn(values) => ScalarCondition::NotIn(fold_all(values)),
+        condition => condition.clone(),
+    }
+}
+
+fn as_str(value: &PrismaValue) -> Option<&str> {
+    match value {
+        PrismaValue::String(s) | PrismaValue::Enum(s) => Some(s.as_str()),
+        PrismaValue::GraphqlId(GraphqlId::String(s)) => Some(s.as_str()),
+        _ => None,
+    }
+}
+
+fn string_op<F>(value: &PrismaValue, other: &PrismaValue, op: F) -> bool
+where
+    F: Fn(&str, &str) -> bool,
+{
+    match (as_str(value), as_str(other)) {
+        (Some(a), Some(b)) => op(a, b),
+        _ => false,
+    }
+}
+
+/// An approximation of the database search without stemming: every word of
+/// the query has to appear in the text, ignoring case.
+fn search_matches(text: &str, query: &str) -> bool {
+    let words = |s: &str| -> Vec<String> {
+        s.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+
+    let text_words = words(text);
+    words(query).iter().all(|w| text_words.contains(w))
+}
+
+fn json_matches(value: &PrismaValue, json: &JsonPathCondition) -> bool {
+    use serde_json::Value as Json;
+
+    let root = match value {
+        PrismaValue::Json(root) => root,
+        _ => return false,
+    };
+
+    let found = json.path.iter().try_fold(root, |acc, key| match acc {
+        Json::Object(map) => map.get(key),
+        Json::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    });
+
+    let json_compare = |a: &Json, b: &Json| match (a, b) {
+        (Json::Number(a), Json::Number(b)) => a.as_f64().and_then(|a| b.as_f64().and_then(|b| a.partial_cmp(&b))),
+        (Json::String(a), Json::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+
+    match (found, &json.condition) {
+        (None, _) => false,
+        (Some(_), JsonCondition::Exists) => true,
+        (Some(found), JsonCondition::Equals(other)) => found == other,
+        (Some(Json::String(s)), JsonCondition::StringContains(other)) => s.contains(other.as_str()),
+        (Some(Json::Array(items)), JsonCondition::ArrayContains(other)) => items.contains(other),
+        (Some(found), JsonCondition::LessThan(other)) => json_compare(found, other) == Some(Ordering::Less),
+        (Some(found), JsonCondition::GreaterThan(other)) => json_compare(found, other) == Some(Ordering::Greater),
+        _ => false,
+    }
+}
+
+fn values_equal(value: &PrismaValue, other: &PrismaValue) -> bool {
+    match compare(value, other) {
+        Some(ordering) => ordering == Ordering::Equal,
+        None => value == other,
+    }
+}
+
+/// Values coming from the query and the database don't always share a variant,
+/// e.g. ids are read as `GraphqlId` but arrive as `String` or `Int` in filters.
+fn compare(value: &PrismaValue, other: &PrismaValue) -> Option<Ordering> {
+    match (value, other) {
+        (PrismaValue::Int(a), PrismaValue::Int(b)) => Some(a.cmp(b)),
+        (PrismaValue::BigInt(a), PrismaValue::BigInt(b))
+        | (PrismaValue::BigInt(a), PrismaValue::Int(b))
+        | (PrismaValue::Int(a), PrismaValue::BigInt(b)) => Some(a.cmp(b)),
+        (PrismaValue::Decimal(a), PrismaValue::Decimal(b)) => Some(a.cmp(b)),
+        (PrismaValue::Decimal(a), PrismaValue::Int(b)) => Some(a.cmp(&Decimal::from(*b))),
+        (PrismaValue::Int(a), PrismaValue::Decimal(b)) => Some(Decimal::from(*a).cmp(b)),
+        (PrismaValue::Float(a), PrismaValue::Float(b)) => a.partial_cmp(b),
+        (PrismaValue::Int(a), PrismaValue::Float(b)) => (*a as f64).partial_cmp(b),
+        (PrismaValue::Float(a), PrismaValue::Int(b)) => a.partial_cmp(&(*b as f64)),
+        (PrismaValue::DateTime(a), PrismaValue::DateTime(b)) => Some(a.cmp(b)),
+        (PrismaValue::Boolean(a), PrismaValue::Boolean(b)) => Some(a.cmp(b)),
+        (PrismaValue::GraphqlId(GraphqlId::Int(a)), PrismaValue::Int(b)) => Some((*a as i64).cmp(b)),
+        (PrismaValue::Int(a), PrismaValue::GraphqlId(GraphqlId::Int(b))) => Some(a.cmp(&(*b as i64))),
+        _ => match (as_str(value), as_str(other)) {
+            (Some(a), Some(b)) => Some(a.cmp(b)),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use connector::{
+        filter::NodeSelector,
+        mutaction::{DatabaseMutactionResult, DatabaseMutactionResultType, Identifier},
+        JsonCompare, ScalarCompare,
+    };
+    use prisma_models::ModelRef;
+    use serde_json::json;
+
+    fn event(model: ModelRef, mutation_type: MutationType, node: SingleNode, updated_fields: &[&str]) -> MutationEvent {
+        let id = GraphqlId::from("1");
+        let (node, previous_values) = match mutation_type {
+            MutationType::Created => (Some(node), None),
+            MutationType::Updated => (Some(node), Some(user(vec![("id", PrismaValue::from("1"))]))),
+            MutationType::Deleted => (None, Some(node)),
+        };
+
+        MutationEvent {
+            selector: NodeSelector::new(model.fields().id(), id.clone()),
+            model,
+            mutation_type,
+            id: id.clone(),
+            result: DatabaseMutactionResult {
+                identifier: Identifier::Id(id),
+                typ: DatabaseMutactionResultType::Create,
+            },
+            node,
+            previous_values,
+            updated_fields: updated_fields.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    fn named(name: &str) -> SingleNode {
+        user(vec![("id", PrismaValue::from("1")), ("name", PrismaValue::from(name))])
+    }
+
+    #[test]
+    fn mutation_in_only_matches_the_given_types() {
+        let internal_data_model = internal_data_model();
+        let model = user_model(&internal_data_model);
+
+        let filter = SubscriptionFilter {
+            mutation_in: Some(vec![MutationType::Created, MutationType::Deleted]),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&event(Arc::clone(&model), MutationType::Created, named("a"), &[])));
+        assert!(filter.matches(&event(Arc::clone(&model), MutationType::Deleted, named("a"), &[])));
+        assert!(!filter.matches(&event(model, MutationType::Updated, named("a"), &["name"])));
+    }
+
+    #[test]
+    fn updated_fields_contains_only_matches_updates_of_the_field() {
+        let internal_data_model = internal_data_model();
+        let model = user_model(&internal_data_model);
+
+        let filter = SubscriptionFilter {
+            updated_fields_contains: Some(String::from("name")),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&event(
+            Arc::clone(&model),
+            MutationType::Updated,
+            named("a"),
+            &["age", "name"]
+        )));
+        assert!(!filter.matches(&event(Arc::clone(&model), MutationType::Updated, named("a"), &["age"])));
+        assert!(!filter.matches(&event(model, MutationType::Created, named("a"), &[])));
+    }
+
+    #[test]
+    fn node_filters_match_the_previous_values_of_deletes() {
+        let internal_data_model = internal_data_model();
+        let model = user_model(&internal_data_model);
+        let name = model.fields().find_from_scalar("name").unwrap();
+
+        let filter = SubscriptionFilter {
+            node: Some(name.equals("a")),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&event(Arc::clone(&model), MutationType::Deleted, named("a"), &[])));
+        assert!(!filter.matches(&event(Arc::clone(&model), MutationType::Deleted, named("b"), &[])));
+        assert!(filter.matches(&event(model, MutationType::Created, named("a"), &[])));
+    }
+
+    #[test]
+    fn node_filters_compare_like_the_database() {
+        let internal_data_model = internal_data_model();
+        let model = user_model(&internal_data_model);
+        let fields = model.fields();
+        let name = fields.find_from_scalar("name").unwrap();
+        let age = fields.find_from_scalar("age").unwrap();
+        let settings = fields.find_from_scalar("settings").unwrap();
+
+        let node = user(vec![
+            ("id", PrismaValue::from("1")),
+            ("name", PrismaValue::from("Alice")),
+            ("age", PrismaValue::Int(42)),
+            ("settings", PrismaValue::Json(json!({"theme": {"color": "dark blue"}}))),
+        ]);
+
+        let matches = |filter: Filter| {
+            let subscription_filter = SubscriptionFilter {
+                node: Some(filter),
+                ..Default::default()
+            };
+
+            subscription_filter.matches(&event(Arc::clone(&model), MutationType::Created, node.clone(), &[]))
+        };
+
+        let insensitive = |condition: ScalarCondition| {
+            Filter::from(ScalarFilter {
+                field: Arc::clone(&name),
+                condition,
+                mode: QueryMode::Insensitive,
+            })
+        };
+
+        assert!(matches(name.starts_with("Al")));
+        assert!(!matches(name.starts_with("al")));
+        assert!(matches(insensitive(ScalarCondition::StartsWith(PrismaValue::from(
+            "al"
+        )))));
+        assert!(matches(age.greater_than(Decimal::from(41))));
+        assert!(!matches(age.is_in(Some(vec![1, 2]))));
+        assert!(matches(Filter::or(vec![age.equals(1), name.equals("Alice")])));
+        assert!(matches(settings.json_path(
+            vec![String::from("theme"), String::from("color")],
+            JsonCondition::StringContains(String::from("blue"))
+        )));
+    }
+
+    #[test]
+    fn only_scalar_filters_are_supported() {
+        let internal_data_model = internal_data_model();
+        let model = user_model(&internal_data_model);
+        let name = model.fields().find_from_scalar("name").unwrap();
+
+        assert!(SubscriptionFilter::supports(&Filter::and(vec![
+            name.equals("a"),
+            Filter::not(vec![name.contains("b")]),
+        ])));
+        assert!(!SubscriptionFilter::supports(&Filter::or(vec![
+            name.equals("a"),
+            Filter::NodeSubscription,
+        ])));
+    }
+}
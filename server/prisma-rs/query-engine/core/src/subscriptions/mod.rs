@@ -0,0 +1,19 @@
+//! Mutation events and their in-process delivery to subscribers
+//!
+//! The `WriteQueryExecutor` publishes a `MutationEvent` for every node changed
+//! by a top-level mutation. Subscribers register a callback per model on the
+//! `SubscriptionDispatcher` and decide with a `SubscriptionFilter` whether
+//! an event is relevant for them.
+//!
+//! If the connector supports a `ChangeFeed`, events are published through the
+//! database instead and dispatched by a `ChangeFeedListener` on every engine.
+
+mod change_feed;
+mod dispatcher;
+mod event;
+mod filter;
+
+pub use change_feed::*;
+pub use dispatcher::*;
+pub use event::*;
+pub use filter::*;
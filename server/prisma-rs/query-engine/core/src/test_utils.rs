@@ -0,0 +1,120 @@
+//! Fixtures shared by the unit tests of the core
+
+use prisma_models::{InternalDataModelRef, InternalDataModelTemplate, ModelRef, Node, PrismaValue, SingleNode};
+use serde_json::{json, Value};
+
+/// A data model with the models
+/// - `User`: `id: ID!`, `name: String!`, `age: Int`, `settings: Json`, `createdAt: DateTime`, `posts: [Post]`
+/// - `Post`: `id: ID!`, `title: String!`, `tags: [String]`, `author: User`
+/// - `Membership`: `id: ID!`, `user: String!`, `team: String!`, with `@@id([user, team])`
+pub fn internal_data_model() -> InternalDataModelRef {
+    let template: InternalDataModelTemplate = serde_json::from_value(json!({
+        "models": [
+            {
+                "name": "User",
+                "stableIdentifier": "User",
+                "isEmbedded": false,
+                "fields": [
+                    scalar_field("id", "GraphQLID", true),
+                    scalar_field("name", "String", true),
+                    scalar_field("age", "Int", false),
+                    scalar_field("settings", "Json", false),
+                    scalar_field("createdAt", "DateTime", false),
+                    relation_field("posts", "A", true),
+                ],
+            },
+            {
+                "name": "Post",
+                "stableIdentifier": "Post",
+                "isEmbedded": false,
+                "fields": [
+                    scalar_field("id", "GraphQLID", true),
+                    scalar_field("title", "String", true),
+                    list_field("tags", "String"),
+                    relation_field("author", "B", false),
+                ],
+            },
+            {
+                "name": "Membership",
+                "stableIdentifier": "Membership",
+                "isEmbedded": false,
+                "compoundUniques": [["user", "team"]],
+                "idFields": ["user", "team"],
+                "fields": [
+                    scalar_field("id", "GraphQLID", true),
+                    scalar_field("user", "String", true),
+                    scalar_field("team", "String", true),
+                ],
+            },
+        ],
+        "relations": [{
+            "name": "UserPosts",
+            "modelAId": "User",
+            "modelBId": "Post",
+            "modelAOnDelete": "SET_NULL",
+            "modelBOnDelete": "SET_NULL",
+        }],
+        "enums": [],
+    }))
+    .unwrap();
+
+    template.build(String::from("test"))
+}
+
+/// The model has to be used while the data model is alive, as it only holds a weak reference.
+pub fn user_model(internal_data_model: &InternalDataModelRef) -> ModelRef {
+    internal_data_model.find_model("User").unwrap()
+}
+
+/// A `User` node with the given values, the other fields are null.
+pub fn user(values: Vec<(&str, PrismaValue)>) -> SingleNode {
+    let field_names = vec!["id", "name", "age", "settings"];
+    let node_values = field_names
+        .iter()
+        .map(|name| {
+            values
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.clone())
+                .unwrap_or(PrismaValue::Null)
+        })
+        .collect();
+
+    SingleNode::new(
+        Node::new(node_values),
+        field_names.into_iter().map(String::from).collect(),
+    )
+}
+
+fn scalar_field(name: &str, type_identifier: &str, is_required: bool) -> Value {
+    json!({
+        "name": name,
+        "typeIdentifier": type_identifier,
+        "isRequired": is_required,
+        "isList": false,
+        "isUnique": name == "id",
+        "isHidden": false,
+        "isAutoGenerated": false,
+    })
+}
+
+fn list_field(name: &str, type_identifier: &str) -> Value {
+    let mut field = scalar_field(name, type_identifier, false);
+    field["isList"] = json!(true);
+    field
+}
+
+/// A field of the relation between `User` and `Post`
+fn relation_field(name: &str, side: &str, is_list: bool) -> Value {
+    json!({
+        "name": name,
+        "typeIdentifier": "Relation",
+        "isRequired": false,
+        "isList": is_list,
+        "isUnique": false,
+        "isHidden": false,
+        "isAutoGenerated": false,
+        "relationName": "UserPosts",
+        "relationSide": side,
+    })
+}
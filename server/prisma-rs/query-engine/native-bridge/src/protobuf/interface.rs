@@ -90,7 +90,7 @@ impl ExternalInterface for ProtoBufInterface {
 
             let value: PrismaValue = input.value.into();
             let field = model.fields().find_from_scalar(&input.field_name)?;
-            let node_selector = NodeSelector { field, value };
+            let node_selector = NodeSelector::new(field, value);
 
             let query_result = self.data_resolver.get_node_by_where(&node_selector, &selected_fields)?;
 
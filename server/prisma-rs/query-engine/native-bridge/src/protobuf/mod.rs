@@ -125,6 +125,7 @@ impl From<ValueContainer> for PrismaValue {
             vc::PrismaValue::Int(v) => PrismaValue::Int(v as i64),
             vc::PrismaValue::Relation(v) => PrismaValue::Relation(v as usize),
             vc::PrismaValue::Null(_) => PrismaValue::Null,
+            vc::PrismaValue::Bytes(v) => PrismaValue::Bytes(v),
             vc::PrismaValue::Uuid(v) => PrismaValue::Uuid(Uuid::parse_str(&v).unwrap()), // You must die if you didn't send uuid
             vc::PrismaValue::GraphqlId(v) => PrismaValue::GraphqlId(v.into()),
             vc::PrismaValue::List(_) => {
@@ -172,6 +173,9 @@ impl From<PrismaValue> for ValueContainer {
             PrismaValue::Enum(v) => vc::PrismaValue::Enum(v),
             PrismaValue::Json(ref v) => vc::PrismaValue::Json(serde_json::to_string(v).unwrap()),
             PrismaValue::Int(v) => vc::PrismaValue::Int(v as i32),
+            PrismaValue::BigInt(v) => vc::PrismaValue::String(v.to_string()),
+            PrismaValue::Decimal(v) => vc::PrismaValue::String(v.to_string()),
+            PrismaValue::Bytes(v) => vc::PrismaValue::Bytes(v),
             PrismaValue::Relation(v) => vc::PrismaValue::Relation(v as i64),
             PrismaValue::Null => vc::PrismaValue::Null(true),
             PrismaValue::Uuid(v) => vc::PrismaValue::Uuid(v.to_hyphenated().to_string()),
@@ -66,6 +66,8 @@ pub fn convert_nested_mutactions(
             .into_iter()
             .map(|m| convert_nested_connect(m, Arc::clone(&project)))
             .collect(),
+        // Not part of the protocol yet.
+        connect_or_creates: Vec::new(),
         disconnects: m
             .disconnects
             .into_iter()
@@ -113,7 +115,9 @@ pub fn convert_update(m: crate::protobuf::prisma::UpdateNode, project: ProjectRe
         where_: convert_node_select(m.where_, Arc::clone(&project)),
         non_list_args: convert_prisma_args(m.non_list_args),
         list_args: convert_list_args(m.list_args),
+        list_operations: convert_list_operations(m.list_operations),
         nested_mutactions: convert_nested_mutactions(m.nested, Arc::clone(&project)),
+        expected_version: None,
     }
 }
 
@@ -124,6 +128,7 @@ pub fn convert_nested_update(m: crate::protobuf::prisma::NestedUpdateNode, proje
         where_: m.where_.map(|w| convert_node_select(w, Arc::clone(&project))),
         non_list_args: convert_prisma_args(m.non_list_args),
         list_args: convert_list_args(m.list_args),
+        list_operations: convert_list_operations(m.list_operations),
         nested_mutactions: convert_nested_mutactions(m.nested, Arc::clone(&project)),
     }
 }
@@ -135,6 +140,7 @@ pub fn convert_update_nodes(m: crate::protobuf::prisma::UpdateNodes, project: Pr
         filter: m.filter.into_filter(model),
         non_list_args: convert_prisma_args(m.non_list_args),
         list_args: convert_list_args(m.list_args),
+        list_operations: convert_list_operations(m.list_operations),
     };
     TopLevelDatabaseMutaction::UpdateNodes(update_nodes)
 }
@@ -149,6 +155,7 @@ pub fn convert_nested_update_nodes(
         filter: m.filter.map(|f| f.into_filter(relation_field.related_model())),
         non_list_args: convert_prisma_args(m.non_list_args),
         list_args: convert_list_args(m.list_args),
+        list_operations: convert_list_operations(m.list_operations),
     }
 }
 
@@ -174,6 +181,7 @@ pub fn convert_nested_upsert(m: crate::protobuf::prisma::NestedUpsertNode, proje
 pub fn convert_delete(m: crate::protobuf::prisma::DeleteNode, project: ProjectRef) -> TopLevelDatabaseMutaction {
     let delete_node = DeleteNode {
         where_: convert_node_select(m.where_, project),
+        expected_version: None,
     };
     TopLevelDatabaseMutaction::DeleteNode(delete_node)
 }
@@ -267,14 +275,24 @@ pub fn convert_node_select(selector: crate::protobuf::prisma::NodeSelector, proj
     let model = project.internal_data_model().find_model(&selector.model_name).unwrap();
     let field = model.fields().find_from_scalar(&selector.field_name).unwrap();
     let value: PrismaValue = selector.value.into();
-    NodeSelector { field, value }
+    NodeSelector::new(field, value)
 }
 
 pub fn convert_prisma_args(proto: crate::protobuf::prisma::PrismaArgs) -> PrismaArgs {
+    use crate::protobuf::prisma::numeric_operation::Operation;
+
     let mut result = PrismaArgs::default();
     for arg in proto.args {
         result.insert(arg.key, arg.value);
     }
+    for op in proto.operations {
+        let operation = match op.operation.unwrap() {
+            Operation::Increment(v) => NumericOperation::Increment(v.into()),
+            Operation::Decrement(v) => NumericOperation::Decrement(v.into()),
+            Operation::Multiply(v) => NumericOperation::Multiply(v.into()),
+        };
+        result.insert_operation(op.key, operation);
+    }
     result
 }
 
@@ -288,6 +306,29 @@ pub fn convert_list_args(proto: crate::protobuf::prisma::PrismaArgs) -> Vec<(Str
     result
 }
 
+pub fn convert_list_operations(
+    proto: Vec<crate::protobuf::prisma::ScalarListOperation>,
+) -> Vec<(String, ScalarListOperation)> {
+    use crate::protobuf::prisma::scalar_list_operation::Operation;
+
+    let values = |list: crate::protobuf::prisma::PrismaListValue| -> Vec<PrismaValue> {
+        list.values.into_iter().map(PrismaValue::from).collect()
+    };
+
+    proto
+        .into_iter()
+        .map(|op| {
+            let operation = match op.operation.unwrap() {
+                Operation::Push(list) => ScalarListOperation::Push(values(list)),
+                Operation::Unshift(list) => ScalarListOperation::Unshift(values(list)),
+                Operation::Remove(list) => ScalarListOperation::Remove(values(list)),
+                Operation::Unset(_) => ScalarListOperation::Unset,
+            };
+            (op.key, operation)
+        })
+        .collect()
+}
+
 pub fn find_relation_field(project: ProjectRef, model: String, field: String) -> Arc<RelationField> {
     project
         .internal_data_model()
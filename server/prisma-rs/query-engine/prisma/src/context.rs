@@ -1,7 +1,8 @@
 use crate::{data_model, exec_loader, PrismaResult};
-use core::{Executor, SchemaBuilder};
+use core::{ChangeFeedListener, Executor, SchemaBuilder};
 use prisma_common::config::{self, PrismaConfig};
 use prisma_models::InternalDataModelRef;
+use std::{sync::Arc, thread};
 
 #[derive(DebugStub)]
 pub struct PrismaContext {
@@ -26,6 +27,18 @@ impl PrismaContext {
         let internal_data_model = data_model::load(db_name)?;
         // let _ = SchemaBuilder::build(internal_data_model.clone());
 
+        // Events of all engines on the database reach our subscribers through the change feed
+        if let Some(ref change_feed) = executor.write_exec.change_feed {
+            let listener = ChangeFeedListener {
+                change_feed: Arc::clone(change_feed),
+                data_resolver: Arc::clone(&executor.read_exec.data_resolver),
+                dispatcher: Arc::clone(&executor.write_exec.dispatcher),
+                internal_data_model: internal_data_model.clone(),
+            };
+
+            thread::spawn(move || listener.run());
+        }
+
         Ok(Self {
             config,
             internal_data_model,
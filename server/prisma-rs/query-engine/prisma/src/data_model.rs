@@ -33,7 +33,38 @@ impl Validatable for InternalDataModelRef {
 /// Loads and builds the internal data model from the data model JSON.
 pub fn load(db_name: String) -> PrismaResult<InternalDataModelRef> {
     let data_model_json = load_string()?;
-    Ok(serde_json::from_str::<InternalDataModelTemplate>(&data_model_json)?.build(db_name))
+    let mut template = serde_json::from_str::<InternalDataModelTemplate>(&data_model_json)?;
+
+    if let Ok(sdl) = load_sdl_string() {
+        apply_model_directives(&mut template, &sdl);
+    }
+
+    Ok(template.build(db_name))
+}
+
+/// Model directives only the datamodel knows about, `@softDelete`, `@@id` and
+/// `@@unique`, are not part of the data model JSON. They're taken from the
+/// SDL, if it is in the datamodel format.
+fn apply_model_directives(template: &mut InternalDataModelTemplate, sdl: &str) {
+    let schema = match datamodel::parser::parse(sdl) {
+        Ok(ast) => match datamodel::Validator::new().validate(&ast) {
+            Ok(schema) => schema,
+            Err(_) => return,
+        },
+        Err(_) => return,
+    };
+
+    for model in template.models.iter_mut() {
+        if let Some(dml_model) = schema.find_model(&model.name) {
+            model.is_soft_delete = dml_model.is_soft_delete;
+            model.compound_uniques = dml_model.unique_fields.clone();
+
+            if !dml_model.id_fields.is_empty() {
+                model.compound_uniques.push(dml_model.id_fields.clone());
+                model.id_fields = dml_model.id_fields.clone();
+            }
+        }
+    }
 }
 
 /// Attempts to load the config as unparsed JSON string.
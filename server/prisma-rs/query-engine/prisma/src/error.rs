@@ -24,6 +24,13 @@ pub enum PrismaError {
 
     #[fail(display = "{}", _0)]
     IOError(Error),
+
+    /// The messages are part of the APQ protocol, clients match on them
+    #[fail(display = "PersistedQueryNotFound")]
+    PersistedQueryNotFound,
+
+    #[fail(display = "PersistedQueryNotSupported")]
+    PersistedQueryNotSupported,
 }
 
 impl From<CoreError> for PrismaError {
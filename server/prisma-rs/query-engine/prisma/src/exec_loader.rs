@@ -1,4 +1,5 @@
-use core::{Executor, ReadQueryExecutor, WriteQueryExecutor};
+use connector::ChangeFeed;
+use core::{Executor, QueryLimits, ReadQueryExecutor, SubscriptionDispatcher, WriteQueryExecutor};
 use prisma_common::config::{ConnectionLimit, FileConfig, PrismaConfig, PrismaDatabase};
 use std::convert::TryFrom;
 use std::sync::Arc;
@@ -7,14 +8,23 @@ use std::sync::Arc;
 use sql_connector::{PostgreSql, SqlDatabase, Sqlite, Transactional};
 
 pub fn load(config: &PrismaConfig) -> Executor {
-    match config.databases.get("default") {
+    let mut executor = match config.databases.get("default") {
         #[cfg(feature = "sql")]
         Some(PrismaDatabase::File(ref config)) if config.connector == "sqlite-native" => sqlite(config),
 
         #[cfg(feature = "sql")]
         Some(config) if config.connector() == "postgres-native" => postgres(config),
         _ => panic!("Database connector is not supported. Supported"),
-    }
+    };
+
+    executor.limits = QueryLimits {
+        max_depth: config.max_query_depth,
+        max_nodes: config.max_query_nodes,
+        default_page_size: config.default_page_size,
+        max_page_size: config.max_page_size,
+    };
+
+    executor
 }
 
 #[cfg(feature = "sql")]
@@ -30,30 +40,46 @@ fn sqlite(config: &FileConfig) -> Executor {
     let wat = SqlDatabase::new(sqlite);
 
     // sql_executor(db_name.clone(), Arc::clone(&arc), arc)
-    sql_executor(db_name.clone(), wat)
+    sql_executor(db_name.clone(), Arc::new(wat), None)
 }
 
 #[cfg(feature = "sql")]
 fn postgres(config: &PrismaDatabase) -> Executor {
     let postgres = PostgreSql::try_from(config).unwrap();
-    let connector = SqlDatabase::new(postgres);
+    let connector = Arc::new(SqlDatabase::new(postgres));
+
+    let change_feed: Option<Arc<ChangeFeed + Send + Sync + 'static>> = if config.change_feed() {
+        Some(connector.clone())
+    } else {
+        None
+    };
 
-    sql_executor("".into(), connector)
+    sql_executor("".into(), connector, change_feed)
 }
 
 #[cfg(feature = "sql")]
-fn sql_executor<T>(db_name: String, connector: SqlDatabase<T>) -> Executor
+fn sql_executor<T>(
+    db_name: String,
+    arc: Arc<SqlDatabase<T>>,
+    change_feed: Option<Arc<ChangeFeed + Send + Sync + 'static>>,
+) -> Executor
 where
     T: Transactional + Send + Sync + 'static,
 {
-    let arc = Arc::new(connector);
     let read_exec: ReadQueryExecutor = ReadQueryExecutor {
         data_resolver: arc.clone(),
     };
     let write_exec: WriteQueryExecutor = WriteQueryExecutor {
         db_name: db_name,
-        write_executor: arc,
+        write_executor: arc.clone(),
+        data_resolver: arc,
+        dispatcher: Arc::new(SubscriptionDispatcher::new()),
+        change_feed,
     };
 
-    Executor { read_exec, write_exec }
+    Executor {
+        read_exec,
+        write_exec,
+        limits: QueryLimits::default(),
+    }
 }
@@ -13,9 +13,10 @@ mod error;
 mod exec_loader;
 mod req_handlers;
 mod serializer;
+mod subscriptions;
 mod utilities;
 
-use actix_web::{http::Method, server, App, HttpRequest, HttpResponse, Json, Responder};
+use actix_web::{http::{header, Method}, server, App, HttpRequest, HttpResponse, Json, Responder};
 use context::PrismaContext;
 use error::PrismaError;
 use req_handlers::{GraphQlBody, GraphQlRequestHandler, PrismaRequest, RequestHandler};
@@ -41,9 +42,10 @@ fn main() {
 
     let context = PrismaContext::new().unwrap();
     let port = context.config.port;
+    let graphql_request_handler = GraphQlRequestHandler::new(&context.config).unwrap();
     let request_context = Arc::new(RequestContext {
         context: context,
-        graphql_request_handler: GraphQlRequestHandler,
+        graphql_request_handler,
     });
 
     let sys = actix::System::new("prisma");
@@ -53,7 +55,7 @@ fn main() {
         App::with_state(Arc::clone(&request_context))
             .resource("/", |r| {
                 r.method(Method::POST).with(http_handler);
-                r.method(Method::GET).with(playground_handler);
+                r.method(Method::GET).f(get_handler);
             })
             .resource("/datamodel", |r| r.method(Method::GET).with(data_model_handler))
     })
@@ -84,11 +86,27 @@ fn http_handler((json, req): (Json<Option<GraphQlBody>>, HttpRequest<Arc<Request
     serde_json::to_string(&result)
 }
 
+/// WebSocket upgrades are subscriptions, everything else gets the playground
+fn get_handler(req: &HttpRequest<Arc<RequestContext>>) -> actix_web::Result<HttpResponse> {
+    let is_upgrade = req
+        .headers()
+        .get(header::UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.eq_ignore_ascii_case("websocket"))
+        .unwrap_or(false);
+
+    if is_upgrade {
+        subscriptions::start(req)
+    } else {
+        Ok(playground_handler(req))
+    }
+}
+
 fn data_model_handler<T>(_: HttpRequest<T>) -> impl Responder {
     data_model::load_sdl_string().unwrap()
 }
 
-fn playground_handler<T>(_: HttpRequest<T>) -> impl Responder {
+fn playground_handler<T>(_: &HttpRequest<T>) -> HttpResponse {
     let index_html = StaticFiles::get("playground.html").unwrap();
     HttpResponse::Ok().content_type("text/html").body(index_html)
 }
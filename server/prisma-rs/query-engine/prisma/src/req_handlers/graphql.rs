@@ -1,12 +1,13 @@
-use super::{PrismaRequest, RequestHandler};
+use super::{PersistedQuery, PersistedQueryStore, PrismaRequest, RequestHandler};
 use crate::{context::PrismaContext, data_model::Validatable, error::PrismaError, PrismaResult};
+use connector::deadline;
 use core::{
     ir::{self, Builder},
-    RootBuilder,
+    CoreResult, Query, RootBuilder,
 };
-use graphql_parser as gql;
+use prisma_common::config::PrismaConfig;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use serde_json::{Map, Value};
 
@@ -14,15 +15,37 @@ use crate::serializer::json;
 
 type JsonMap = Map<String, Value>;
 
+const TIMEOUT_HEADER: &str = "x-prisma-timeout";
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GraphQlBody {
-    query: String,
+    /// Can be left out if the query was persisted before
+    query: Option<String>,
     operation_name: Option<String>,
+
+    #[serde(default)]
     variables: HashMap<String, String>,
+    extensions: Option<GraphQlExtensions>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQlExtensions {
+    persisted_query: Option<PersistedQuery>,
 }
 
-pub struct GraphQlRequestHandler;
+pub struct GraphQlRequestHandler {
+    persisted_queries: PersistedQueryStore,
+}
+
+impl GraphQlRequestHandler {
+    pub fn new(config: &PrismaConfig) -> PrismaResult<Self> {
+        Ok(Self {
+            persisted_queries: PersistedQueryStore::new(config)?,
+        })
+    }
+}
 
 #[allow(unused_variables)]
 impl RequestHandler for GraphQlRequestHandler {
@@ -30,8 +53,11 @@ impl RequestHandler for GraphQlRequestHandler {
 
     fn handle<S: Into<PrismaRequest<Self::Body>>>(&self, req: S, ctx: &PrismaContext) -> Value {
         // Handle incoming request and deal with errors properly
-        match handle_safely(req.into(), ctx) {
+        match self.handle_safely(req.into(), ctx) {
             Ok(val) => val,
+            Err(err @ PrismaError::PersistedQueryNotFound) | Err(err @ PrismaError::PersistedQueryNotSupported) => {
+                persisted_query_error(err)
+            }
             Err(err) => {
                 let mut map = Map::new();
                 map.insert("reason".into(), format!("{}", err).into());
@@ -41,31 +67,63 @@ impl RequestHandler for GraphQlRequestHandler {
     }
 }
 
-fn handle_safely(req: PrismaRequest<GraphQlBody>, ctx: &PrismaContext) -> PrismaResult<Value> {
-    debug!("Incoming GQL query: {:?}", &req.body.query);
+impl GraphQlRequestHandler {
+    fn handle_safely(&self, req: PrismaRequest<GraphQlBody>, ctx: &PrismaContext) -> PrismaResult<Value> {
+        debug!("Incoming GQL query: {:?}", &req.body.query);
 
-    let query_doc = match gql::parse_query(&req.body.query) {
-        Ok(doc) => doc,
-        Err(e) => return Err(PrismaError::QueryParsingError(format!("{:?}", e))),
-    };
+        let persisted_query = req.body.extensions.as_ref().and_then(|e| e.persisted_query.as_ref());
+        let cached = self
+            .persisted_queries
+            .resolve(req.body.query.as_ref().map(String::as_str), persisted_query)?;
+
+        // Let's validate the schema!
+        if let Err(_) = ctx.internal_data_model.validate(&cached.document) {
+            return Err(PrismaError::QueryValidationError(
+                "InternalDataModel validation failed for unknown reasons".into(),
+            ));
+        }
+
+        let queries = match cached.queries(&req.body.operation_name) {
+            Some(queries) => Ok(queries),
+            None => {
+                let rb = RootBuilder {
+                    query: cached.document.clone(),
+                    internal_data_model: ctx.internal_data_model.clone(),
+                    operation_name: req.body.operation_name.clone(),
+                };
 
-    // Let's validate the schema!
-    if let Err(_) = ctx.internal_data_model.validate(&query_doc) {
-        return Err(PrismaError::QueryValidationError(
-            "InternalDataModel validation failed for unknown reasons".into(),
-        ));
+                rb.build().map(|queries| {
+                    cached.set_queries(req.body.operation_name.clone(), queries.clone());
+                    queries
+                })
+            }
+        };
+
+        let timeout = request_timeout(&req, ctx)?;
+        Ok(deadline::with_timeout(timeout, || execute(queries, ctx)))
     }
+}
 
-    let rb = RootBuilder {
-        query: query_doc,
-        internal_data_model: ctx.internal_data_model.clone(),
-        operation_name: req.body.operation_name,
+/// The configured timeout, which the timeout header can lower but not raise
+fn request_timeout(req: &PrismaRequest<GraphQlBody>, ctx: &PrismaContext) -> PrismaResult<Option<Duration>> {
+    let header = match req.headers.get(TIMEOUT_HEADER) {
+        Some(value) => Some(value.parse::<u64>().map_err(|_| {
+            PrismaError::QueryValidationError(format!("`{}` must be a number of milliseconds", TIMEOUT_HEADER))
+        })?),
+        None => None,
     };
 
-    let queries = rb.build();
+    let timeout = match (header, ctx.config.request_timeout) {
+        (Some(header), Some(config)) => Some(header.min(config)),
+        (header, config) => header.or(config),
+    };
 
+    Ok(timeout.map(Duration::from_millis))
+}
+
+fn execute(queries: CoreResult<Vec<Query>>, ctx: &PrismaContext) -> Value {
     let ir = match queries {
-        Ok(q) => match dbg!(ctx.executor.exec_all(q)) {
+        Ok(q) => match ctx.executor.exec_all(q) {
             Ok(results) => results
                 .into_iter()
                 .fold(Builder::new(), |builder, result| builder.add(result))
@@ -75,7 +133,26 @@ fn handle_safely(req: PrismaRequest<GraphQlBody>, ctx: &PrismaContext) -> Prisma
         Err(err) => vec![ir::Response::Error(format!("{:?}", err))], // This is merely a workaround
     };
 
-    Ok(json::serialize(ir))
+    json::serialize(ir)
+}
+
+/// APQ errors use the GraphQL error format, as clients only retry with the full query on those
+fn persisted_query_error(err: PrismaError) -> Value {
+    let code = match err {
+        PrismaError::PersistedQueryNotFound => "PERSISTED_QUERY_NOT_FOUND",
+        _ => "PERSISTED_QUERY_NOT_SUPPORTED",
+    };
+
+    let mut extensions = Map::new();
+    extensions.insert("code".into(), code.into());
+
+    let mut error = Map::new();
+    error.insert("message".into(), format!("{}", err).into());
+    error.insert("extensions".into(), Value::Object(extensions));
+
+    let mut envelope = JsonMap::new();
+    envelope.insert("errors".into(), Value::Array(vec![Value::Object(error)]));
+    Value::Object(envelope)
 }
 
 /// Create a json envelope
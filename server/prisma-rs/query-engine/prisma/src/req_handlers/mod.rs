@@ -1,6 +1,9 @@
 mod graphql;
+mod persisted_queries;
+
 use crate::context::PrismaContext;
 pub use graphql::{GraphQlBody, GraphQlRequestHandler};
+pub use persisted_queries::{PersistedQuery, PersistedQueryStore};
 
 use crate::RequestContext;
 use actix_web::HttpRequest;
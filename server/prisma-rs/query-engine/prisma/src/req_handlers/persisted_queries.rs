@@ -0,0 +1,278 @@
+//! Automatic persisted queries (APQ) following the Apollo protocol.
+//!
+//! Every document is cached by the SHA-256 hash of its query string, which
+//! lets clients send only the hash and skips parsing and building hot queries.
+
+use crate::{PrismaError, PrismaResult};
+use core::Query;
+use graphql_parser::{self as gql, query::Document};
+use prisma_common::config::PrismaConfig;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    sync::{Arc, Mutex},
+};
+
+const DEFAULT_CACHE_SIZE: usize = 1000;
+const PROTOCOL_VERSION: u32 = 1;
+
+/// `extensions.persistedQuery` of a GraphQL request
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedQuery {
+    pub version: u32,
+    pub sha256_hash: String,
+}
+
+/// A parsed document and the queries built from its operations
+pub struct CachedQuery {
+    pub document: Document,
+    queries: Mutex<HashMap<Option<String>, Vec<Query>>>,
+}
+
+impl CachedQuery {
+    fn new(document: Document) -> Self {
+        Self {
+            document,
+            queries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn queries(&self, operation_name: &Option<String>) -> Option<Vec<Query>> {
+        self.queries.lock().unwrap().get(operation_name).cloned()
+    }
+
+    pub fn set_queries(&self, operation_name: Option<String>, queries: Vec<Query>) {
+        self.queries.lock().unwrap().insert(operation_name, queries);
+    }
+}
+
+pub struct PersistedQueryStore {
+    cache: Mutex<LruCache>,
+
+    /// If set, only queries with these hashes are executed
+    allowlist: Option<HashSet<String>>,
+}
+
+impl PersistedQueryStore {
+    pub fn new(config: &PrismaConfig) -> PrismaResult<Self> {
+        let allowlist = match config.persisted_query_allowlist {
+            Some(ref path) => Some(Self::load_allowlist(path)?),
+            None => None,
+        };
+
+        Ok(Self {
+            cache: Mutex::new(LruCache::new(
+                config.persisted_query_cache_size.unwrap_or(DEFAULT_CACHE_SIZE),
+            )),
+            allowlist,
+        })
+    }
+
+    /// Finds the document of a request in the cache, or parses and registers it on a miss
+    pub fn resolve(&self, query: Option<&str>, persisted: Option<&PersistedQuery>) -> PrismaResult<Arc<CachedQuery>> {
+        let hash = match (persisted, query) {
+            (Some(pq), _) if pq.version != PROTOCOL_VERSION => return Err(PrismaError::PersistedQueryNotSupported),
+            (Some(pq), Some(query)) => {
+                let hash = sha256(query);
+
+                if hash != pq.sha256_hash.to_lowercase() {
+                    return Err(PrismaError::QueryValidationError(
+                        "Provided sha256Hash does not match the query".into(),
+                    ));
+                }
+
+                hash
+            }
+            (Some(pq), None) => pq.sha256_hash.to_lowercase(),
+            (None, Some(query)) => sha256(query),
+            (None, None) => return Err(PrismaError::QueryValidationError("Missing query".into())),
+        };
+
+        if let Some(ref allowlist) = self.allowlist {
+            if !allowlist.contains(&hash) {
+                return Err(PrismaError::QueryValidationError(format!(
+                    "Query with hash `{}` is not allowed",
+                    hash
+                )));
+            }
+        }
+
+        if let Some(cached) = self.cache.lock().unwrap().get(&hash) {
+            return Ok(cached);
+        }
+
+        let query = query.ok_or(PrismaError::PersistedQueryNotFound)?;
+        let document = gql::parse_query(query).map_err(|e| PrismaError::QueryParsingError(format!("{:?}", e)))?;
+        let cached = Arc::new(CachedQuery::new(document));
+
+        self.cache.lock().unwrap().insert(hash, Arc::clone(&cached));
+        Ok(cached)
+    }
+
+    /// Reads one hash per line, ignoring blank lines and `#` comments
+    fn load_allowlist(path: &str) -> PrismaResult<HashSet<String>> {
+        let content = fs::read_to_string(path)?;
+
+        Ok(content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.to_lowercase())
+            .collect())
+    }
+}
+
+fn sha256(query: &str) -> String {
+    format!("{:x}", Sha256::digest(query.as_bytes()))
+}
+
+/// Evicts the least recently used document once full
+struct LruCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<String, (u64, Arc<CachedQuery>)>,
+}
+
+impl LruCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, hash: &str) -> Option<Arc<CachedQuery>> {
+        self.tick += 1;
+        let tick = self.tick;
+
+        self.entries.get_mut(hash).map(|entry| {
+            entry.0 = tick;
+            Arc::clone(&entry.1)
+        })
+    }
+
+    fn insert(&mut self, hash: String, query: Arc<CachedQuery>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if !self.entries.contains_key(&hash) && self.entries.len() >= self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (tick, _))| *tick)
+                .map(|(hash, _)| hash.clone());
+
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.tick += 1;
+        self.entries.insert(hash, (self.tick, query));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUERY: &str = "{ users { id } }";
+
+    fn store(capacity: usize, allowlist: Option<HashSet<String>>) -> PersistedQueryStore {
+        PersistedQueryStore {
+            cache: Mutex::new(LruCache::new(capacity)),
+            allowlist,
+        }
+    }
+
+    fn persisted(query: &str) -> PersistedQuery {
+        PersistedQuery {
+            version: PROTOCOL_VERSION,
+            sha256_hash: sha256(query),
+        }
+    }
+
+    #[test]
+    fn documents_are_parsed_once() {
+        let store = store(10, None);
+
+        let first = store.resolve(Some(QUERY), None).unwrap();
+        let second = store.resolve(Some(QUERY), None).unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn hashes_resolve_after_the_query_was_registered() {
+        let store = store(10, None);
+        let persisted = persisted(QUERY);
+
+        match store.resolve(None, Some(&persisted)) {
+            Err(PrismaError::PersistedQueryNotFound) => (),
+            _ => panic!("Expected an unknown hash to be reported as not found"),
+        }
+
+        let registered = store.resolve(Some(QUERY), Some(&persisted)).unwrap();
+        let resolved = store.resolve(None, Some(&persisted)).unwrap();
+
+        assert!(Arc::ptr_eq(&registered, &resolved));
+    }
+
+    #[test]
+    fn hashes_have_to_match_the_query() {
+        let store = store(10, None);
+
+        match store.resolve(Some("{ posts { id } }"), Some(&persisted(QUERY))) {
+            Err(PrismaError::QueryValidationError(_)) => (),
+            _ => panic!("Expected a mismatching hash to be rejected"),
+        }
+    }
+
+    #[test]
+    fn other_protocol_versions_are_not_supported() {
+        let store = store(10, None);
+        let persisted = PersistedQuery {
+            version: 2,
+            ..persisted(QUERY)
+        };
+
+        match store.resolve(Some(QUERY), Some(&persisted)) {
+            Err(PrismaError::PersistedQueryNotSupported) => (),
+            _ => panic!("Expected version 2 to be rejected"),
+        }
+    }
+
+    #[test]
+    fn the_allowlist_rejects_other_queries() {
+        let allowlist = vec![sha256(QUERY)].into_iter().collect();
+        let store = store(10, Some(allowlist));
+
+        assert!(store.resolve(Some(QUERY), None).is_ok());
+
+        match store.resolve(Some("{ posts { id } }"), None) {
+            Err(PrismaError::QueryValidationError(_)) => (),
+            _ => panic!("Expected a query that is not on the allowlist to be rejected"),
+        }
+    }
+
+    #[test]
+    fn the_least_recently_used_document_is_evicted() {
+        let store = store(2, None);
+        let queries = ["{ a { id } }", "{ b { id } }", "{ c { id } }"];
+
+        let a = store.resolve(Some(queries[0]), None).unwrap();
+        let b = store.resolve(Some(queries[1]), None).unwrap();
+
+        // Touching `a` leaves `b` as the least recently used one
+        store.resolve(Some(queries[0]), None).unwrap();
+        store.resolve(Some(queries[2]), None).unwrap();
+
+        assert!(Arc::ptr_eq(&a, &store.resolve(Some(queries[0]), None).unwrap()));
+        assert!(!Arc::ptr_eq(&b, &store.resolve(Some(queries[1]), None).unwrap()));
+    }
+}
@@ -85,10 +85,11 @@ fn serialize_prisma_value(value: PrismaValue) -> PrismaResult<Value> {
         PrismaValue::DateTime(date) => Value::String(format!("{}", date.format("%Y-%m-%dT%H:%M:%S%.3fZ"))),
         PrismaValue::Enum(x) => Value::String(x.clone()),
         PrismaValue::Json(x) => x,
-        PrismaValue::Int(x) => Value::Number(match Number::from_f64(x as f64) {
-            Some(num) => num,
-            None => return Err(PrismaError::SerializationError("`f64` number was invalid".into())),
-        }),
+        PrismaValue::Int(x) => Value::Number(Number::from(x)),
+        // Strings, as JSON numbers are doubles to most clients
+        PrismaValue::BigInt(x) => Value::String(x.to_string()),
+        PrismaValue::Decimal(x) => Value::String(x.to_string()),
+        PrismaValue::Bytes(x) => Value::String(base64::encode(&x)),
         PrismaValue::Relation(_) => unreachable!(),
         PrismaValue::Null => Value::Null,
         PrismaValue::Uuid(x) => Value::String(x.to_hyphenated().to_string()),
@@ -100,10 +101,7 @@ fn serialize_prisma_value(value: PrismaValue) -> PrismaResult<Value> {
 fn serialize_graphql_id(id: &GraphqlId) -> PrismaResult<Value> {
     Ok(match id {
         GraphqlId::String(x) => Value::String(x.clone()),
-        GraphqlId::Int(x) => Value::Number(match serde_json::Number::from_f64(*x as f64) {
-            Some(num) => num,
-            None => return Err(PrismaError::SerializationError("`f64` number was invalid".into())),
-        }),
+        GraphqlId::Int(x) => Value::Number(Number::from(*x as u64)),
         GraphqlId::UUID(x) => Value::String(x.to_hyphenated().to_string()),
     })
 }
@@ -0,0 +1,241 @@
+//! GraphQL subscriptions over WebSockets, speaking the `graphql-ws` protocol
+//! (as implemented by `subscriptions-transport-ws` and the playground).
+
+use crate::{serializer::json, PrismaError, PrismaResult, RequestContext};
+use actix::prelude::*;
+use actix_web::{ws, HttpRequest, HttpResponse};
+use core::{MutationEvent, RootBuilder, SubscriberId, Subscription};
+use graphql_parser as gql;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+const PROTOCOL: &str = "graphql-ws";
+
+/// Upgrades the request to a WebSocket that serves subscriptions
+pub fn start(req: &HttpRequest<Arc<RequestContext>>) -> actix_web::Result<HttpResponse> {
+    let mut resp = ws::handshake(req)?;
+    let stream = ws::WsStream::new(req.payload());
+    let body = ws::WebsocketContext::create(req.clone(), SubscriptionSession::default(), stream);
+
+    Ok(resp.header("Sec-WebSocket-Protocol", PROTOCOL).body(body))
+}
+
+/// A `graphql-ws` protocol message
+#[derive(Debug, Serialize, Deserialize)]
+struct OperationMessage {
+    #[serde(rename = "type")]
+    typ: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payload: Option<Value>,
+}
+
+impl OperationMessage {
+    fn new(typ: &str, id: Option<String>, payload: Option<Value>) -> Self {
+        Self {
+            typ: typ.into(),
+            id,
+            payload,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartPayload {
+    query: String,
+    operation_name: Option<String>,
+}
+
+/// The subscriptions started by one `start` message
+struct Operation {
+    subscriptions: Vec<Subscription>,
+    subscribers: Vec<SubscriberId>,
+}
+
+/// Delivers a mutation event to the session that subscribed to it
+struct EventMessage {
+    operation_id: String,
+    subscription: usize,
+    event: Arc<MutationEvent>,
+}
+
+impl Message for EventMessage {
+    type Result = ();
+}
+
+/// One WebSocket connection, holding all of its running operations
+#[derive(Default)]
+struct SubscriptionSession {
+    operations: HashMap<String, Operation>,
+}
+
+impl Actor for SubscriptionSession {
+    type Context = ws::WebsocketContext<Self, Arc<RequestContext>>;
+
+    fn stopped(&mut self, ctx: &mut Self::Context) {
+        let ids: Vec<String> = self.operations.keys().cloned().collect();
+        ids.iter().for_each(|id| self.stop_operation(id, ctx));
+    }
+}
+
+impl StreamHandler<ws::Message, ws::ProtocolError> for SubscriptionSession {
+    fn handle(&mut self, msg: ws::Message, ctx: &mut Self::Context) {
+        match msg {
+            ws::Message::Ping(msg) => ctx.pong(&msg),
+            ws::Message::Text(text) => match serde_json::from_str::<OperationMessage>(&text) {
+                Ok(msg) => self.handle_operation_message(msg, ctx),
+                Err(err) => Self::send(
+                    ctx,
+                    OperationMessage::new("error", None, Some(error_payload(err.into()))),
+                ),
+            },
+            ws::Message::Close(_) => ctx.stop(),
+            _ => (),
+        }
+    }
+}
+
+impl Handler<EventMessage> for SubscriptionSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: EventMessage, ctx: &mut Self::Context) {
+        let subscription = match self.operations.get(&msg.operation_id) {
+            Some(op) => &op.subscriptions[msg.subscription],
+            None => return,
+        };
+
+        let state = Arc::clone(ctx.state());
+        let id = Some(msg.operation_id.clone());
+
+        match state.context.executor.exec_subscription(subscription, &msg.event) {
+            Ok(Some(response)) => Self::send(
+                ctx,
+                OperationMessage::new("data", id, Some(json::serialize(vec![response]))),
+            ),
+            Ok(None) => (),
+            Err(err) => Self::send(ctx, OperationMessage::new("error", id, Some(error_payload(err.into())))),
+        }
+    }
+}
+
+impl SubscriptionSession {
+    fn handle_operation_message(
+        &mut self,
+        msg: OperationMessage,
+        ctx: &mut ws::WebsocketContext<Self, Arc<RequestContext>>,
+    ) {
+        match (msg.typ.as_str(), msg.id) {
+            ("connection_init", _) => Self::send(ctx, OperationMessage::new("connection_ack", None, None)),
+            ("start", Some(id)) => {
+                if let Err(err) = self.start_operation(id.clone(), msg.payload, ctx) {
+                    Self::send(ctx, OperationMessage::new("error", Some(id), Some(error_payload(err))));
+                }
+            }
+            ("stop", Some(id)) => {
+                self.stop_operation(&id, ctx);
+                Self::send(ctx, OperationMessage::new("complete", Some(id), None));
+            }
+            ("connection_terminate", _) => ctx.stop(),
+            (typ, id) => Self::send(
+                ctx,
+                OperationMessage::new(
+                    "error",
+                    id,
+                    Some(error_payload(PrismaError::QueryValidationError(format!(
+                        "Unknown message type `{}`",
+                        typ
+                    )))),
+                ),
+            ),
+        }
+    }
+
+    fn start_operation(
+        &mut self,
+        id: String,
+        payload: Option<Value>,
+        ctx: &mut ws::WebsocketContext<Self, Arc<RequestContext>>,
+    ) -> PrismaResult<()> {
+        let payload: StartPayload = match payload {
+            Some(payload) => serde_json::from_value(payload)?,
+            None => return Err(PrismaError::QueryValidationError("Missing payload for `start`".into())),
+        };
+
+        let query_doc = match gql::parse_query(&payload.query) {
+            Ok(doc) => doc,
+            Err(e) => return Err(PrismaError::QueryParsingError(format!("{:?}", e))),
+        };
+
+        let state = Arc::clone(ctx.state());
+        let rb = RootBuilder {
+            query: query_doc,
+            internal_data_model: state.context.internal_data_model.clone(),
+            operation_name: payload.operation_name,
+        };
+
+        let subscriptions = rb.build_subscriptions()?;
+        state.context.executor.check_subscriptions(&subscriptions)?;
+
+        // Restarting an operation id replaces the previous operation
+        self.stop_operation(&id, ctx);
+
+        let dispatcher = &state.context.executor.write_exec.dispatcher;
+        let subscribers = subscriptions
+            .iter()
+            .enumerate()
+            .map(|(index, subscription)| {
+                let addr = Mutex::new(ctx.address());
+                let operation_id = id.clone();
+
+                dispatcher.subscribe(
+                    subscription.model.name.clone(),
+                    Box::new(move |event| {
+                        addr.lock().unwrap().do_send(EventMessage {
+                            operation_id: operation_id.clone(),
+                            subscription: index,
+                            event,
+                        })
+                    }),
+                )
+            })
+            .collect();
+
+        self.operations.insert(
+            id,
+            Operation {
+                subscriptions,
+                subscribers,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn stop_operation(&mut self, id: &str, ctx: &mut ws::WebsocketContext<Self, Arc<RequestContext>>) {
+        if let Some(op) = self.operations.remove(id) {
+            let dispatcher = &ctx.state().context.executor.write_exec.dispatcher;
+            op.subscribers.into_iter().for_each(|s| dispatcher.unsubscribe(s));
+        }
+    }
+
+    fn send(ctx: &mut ws::WebsocketContext<Self, Arc<RequestContext>>, msg: OperationMessage) {
+        match serde_json::to_string(&msg) {
+            Ok(text) => ctx.text(text),
+            Err(err) => error!("Unable to serialize subscription message: {}", err),
+        }
+    }
+}
+
+fn error_payload(err: PrismaError) -> Value {
+    let mut map = serde_json::Map::new();
+    map.insert("message".into(), format!("{}", err).into());
+    Value::Object(map)
+}